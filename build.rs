@@ -9,10 +9,12 @@ extern crate handlebars;
 extern crate rustc_serialize;
 
 mod gensh;
+mod gentrace;
 
 fn main() {
     gensh::generate_spatial_hash("sh.toml", Path::new("src").join("spatial_hash").join("generated.rs"));
     genecs::generate_ecs("ecs.toml", Path::new("src").join("ecs").join("generated.rs"));
+    gentrace::generate_trace("ecs.toml", Path::new("src").join("ecs").join("generated_trace.rs"));
 
     copy_resources("resources");
     copy_resources("user");
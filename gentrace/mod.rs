@@ -0,0 +1,64 @@
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::fs::File;
+
+use tomson::Toml;
+use handlebars::Handlebars;
+
+fn generate_code(mut toml: String) -> String {
+    // turn the toml string into json for compatibility with handlebars
+    let json = Toml::as_json(&mut toml).unwrap();
+
+    let mut handlebars = Handlebars::new();
+
+    // prevent xml escaping
+    handlebars.register_escape_fn(|input| input.to_string());
+    handlebars.template_render(TEMPLATE, &json).unwrap()
+}
+
+fn read_file_to_string<P: AsRef<Path>>(path: P) -> String {
+    let mut file = File::open(path).unwrap();
+    let mut string = String::new();
+    file.read_to_string(&mut string).unwrap();
+
+    string
+}
+
+pub fn generate_trace<P: AsRef<Path>, Q: AsRef<Path>>(in_path: P, out_path: Q) {
+    let string = read_file_to_string(in_path);
+    let output_string = generate_code(string);
+
+    let mut outfile = File::create(out_path).unwrap();
+    write!(outfile, "{}", output_string).unwrap();
+}
+
+const TEMPLATE: &'static str = r#"// Automatically generated. Do not edit.
+#![allow(unused_imports)]
+use ecs::*;
+
+/// Writes every component inserted or removed by an action to the debug
+/// writer. Values are only written for copy components.
+pub fn trace_components(ecs: &EcsCtx, action: &EcsAction) {
+{{#each component}}
+    {{#if copy}}
+    for (entity_id, value) in action.{{ @key }}_positive_iter(ecs) {
+        debug_println!("    {:?} insert {{ @key }} {:?}", entity_id, value);
+    }
+    {{else}}
+    {{#if type}}
+    for (entity_id, _) in action.{{ @key }}_positive_iter(ecs) {
+        debug_println!("    {:?} insert {{ @key }}", entity_id);
+    }
+    {{else}}
+    for entity_id in action.{{ @key }}_positive_iter(ecs) {
+        debug_println!("    {:?} insert {{ @key }}", entity_id);
+    }
+    {{/if}}
+    {{/if}}
+    for entity_id in action.{{ @key }}_negative_iter(ecs) {
+        debug_println!("    {:?} remove {{ @key }}", entity_id);
+    }
+{{/each}}
+}
+"#;
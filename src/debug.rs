@@ -32,6 +32,18 @@ impl io::Write for PrintDebug {
     }
 }
 
+pub struct ErrDebug;
+
+impl io::Write for ErrDebug {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        eprint!("{}", str::from_utf8(buf).unwrap());
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 pub fn write(buf: &[u8]) -> io::Result<usize> {
     unsafe {
         TARGET.map_or_else(|| Err(io::Error::new(io::ErrorKind::NotFound, "Debug not initialised")),
@@ -40,10 +52,10 @@ pub fn write(buf: &[u8]) -> io::Result<usize> {
 }
 
 macro_rules! debug_print {
-    ($($arg:tt)*) => {
+    ($($arg:tt)*) => {{
         use debug;
-        debug::write(format!($($arg)*).as_bytes()).unwrap()
-    };
+        debug::write(format!($($arg)*).as_bytes()).unwrap();
+    }};
 }
 
 macro_rules! debug_println {
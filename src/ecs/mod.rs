@@ -1,3 +1,5 @@
 mod generated;
+mod generated_trace;
 
 pub use self::generated::*;
+pub use self::generated_trace::*;
@@ -1,10 +1,11 @@
 use std::f64;
+use std::cmp;
 use rand::Rng;
 use ecs::*;
 use game::*;
 use game::data::*;
 use direction::Direction;
-use coord::Coord;
+use coord::{Coord, InfiniteLineState};
 use math::Vector2;
 
 pub fn walk(action: &mut EcsAction, entity: EntityRef, direction: Direction) {
@@ -34,6 +35,17 @@ pub fn realtime_velocity_move(action: &mut EcsAction, entity: EntityRef, velocit
         action.insert_realtime_velocity(entity.id(), new_velocity);
         action.insert_position(entity.id(), current_position + offset);
 
+        // vehicles burn more fuel per cell the faster they go
+        if let (Some(mut fuel), Some(speed)) = (entity.fuel(), entity.current_speed()) {
+            if fuel.is_positive() {
+                fuel.dec(speed);
+                action.insert_fuel(entity.id(), fuel);
+                if !fuel.is_positive() {
+                    action.set_action_description(ActionDescription::new(current_position, ActionMessageType::OutOfFuel));
+                }
+            }
+        }
+
         if let Some(remaining) = entity.realtime_moves_remaining() {
             if remaining > 0 {
                 action.insert_realtime_moves_remaining(entity.id(), remaining - 1);
@@ -116,16 +128,26 @@ pub fn physics(action: &mut EcsAction) {
     action.set_physics();
 }
 
-pub fn steer<R: Rng>(action: &mut EcsAction, entity: EntityRef, direction: SteerDirection, rng: &mut R) {
-    if entity.contains_pc() {
-        if entity.steer_check(rng).expect("Expected components for steer check") {
-            action.insert_steering(entity.id(), direction);
-        } else {
-            let position = entity.position().expect("Entity missing position");
-            action.set_action_description(ActionDescription::new(position, ActionMessageType::FailToTurn));
-        }
+pub fn strand(action: &mut EcsAction) {
+    action.set_player_stranded();
+}
+
+/// Burns a burning entity down by a turn, removing it once it burns out
+pub fn burn(action: &mut EcsAction, entity: EntityRef) {
+    let turns = entity.burning().expect("Entity missing burning");
+    if turns > 1 {
+        action.insert_burning(entity.id(), turns - 1);
     } else {
+        destroy(action, entity);
+    }
+}
+
+pub fn steer<R: Rng>(action: &mut EcsAction, entity: EntityRef, direction: SteerDirection, weather: Weather, rng: &mut R) {
+    if entity.steer_check(weather.grip(), rng) {
         action.insert_steering(entity.id(), direction);
+    } else if entity.contains_pc() {
+        let position = entity.position().expect("Entity missing position");
+        action.set_action_description(ActionDescription::new(position, ActionMessageType::FailToTurn));
     }
 }
 
@@ -133,14 +155,24 @@ pub fn remove_steer(action: &mut EcsAction, entity_id: EntityId) {
     action.remove_steering(entity_id);
 }
 
-pub fn change_speed(action: &mut EcsAction, entity: EntityRef, change: ChangeSpeed) {
+pub fn change_speed<R: Rng>(action: &mut EcsAction, entity: EntityRef, change: ChangeSpeed, rng: &mut R) {
     let current_speed = entity.current_speed().expect("Entity missing current_speed");
     let max_speed = entity.general_max_speed().expect("Entity missing max_speed");
 
     let new_speed = match change {
         ChangeSpeed::Accelerate => {
-            if current_speed < max_speed {
-                current_speed + 1
+            if entity.out_of_fuel() {
+                let position = entity.position().expect("Entity missing position");
+                action.set_action_description(ActionDescription::new(position, ActionMessageType::OutOfFuel));
+                current_speed
+            } else if current_speed < max_speed {
+                if entity.accelerate_check(rng) {
+                    current_speed + 1
+                } else {
+                    let position = entity.position().expect("Entity missing position");
+                    action.set_action_description(ActionDescription::new(position, ActionMessageType::FailToAccelerateLoaded));
+                    current_speed
+                }
             } else {
                 let position = entity.position().expect("Entity missing position");
                 action.set_action_description(ActionDescription::new(position, ActionMessageType::FailToAccelerate));
@@ -167,15 +199,15 @@ pub fn become_bloodstain(action: &mut EcsAction, entity: EntityRef, ids: &Entity
     prototypes::bloodstain(action.entity_mut(ids.new_id()), position);
 }
 
-pub fn fire_burst<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: EntityRef, direction: Direction, remaining: usize, speed: f64, period: u64, spread: usize, range: usize, bullet_type: BulletType, ids: &EntityIdReserver, r: &mut R) {
+pub fn fire_burst<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: EntityRef, vector: Coord, remaining: usize, speed: f64, period: u64, spread: usize, range: usize, bullet_type: BulletType, ids: &EntityIdReserver, r: &mut R) {
 
     let shooter_position = shooter.position().expect("Missing component position");
 
-    let ideal_vector = direction.vector() * range as isize;
+    let ideal_vector = scale_to_range(vector, range);
     let x_spread = (r.gen::<usize>() % (spread * 2 + 1)) as isize - spread as isize;
     let y_spread = (r.gen::<usize>() % (spread * 2 + 1)) as isize - spread as isize;
-    let vector = ideal_vector + Coord::new(x_spread, y_spread);
-    let mut velocity = RealtimeVelocity::new(vector, speed);
+    let shot_vector = ideal_vector + Coord::new(x_spread, y_spread);
+    let mut velocity = RealtimeVelocity::new(shot_vector, speed);
 
     let bullet_position = shooter_position + velocity.step_in_place();
 
@@ -184,13 +216,12 @@ pub fn fire_burst<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: Entit
 
     match bullet_type {
         BulletType::RailgunSlug => {
-            if direction == Direction::East || direction == Direction::West {
+            if shot_vector.y == 0 {
                 action.insert_tile(bullet_id, TileType::RailgunSlugHorizontal);
-            } else if direction == Direction::North || direction == Direction::South {
+            } else if shot_vector.x == 0 {
                 action.insert_tile(bullet_id, TileType::RailgunSlugVertical);
-            } else {
-                panic!("Invalid direction");
             }
+            // slugs fired at an angle keep the bullet tile
         }
         _ => {}
     }
@@ -201,7 +232,7 @@ pub fn fire_burst<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: Entit
         action.set_then(Reaction::new(ActionArgs::FireBurst {
             gun_id: gun.id(),
             shooter_id: shooter.id(),
-            direction: direction,
+            vector: vector,
             remaining: next_remaining,
             speed: speed,
             period: period,
@@ -213,14 +244,43 @@ pub fn fire_burst<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: Entit
     action.set_action_time_ms(period);
 }
 
-pub fn fire_gun<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: EntityRef, direction: Direction, ids: &EntityIdReserver, r: &mut R) {
+/// Shotgun pellets stray up to this many cells either side of the aim at
+/// the end of their range
+pub const SHOTGUN_SPREAD: usize = 2;
+pub const FLAMETHROWER_SPREAD: usize = 1;
+
+/// Distance travelled by the fragments of an explosion
+pub const EXPLOSION_RANGE: usize = 6;
+
+/// Scales a vector so it reaches `range` cells along its major axis
+pub fn scale_to_range(vector: Coord, range: usize) -> Coord {
+    let major = cmp::max(vector.x.abs(), vector.y.abs());
+    if major == 0 {
+        return vector;
+    }
+    Coord::new(vector.x * range as isize / major, vector.y * range as isize / major)
+}
+
+pub fn fire_gun<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: EntityRef, vector: Coord, ids: &EntityIdReserver, r: &mut R) {
     let gun_type = gun.gun_type().expect("Missing component gun_type");
     let range = gun.gun_range().expect("Missing component gun_range");
     let shooter_position = shooter.position().expect("Missing component position");
+
+    // only the player has to keep their guns loaded
+    if shooter.contains_pc() {
+        if let Some(ammo) = gun.ammo() {
+            if ammo == 0 {
+                action.set_action_description(ActionDescription::new(shooter_position, ActionMessageType::GunEmpty));
+                return;
+            }
+            action.insert_ammo(gun.id(), ammo - 1);
+        }
+    }
+
     match gun_type {
         GunType::Pistol => {
             const SPEED_CELLS_PER_SEC: f64 = 100.0;
-            let mut velocity = RealtimeVelocity::new(direction.vector(), SPEED_CELLS_PER_SEC);
+            let mut velocity = RealtimeVelocity::new(vector, SPEED_CELLS_PER_SEC);
             let bullet_position = shooter_position + velocity.step_in_place();
             let id = ids.new_id();
             prototypes::bullet(action.entity_mut(id), bullet_position, velocity, range);
@@ -230,11 +290,10 @@ pub fn fire_gun<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: EntityR
         GunType::Shotgun => {
             const SPEED_CELLS_PER_SEC: f64 = 50.0;
             const NUM_SHOTS: usize = 6;
-            const SPREAD: usize = 2;
-            let ideal_vector = direction.vector() * range as isize;
+            let ideal_vector = scale_to_range(vector, range);
             for _ in 0..NUM_SHOTS {
-                let x_spread = (r.gen::<usize>() % (SPREAD * 2)) as isize - SPREAD as isize;
-                let y_spread = (r.gen::<usize>() % (SPREAD * 2)) as isize - SPREAD as isize;
+                let x_spread = (r.gen::<usize>() % (SHOTGUN_SPREAD * 2)) as isize - SHOTGUN_SPREAD as isize;
+                let y_spread = (r.gen::<usize>() % (SHOTGUN_SPREAD * 2)) as isize - SHOTGUN_SPREAD as isize;
 
                 let vector = ideal_vector + Coord::new(x_spread, y_spread);
                 let mut velocity = RealtimeVelocity::new(vector, SPEED_CELLS_PER_SEC);
@@ -250,7 +309,7 @@ pub fn fire_gun<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: EntityR
             action.set_then(Reaction::new(ActionArgs::FireBurst {
                 gun_id: gun.id(),
                 shooter_id: shooter.id(),
-                direction: direction,
+                vector: vector,
                 remaining: 6,
                 speed: 100.0,
                 period: 20,
@@ -263,7 +322,7 @@ pub fn fire_gun<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: EntityR
             action.set_then(Reaction::new(ActionArgs::FireBurst {
                 gun_id: gun.id(),
                 shooter_id: shooter.id(),
-                direction: direction,
+                vector: vector,
                 remaining: 20,
                 speed: 200.0,
                 period: 1,
@@ -272,13 +331,64 @@ pub fn fire_gun<R: Rng>(action: &mut EcsAction, gun: EntityRef, shooter: EntityR
                 bullet_type: BulletType::RailgunSlug,
             }, 0));
         }
+        GunType::Flamethrower => {
+            const SPEED_CELLS_PER_SEC: f64 = 15.0;
+            const NUM_FLAMES: usize = 5;
+            let ideal_vector = scale_to_range(vector, range);
+            for _ in 0..NUM_FLAMES {
+                let x_spread = (r.gen::<usize>() % (FLAMETHROWER_SPREAD * 2 + 1)) as isize - FLAMETHROWER_SPREAD as isize;
+                let y_spread = (r.gen::<usize>() % (FLAMETHROWER_SPREAD * 2 + 1)) as isize - FLAMETHROWER_SPREAD as isize;
+
+                let vector = ideal_vector + Coord::new(x_spread, y_spread);
+                let mut velocity = RealtimeVelocity::new(vector, SPEED_CELLS_PER_SEC);
+
+                let flame_position = shooter_position + velocity.step_in_place();
+                prototypes::flame(action.entity_mut(ids.new_id()), flame_position, velocity, range);
+                action.set_action_time_ms(velocity.ms_per_cell());
+            }
+        }
+        GunType::MineDropper => {
+            let offset = InfiniteLineState::new(vector, false).step();
+            prototypes::mine(action.entity_mut(ids.new_id()), shooter_position + offset);
+        }
+        GunType::Harpoon => {
+            const SPEED_CELLS_PER_SEC: f64 = 60.0;
+            let mut velocity = RealtimeVelocity::new(vector, SPEED_CELLS_PER_SEC);
+            let harpoon_position = shooter_position + velocity.step_in_place();
+            let id = ids.new_id();
+            prototypes::harpoon(action.entity_mut(id), harpoon_position, velocity, range);
+            action.insert_shooter_id(id, shooter.id());
+            action.set_action_time_ms(velocity.ms_per_cell());
+        }
+        GunType::RocketLauncher => {
+            const SPEED_CELLS_PER_SEC: f64 = 40.0;
+            let mut velocity = RealtimeVelocity::new(vector, SPEED_CELLS_PER_SEC);
+            let rocket_position = shooter_position + velocity.step_in_place();
+            let id = ids.new_id();
+            prototypes::rocket(action.entity_mut(id), rocket_position, velocity, range);
+            action.insert_shooter_id(id, shooter.id());
+            action.set_action_time_ms(velocity.ms_per_cell());
+        }
     }
 }
 
 pub fn complex_damage<R: Rng>(action: &mut EcsAction, entity: EntityRef, damage: usize, rng: &mut R) {
     let position = entity.position().expect("Entity missing position");
+    let mut cargo_damage = entity.cargo_damage();
     for _ in 0..damage {
-        entity.damage_type(rng).map(|damage_type| match damage_type {
+        let damage_type = if let Some(damage_type) = entity.damage_type(rng) {
+            damage_type
+        } else {
+            continue;
+        };
+
+        // anything which gets past the armour jostles the cargo
+        match damage_type {
+            DamageType::Deflect => {}
+            _ => cargo_damage = cargo_damage.map(|d| d + 1),
+        }
+
+        match damage_type {
             DamageType::Health => {
                 let mut hit_points = entity.hit_points().expect("Entity missing hit_points");
                 hit_points.dec(1);
@@ -288,7 +398,7 @@ pub fn complex_damage<R: Rng>(action: &mut EcsAction, entity: EntityRef, damage:
             DamageType::Engine => {
                 let mut engine = entity.engine_health().expect("Entity missing engine_health");
                 engine.dec(1);
-                let new_max_speed = (engine.ucurrent() + 1) / 2;
+                let new_max_speed = engine_max_speed(engine, entity.cargo_weight());
                 let max_speed = entity.general_max_speed().expect("Entity missing general_max_speed components");
                 let current_speed = entity.current_speed().expect("Entity missing current_speed");
 
@@ -320,7 +430,11 @@ pub fn complex_damage<R: Rng>(action: &mut EcsAction, entity: EntityRef, damage:
             DamageType::Deflect => {
                 action.set_action_description(ActionDescription::new(position, ActionMessageType::ArmourDeflect));
             }
-        });
+        }
+    }
+
+    if let Some(cargo_damage) = cargo_damage {
+        action.insert_cargo_damage(entity.id(), cargo_damage);
     }
 }
 
@@ -338,7 +452,8 @@ pub fn bump(action: &mut EcsAction, victim: EntityRef, attacker: EntityRef) {
 pub fn acid_damage<R: Rng>(action: &mut EcsAction, entity: EntityRef, rng: &mut R) {
     const CHANCE_TO_DAMAGE: f64 = 0.25;
 
-    if rng.next_f64() < CHANCE_TO_DAMAGE {
+    // heavily loaded tyres wear through faster
+    if rng.next_f64() < CHANCE_TO_DAMAGE * (1.0 + entity.load()) {
         let mut tyres = entity.tyre_health().expect("Entity missing tyre_health");
         if tyres.current() > 0 {
             let position = entity.position().expect("Entity missing position");
@@ -362,16 +477,40 @@ pub fn explode(action: &mut EcsAction, entity: EntityRef) {
     }
 }
 
+/// Moves an entity a cell closer to another, continuing until it's been
+/// moved `remaining` cells, it's adjacent to the other entity, or it's
+/// blocked
+pub fn pull(action: &mut EcsAction, entity: EntityRef, towards: EntityRef, remaining: usize) {
+    const STEP_MS: u64 = 20;
+
+    if let (Some(position), Some(target)) = (entity.position(), towards.position()) {
+        let offset = target - position;
+        if offset.x.abs() <= 1 && offset.y.abs() <= 1 {
+            return;
+        }
+
+        action.insert_position(entity.id(), position + Coord::new(offset.x.signum(), offset.y.signum()));
+
+        if remaining > 1 {
+            action.set_then(Reaction::new(ActionArgs::Pull {
+                entity_id: entity.id(),
+                towards_id: towards.id(),
+                remaining: remaining - 1,
+            }, STEP_MS));
+        }
+        action.set_action_time_ms(STEP_MS);
+    }
+}
+
 pub fn explode_spawn(action: &mut EcsAction, coord: Coord, ids: &EntityIdReserver) {
     const SPEED_CELLS_PER_SEC: f64 = 20.0;
-    const RANGE: usize = 6;
     const COUNT: usize = 32;
     const STEP: f64 = 2.0 * f64::consts::PI / COUNT as f64;
     let mut angle = 0.0;
     for _ in 0..COUNT {
         let v = Vector2::from_radial(10.0, angle);
         let velocity = RealtimeVelocity::new(Coord::new(v.x as isize, v.y as isize), SPEED_CELLS_PER_SEC);
-        prototypes::explosion(action.entity_mut(ids.new_id()), coord, velocity, RANGE);
+        prototypes::explosion(action.entity_mut(ids.new_id()), coord, velocity, EXPLOSION_RANGE);
         angle += STEP;
     }
 }
@@ -392,6 +531,70 @@ pub fn repair_engine(action: &mut EcsAction, entity: EntityRef, amount: usize) {
     action.set_action_description(ActionDescription::new(position, ActionMessageType::EngineRepaired));
 }
 
+pub fn refuel(action: &mut EcsAction, entity: EntityRef, amount: usize) {
+    let mut fuel = entity.fuel().expect("Entity missing fuel");
+    fuel.inc(amount);
+    action.insert_fuel(entity.id(), fuel);
+    let position = entity.position().expect("Entity missing position");
+    action.set_action_description(ActionDescription::new(position, ActionMessageType::Refuelled));
+}
+
+/// Fills a gun from the ammo boxes in the entity's inventory which hold the
+/// gun's type of bullet. Reloading takes several turns.
+pub fn reload(action: &mut EcsAction, ecs: &EcsCtx, entity: EntityRef, gun: EntityRef) {
+    const RELOAD_TURNS: u64 = 3;
+
+    let position = entity.position().expect("Entity missing position");
+    let bullet_type = gun.bullet_type().expect("Entity missing bullet_type");
+    let loaded = gun.ammo().expect("Entity missing ammo");
+    let capacity = gun.ammo_capacity().expect("Entity missing ammo_capacity");
+
+    if loaded >= capacity {
+        action.set_action_description(ActionDescription::new(position, ActionMessageType::AlreadyLoaded));
+        return;
+    }
+
+    let mut needed = capacity - loaded;
+    let mut emptied = Vec::new();
+
+    for item_id in entity.inventory_borrow().expect("Entity missing inventory").iter() {
+        if needed == 0 {
+            break;
+        }
+
+        let item = ecs.entity(item_id);
+        if item.contains_gun_type() || item.bullet_type() != Some(bullet_type) {
+            continue;
+        }
+
+        let rounds = item.ammo().expect("Entity missing ammo");
+        let taken = cmp::min(rounds, needed);
+        needed -= taken;
+
+        if taken == rounds {
+            emptied.push(item_id);
+        } else {
+            action.insert_ammo(item_id, rounds - taken);
+        }
+    }
+
+    if needed == capacity - loaded {
+        action.set_action_description(ActionDescription::new(position, ActionMessageType::NoAmmo));
+        return;
+    }
+
+    for item_id in emptied {
+        entity.inventory_borrow_mut().expect("Entity missing inventory").remove(item_id);
+        action.remove_entity(ecs.entity(item_id));
+    }
+
+    action.insert_ammo(gun.id(), capacity - needed);
+    action.set_action_description(ActionDescription::new(position, ActionMessageType::Reloaded));
+
+    let turn_time = entity.turn_time().expect("Entity missing turn_time");
+    action.set_alternative_turn_time(turn_time * RELOAD_TURNS);
+}
+
 pub fn consume(action: &mut EcsAction, entity: EntityRef, item: EntityRef) {
     let mut used = false;
     match item.consumable_type().expect("Entity missing consumable_type") {
@@ -409,6 +612,14 @@ pub fn consume(action: &mut EcsAction, entity: EntityRef, item: EntityRef) {
                 used = true;
             }
         }
+        ConsumableType::FuelCan => {
+            let fuel = entity.fuel().expect("Entity missing fuel");
+            if !fuel.is_full() {
+                let amount = item.fuel().expect("Fuel can missing fuel").ucurrent();
+                action.set_then(Reaction::new(ActionArgs::Refuel(entity.id(), amount), 0));
+                used = true;
+            }
+        }
     }
 
     if used {
@@ -5,3 +5,6 @@ mod common;
 pub mod actions {
     pub use super::common::*;
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,554 @@
+use std::ops::DerefMut;
+
+use ecs::*;
+use game::*;
+use game::data::*;
+use game::tests::prototypes;
+use spatial_hash::*;
+use coord::Coord;
+
+struct Armoury {
+    ecs: EcsCtx,
+    ids: EntityIdReserver,
+    rng: GameRng,
+    pc_id: EntityId,
+    gun_id: EntityId,
+}
+
+impl Armoury {
+    /// A player carrying the named gun, along with the named ammo boxes
+    fn new(gun: &str, boxes: &[&str]) -> Self {
+        let prototypes = prototypes();
+        let ids = EntityIdReserver::new();
+
+        let pc_id = ids.new_id();
+        let gun_id = ids.new_id();
+
+        let mut action = EcsAction::new();
+        prototypes::pc(action.entity_mut(pc_id), Coord::new(10, 10), &prototypes);
+        prototypes.populate(gun, &mut action.entity_mut(gun_id));
+
+        for name in boxes {
+            let box_id = ids.new_id();
+            prototypes.populate(name, &mut action.entity_mut(box_id));
+            action.inventory_mut(pc_id).expect("Missing component inventory").insert(box_id);
+        }
+
+        let mut ecs = EcsCtx::new();
+        ecs.commit(&mut action);
+
+        Armoury {
+            ecs: ecs,
+            ids: ids,
+            rng: GameRng::new(0),
+            pc_id: pc_id,
+            gun_id: gun_id,
+        }
+    }
+
+    fn set_loaded(&mut self, ammo: usize) {
+        let mut action = EcsAction::new();
+        action.insert_ammo(self.gun_id, ammo);
+        self.ecs.commit(&mut action);
+    }
+
+    fn fire(&self, shooter_id: EntityId) -> EcsAction {
+        let mut action = EcsAction::new();
+        actions::fire_gun(&mut action, self.ecs.entity(self.gun_id), self.ecs.entity(shooter_id),
+                          Coord::new(1, 0), &self.ids, self.rng.inner_mut().deref_mut());
+        action
+    }
+
+    fn reload(&self) -> (EcsAction, Option<ActionMessageType>) {
+        let mut action = EcsAction::new();
+        actions::reload(&mut action, &self.ecs, self.ecs.entity(self.pc_id), self.ecs.entity(self.gun_id));
+        let message = action.clear_action_description().map(|description| description.message);
+        (action, message)
+    }
+
+    fn boxes(&self) -> Vec<EntityId> {
+        self.ecs.inventory_borrow(self.pc_id).expect("Missing component inventory").iter().collect()
+    }
+}
+
+#[test]
+fn scale_to_range_follows_major_axis() {
+    assert_eq!(actions::scale_to_range(Coord::new(1, 0), 8), Coord::new(8, 0));
+    assert_eq!(actions::scale_to_range(Coord::new(0, -2), 8), Coord::new(0, -8));
+    assert_eq!(actions::scale_to_range(Coord::new(2, 1), 8), Coord::new(8, 4));
+    assert_eq!(actions::scale_to_range(Coord::new(-3, 3), 6), Coord::new(-6, 6));
+}
+
+#[test]
+fn scale_to_range_leaves_zero_vector() {
+    assert_eq!(actions::scale_to_range(Coord::new(0, 0), 8), Coord::new(0, 0));
+}
+
+#[test]
+fn player_firing_uses_a_round() {
+    let armoury = Armoury::new("pistol", &[]);
+    let action = armoury.fire(armoury.pc_id);
+
+    assert_eq!(action.ammo(armoury.gun_id), Some(7));
+}
+
+#[test]
+fn empty_gun_does_not_fire() {
+    let mut armoury = Armoury::new("pistol", &[]);
+    armoury.set_loaded(0);
+
+    let mut action = armoury.fire(armoury.pc_id);
+
+    assert_eq!(action.ammo(armoury.gun_id), None);
+    assert_eq!(action.action_time_ms(), None);
+    assert_eq!(action.clear_action_description().map(|description| description.message),
+               Some(ActionMessageType::GunEmpty));
+}
+
+#[test]
+fn npc_firing_does_not_use_ammo() {
+    let mut armoury = Armoury::new("pistol", &[]);
+    let zombie_id = armoury.ids.new_id();
+
+    let mut action = EcsAction::new();
+    prototypes::zombie(action.entity_mut(zombie_id), Coord::new(5, 5), &prototypes());
+    armoury.ecs.commit(&mut action);
+
+    let action = armoury.fire(zombie_id);
+
+    assert_eq!(action.ammo(armoury.gun_id), None);
+    assert!(action.action_time_ms().is_some());
+}
+
+#[test]
+fn reload_takes_rounds_from_box() {
+    let mut armoury = Armoury::new("pistol", &["bullets"]);
+    armoury.set_loaded(3);
+    let box_id = armoury.boxes()[0];
+
+    let (mut action, message) = armoury.reload();
+
+    assert_eq!(message, Some(ActionMessageType::Reloaded));
+    assert_eq!(action.ammo(armoury.gun_id), Some(8));
+    assert_eq!(action.ammo(box_id), Some(19));
+
+    armoury.ecs.commit(&mut action);
+    assert_eq!(armoury.boxes(), vec![box_id]);
+}
+
+#[test]
+fn reload_empties_boxes_in_turn() {
+    let mut armoury = Armoury::new("pistol", &["bullets", "bullets"]);
+    armoury.set_loaded(0);
+    let boxes = armoury.boxes();
+
+    // leave 2 rounds in the first box the inventory yields
+    let mut action = EcsAction::new();
+    action.insert_ammo(boxes[0], 2);
+    armoury.ecs.commit(&mut action);
+
+    let (mut action, message) = armoury.reload();
+
+    assert_eq!(message, Some(ActionMessageType::Reloaded));
+    assert_eq!(action.ammo(armoury.gun_id), Some(8));
+    assert_eq!(action.ammo(boxes[1]), Some(18));
+
+    armoury.ecs.commit(&mut action);
+    assert_eq!(armoury.boxes(), vec![boxes[1]]);
+    assert_eq!(armoury.ecs.ammo(boxes[0]), None);
+}
+
+#[test]
+fn reload_uses_up_short_box() {
+    let mut armoury = Armoury::new("pistol", &["bullets"]);
+    armoury.set_loaded(0);
+    let box_id = armoury.boxes()[0];
+
+    let mut action = EcsAction::new();
+    action.insert_ammo(box_id, 5);
+    armoury.ecs.commit(&mut action);
+
+    let (mut action, message) = armoury.reload();
+
+    assert_eq!(message, Some(ActionMessageType::Reloaded));
+    assert_eq!(action.ammo(armoury.gun_id), Some(5));
+
+    armoury.ecs.commit(&mut action);
+    assert!(armoury.boxes().is_empty());
+}
+
+#[test]
+fn reload_ignores_other_bullet_types() {
+    let mut armoury = Armoury::new("pistol", &["shells"]);
+    armoury.set_loaded(3);
+    let box_id = armoury.boxes()[0];
+
+    let (action, message) = armoury.reload();
+
+    assert_eq!(message, Some(ActionMessageType::NoAmmo));
+    assert_eq!(action.ammo(armoury.gun_id), None);
+    assert_eq!(action.ammo(box_id), None);
+    assert_eq!(action.alternative_turn_time(), None);
+}
+
+#[test]
+fn full_gun_is_not_reloaded() {
+    let armoury = Armoury::new("pistol", &["bullets"]);
+
+    let (action, message) = armoury.reload();
+
+    assert_eq!(message, Some(ActionMessageType::AlreadyLoaded));
+    assert_eq!(action.ammo(armoury.gun_id), None);
+    assert_eq!(action.alternative_turn_time(), None);
+}
+
+#[test]
+fn reload_takes_several_turns() {
+    let mut armoury = Armoury::new("pistol", &["bullets"]);
+    armoury.set_loaded(3);
+
+    let (action, _) = armoury.reload();
+
+    assert_eq!(action.alternative_turn_time(), Some(48));
+}
+
+#[test]
+fn flamethrower_spreads_flames_around_shooter() {
+    let armoury = Armoury::new("flamethrower", &[]);
+    let action = armoury.fire(armoury.pc_id);
+
+    let flames: Vec<Coord> = action.position_profile().insertion_copy_iter().map(|(_, position)| position).collect();
+
+    assert_eq!(flames.len(), 5);
+    for flame in flames {
+        let offset = flame - Coord::new(10, 10);
+        assert!(offset.x.abs() <= 1 && offset.y.abs() <= 1);
+    }
+}
+
+#[test]
+fn flames_burn_where_they_land() {
+    let armoury = Armoury::new("flamethrower", &[]);
+    let action = armoury.fire(armoury.pc_id);
+
+    for (flame_id, _) in action.position_profile().insertion_copy_iter() {
+        assert_eq!(action.burning(flame_id), Some(prototypes::FLAME_BURN_TURNS));
+    }
+}
+
+fn burn(turns: usize) -> (EcsCtx, EntityId, EcsAction) {
+    let mut ecs = EcsCtx::new();
+    let ids = EntityIdReserver::new();
+    let fire_id = ids.new_id();
+
+    let mut action = EcsAction::new();
+    action.insert_position(fire_id, Coord::new(3, 3));
+    action.insert_burning(fire_id, turns);
+    ecs.commit(&mut action);
+
+    let mut action = EcsAction::new();
+    actions::burn(&mut action, ecs.entity(fire_id));
+    (ecs, fire_id, action)
+}
+
+#[test]
+fn fire_burns_down_a_turn_at_a_time() {
+    let (ecs, fire_id, action) = burn(3);
+
+    assert_eq!(action.burning(fire_id), Some(2));
+    assert_eq!(action.position_negative_iter(&ecs).count(), 0);
+}
+
+#[test]
+fn fire_is_removed_when_burnt_out() {
+    let (ecs, fire_id, action) = burn(1);
+
+    assert_eq!(action.position_negative_iter(&ecs).collect::<Vec<_>>(), vec![fire_id]);
+}
+
+#[test]
+fn mine_dropper_drops_mine_next_to_shooter() {
+    let armoury = Armoury::new("mine_dropper", &[]);
+    let action = armoury.fire(armoury.pc_id);
+
+    let mines: Vec<Coord> = action.position_profile().insertion_copy_iter().map(|(_, position)| position).collect();
+
+    assert_eq!(mines, vec![Coord::new(11, 10)]);
+    assert_eq!(action.ammo(armoury.gun_id), Some(2));
+}
+
+fn pull(target: Coord, remaining: usize) -> EcsAction {
+    let armoury = Armoury::new("harpoon", &[]);
+    let mut ecs = armoury.ecs;
+    let target_id = armoury.ids.new_id();
+
+    let mut action = EcsAction::new();
+    prototypes::zombie(action.entity_mut(target_id), target, &prototypes());
+    ecs.commit(&mut action);
+
+    let mut action = EcsAction::new();
+    actions::pull(&mut action, ecs.entity(target_id), ecs.entity(armoury.pc_id), remaining);
+    action
+}
+
+#[test]
+fn pull_moves_a_cell_towards_shooter() {
+    let action = pull(Coord::new(15, 14), 3);
+
+    let moved: Vec<Coord> = action.position_profile().insertion_copy_iter().map(|(_, position)| position).collect();
+    assert_eq!(moved, vec![Coord::new(14, 13)]);
+
+    match action.then().map(|reaction| reaction.action) {
+        Some(ActionArgs::Pull { remaining, .. }) => assert_eq!(remaining, 2),
+        other => panic!("Unexpected reaction: {:?}", other),
+    }
+}
+
+#[test]
+fn pull_ends_after_last_cell() {
+    let action = pull(Coord::new(15, 10), 1);
+
+    assert_eq!(action.position_profile().insertion_copy_iter().count(), 1);
+    assert!(action.then().is_none());
+}
+
+#[test]
+fn pull_stops_next_to_shooter() {
+    let action = pull(Coord::new(11, 11), 3);
+
+    assert_eq!(action.position_profile().insertion_copy_iter().count(), 0);
+    assert!(action.then().is_none());
+}
+
+#[test]
+fn burst_shots_stay_within_spread_of_aim() {
+    const RANGE: usize = 8;
+    const SPREAD: usize = 1;
+
+    let armoury = Armoury::new("machine_gun", &[]);
+    let shooter = Coord::new(10, 10);
+    let aim = Coord::new(1, 0);
+    let target = shooter + actions::scale_to_range(aim, RANGE);
+
+    let mut vector = aim;
+    let mut remaining = 20;
+
+    while remaining > 0 {
+        let mut action = EcsAction::new();
+        actions::fire_burst(&mut action, armoury.ecs.entity(armoury.gun_id), armoury.ecs.entity(armoury.pc_id),
+                            vector, remaining, 100.0, 20, SPREAD, RANGE, BulletType::Bullet,
+                            &armoury.ids, armoury.rng.inner_mut().deref_mut());
+
+        // follow the shot to the end of its range
+        let (bullet_id, velocity) = action.realtime_velocity_positive_iter(&armoury.ecs).next().expect("No bullet fired");
+        let mut velocity = *velocity;
+        let mut end = action.position(bullet_id).expect("Bullet missing position");
+        for _ in 1..RANGE {
+            end = end + velocity.step_in_place();
+        }
+        assert!((end.x - target.x).abs() <= SPREAD as isize && (end.y - target.y).abs() <= SPREAD as isize,
+                "shot ended at {:?}, aimed at {:?}", end, target);
+
+        match action.then().map(|reaction| reaction.action) {
+            Some(ActionArgs::FireBurst { vector: next_vector, remaining: next_remaining, .. }) => {
+                assert_eq!(next_vector, aim);
+                vector = next_vector;
+                remaining = next_remaining;
+            }
+            None => remaining = 0,
+            other => panic!("Unexpected reaction: {:?}", other),
+        }
+    }
+}
+
+/// Fuel tank capacity of the player's van in the prototypes
+const TANK: isize = 300;
+
+/// The player's van with the given fuel and speed, carrying the named items
+fn van(fuel: isize, speed: usize, items: &[&str]) -> Armoury {
+    let mut armoury = Armoury::new("pistol", items);
+
+    let mut action = EcsAction::new();
+    action.insert_fuel(armoury.pc_id, HitPoints::new_with(TANK, fuel));
+    action.insert_current_speed(armoury.pc_id, speed);
+    armoury.ecs.commit(&mut action);
+
+    armoury
+}
+
+fn drive(armoury: &mut Armoury) -> EcsAction {
+    let velocity = RealtimeVelocity::new(Coord::new(1, 0), 20.0);
+
+    let mut action = EcsAction::new();
+    action.insert_realtime_velocity(armoury.pc_id, velocity);
+    armoury.ecs.commit(&mut action);
+
+    let mut action = EcsAction::new();
+    actions::realtime_velocity_move(&mut action, armoury.ecs.entity(armoury.pc_id), velocity);
+    action
+}
+
+fn physics(armoury: &Armoury) -> Vec<ActionArgs> {
+    let mut action = EcsAction::new();
+    actions::physics(&mut action);
+
+    let sh = SpatialHashTable::new(20, 20);
+    let env = RuleEnv {
+        ecs: &armoury.ecs,
+        spatial_hash: &sh,
+    };
+
+    let mut reactions = Vec::new();
+    assert!(rules::physics(env, &action, &mut reactions).is_ok());
+    reactions.into_iter().map(|reaction| reaction.action).collect()
+}
+
+#[test]
+fn driving_burns_fuel_by_speed() {
+    let mut armoury = van(TANK, 2, &[]);
+
+    let mut action = drive(&mut armoury);
+
+    assert_eq!(action.fuel(armoury.pc_id).map(|fuel| fuel.current()), Some(TANK - 2));
+    assert!(action.clear_action_description().is_none());
+}
+
+#[test]
+fn running_dry_reports_out_of_fuel() {
+    let mut armoury = van(1, 2, &[]);
+
+    let mut action = drive(&mut armoury);
+
+    assert_eq!(action.fuel(armoury.pc_id).map(|fuel| fuel.current()), Some(0));
+    assert_eq!(action.clear_action_description().map(|description| description.message),
+               Some(ActionMessageType::OutOfFuel));
+}
+
+#[test]
+fn empty_van_does_not_accelerate() {
+    let armoury = van(0, 1, &[]);
+
+    let mut action = EcsAction::new();
+    actions::change_speed(&mut action, armoury.ecs.entity(armoury.pc_id), ChangeSpeed::Accelerate,
+                          armoury.rng.inner_mut().deref_mut());
+
+    assert_eq!(action.current_speed(armoury.pc_id), Some(1));
+    assert_eq!(action.clear_action_description().map(|description| description.message),
+               Some(ActionMessageType::OutOfFuel));
+}
+
+#[test]
+fn empty_van_decelerates() {
+    let armoury = van(0, 2, &[]);
+
+    let decelerated = physics(&armoury).into_iter().any(|args| match args {
+        ActionArgs::ChangeSpeed(id, ChangeSpeed::Decelerate) => id == armoury.pc_id,
+        _ => false,
+    });
+
+    assert!(decelerated);
+}
+
+#[test]
+fn van_with_fuel_keeps_its_speed() {
+    let armoury = van(TANK, 2, &[]);
+
+    let decelerated = physics(&armoury).into_iter().any(|args| match args {
+        ActionArgs::ChangeSpeed(..) => true,
+        _ => false,
+    });
+
+    assert!(!decelerated);
+}
+
+fn stranded(armoury: &Armoury) -> bool {
+    physics(armoury).into_iter().any(|args| match args {
+        ActionArgs::Strand => true,
+        _ => false,
+    })
+}
+
+#[test]
+fn stopped_empty_van_is_stranded() {
+    assert!(stranded(&van(0, 0, &[])));
+    assert!(stranded(&van(0, 1, &[])));
+}
+
+#[test]
+fn van_carrying_fuel_is_not_stranded() {
+    assert!(!stranded(&van(0, 0, &["fuel_can"])));
+    assert!(!stranded(&van(1, 0, &[])));
+}
+
+#[test]
+fn fuel_can_refuels_by_its_contents() {
+    let armoury = van(100, 1, &["fuel_can"]);
+    let can_id = armoury.boxes()[0];
+
+    let mut action = EcsAction::new();
+    actions::consume(&mut action, armoury.ecs.entity(armoury.pc_id), armoury.ecs.entity(can_id));
+
+    match action.then().map(|reaction| reaction.action) {
+        Some(ActionArgs::Refuel(id, amount)) => {
+            assert_eq!(id, armoury.pc_id);
+            assert_eq!(amount, 150);
+        }
+        other => panic!("Unexpected reaction: {:?}", other),
+    }
+    assert!(armoury.boxes().is_empty());
+}
+
+#[test]
+fn fuel_can_is_kept_when_tank_is_full() {
+    let armoury = van(TANK, 1, &["fuel_can"]);
+    let can_id = armoury.boxes()[0];
+
+    let mut action = EcsAction::new();
+    actions::consume(&mut action, armoury.ecs.entity(armoury.pc_id), armoury.ecs.entity(can_id));
+
+    assert!(action.then().is_none());
+    assert_eq!(armoury.boxes(), vec![can_id]);
+}
+
+#[test]
+fn refuel_fills_up_to_capacity() {
+    let armoury = van(250, 1, &[]);
+
+    let mut action = EcsAction::new();
+    actions::refuel(&mut action, armoury.ecs.entity(armoury.pc_id), 150);
+
+    assert_eq!(action.fuel(armoury.pc_id).map(|fuel| fuel.current()), Some(TANK));
+    assert_eq!(action.clear_action_description().map(|description| description.message),
+               Some(ActionMessageType::Refuelled));
+}
+
+/// Number of times out of `attempts` a car manages to steer in some weather
+fn car_steers(weather: Weather, attempts: usize) -> usize {
+    let ids = EntityIdReserver::new();
+    let car_id = ids.new_id();
+    let rng = GameRng::new(0);
+
+    let mut action = EcsAction::new();
+    prototypes::car(action.entity_mut(car_id), Coord::new(10, 10), &prototypes());
+    let mut ecs = EcsCtx::new();
+    ecs.commit(&mut action);
+
+    (0..attempts).filter(|_| {
+        let mut action = EcsAction::new();
+        actions::steer(&mut action, ecs.entity(car_id), SteerDirection::Up, weather, rng.inner_mut().deref_mut());
+        action.steering(car_id).is_some()
+    }).count()
+}
+
+#[test]
+fn cars_always_steer_in_clear_weather() {
+    assert_eq!(car_steers(Weather::Clear, 100), 100);
+}
+
+#[test]
+fn cars_lose_grip_in_acid_rain() {
+    let steered = car_steers(Weather::AcidRain, 100);
+
+    assert!(steered > 0);
+    assert!(steered < 100);
+}
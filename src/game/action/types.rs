@@ -40,6 +40,8 @@ pub enum ActionArgs {
     Die(EntityId),
     AcidAnimate,
     Physics,
+    Burn(EntityId),
+    Strand,
     Steer(EntityId, SteerDirection),
     RemoveSteer(EntityId),
     ChangeSpeed(EntityId, ChangeSpeed),
@@ -52,7 +54,7 @@ pub enum ActionArgs {
     FireBurst {
         gun_id: EntityId,
         shooter_id: EntityId,
-        direction: Direction,
+        vector: Coord,
         remaining: usize,
         speed: f64,
         period: u64,
@@ -68,10 +70,22 @@ pub enum ActionArgs {
     RepairTyre(EntityId, usize),
     RepairEngine(EntityId, usize),
     Consume(EntityId, EntityId),
+    Refuel(EntityId, usize),
+    Reload(EntityId, EntityId),
+    Pull {
+        entity_id: EntityId,
+        towards_id: EntityId,
+        remaining: usize,
+    },
+    FireGunAlong {
+        gun_id: EntityId,
+        shooter_id: EntityId,
+        vector: Coord,
+    },
 }
 
 impl ActionArgs {
-    pub fn to_action<R: Rng>(self, action: &mut EcsAction, ecs: &EcsCtx, _spatial_hash: &SpatialHashTable, entity_ids: &EntityIdReserver, r: &mut R) {
+    pub fn to_action<R: Rng>(self, action: &mut EcsAction, ecs: &EcsCtx, _spatial_hash: &SpatialHashTable, weather: Weather, entity_ids: &EntityIdReserver, r: &mut R) {
         match self {
             ActionArgs::Null => (),
             ActionArgs::Walk(entity_id, direction) => {
@@ -110,23 +124,29 @@ impl ActionArgs {
             ActionArgs::Physics => {
                 actions::physics(action);
             }
+            ActionArgs::Burn(entity_id) => {
+                actions::burn(action, ecs.entity(entity_id));
+            }
+            ActionArgs::Strand => {
+                actions::strand(action);
+            }
             ActionArgs::Steer(entity_id, direction) => {
-                actions::steer(action, ecs.entity(entity_id), direction, r);
+                actions::steer(action, ecs.entity(entity_id), direction, weather, r);
             }
             ActionArgs::RemoveSteer(entity_id) => {
                 actions::remove_steer(action, entity_id);
             }
             ActionArgs::ChangeSpeed(entity_id, change) => {
-                actions::change_speed(action, ecs.entity(entity_id), change);
+                actions::change_speed(action, ecs.entity(entity_id), change, r);
             }
             ActionArgs::BecomeBloodstain(entity_id) => {
                 actions::become_bloodstain(action, ecs.entity(entity_id), entity_ids);
             }
             ActionArgs::FireGun { gun_id, shooter_id, direction } => {
-                actions::fire_gun(action, ecs.entity(gun_id), ecs.entity(shooter_id), direction, entity_ids, r);
+                actions::fire_gun(action, ecs.entity(gun_id), ecs.entity(shooter_id), direction.vector(), entity_ids, r);
             }
-            ActionArgs::FireBurst { gun_id, shooter_id, direction, remaining, speed, period, spread, range, bullet_type } => {
-                actions::fire_burst(action, ecs.entity(gun_id), ecs.entity(shooter_id), direction, remaining, speed, period, spread, range, bullet_type, entity_ids, r);
+            ActionArgs::FireBurst { gun_id, shooter_id, vector, remaining, speed, period, spread, range, bullet_type } => {
+                actions::fire_burst(action, ecs.entity(gun_id), ecs.entity(shooter_id), vector, remaining, speed, period, spread, range, bullet_type, entity_ids, r);
             }
             ActionArgs::ComplexDamage(entity_id, damage) => {
                 actions::complex_damage(action, ecs.entity(entity_id), damage, r);
@@ -155,6 +175,18 @@ impl ActionArgs {
             ActionArgs::Consume(entity_id, item_id) => {
                 actions::consume(action, ecs.entity(entity_id), ecs.entity(item_id));
             }
+            ActionArgs::Refuel(entity_id, amount) => {
+                actions::refuel(action, ecs.entity(entity_id), amount);
+            }
+            ActionArgs::Reload(entity_id, gun_id) => {
+                actions::reload(action, ecs, ecs.entity(entity_id), ecs.entity(gun_id));
+            }
+            ActionArgs::Pull { entity_id, towards_id, remaining } => {
+                actions::pull(action, ecs.entity(entity_id), ecs.entity(towards_id), remaining);
+            }
+            ActionArgs::FireGunAlong { gun_id, shooter_id, vector } => {
+                actions::fire_gun(action, ecs.entity(gun_id), ecs.entity(shooter_id), vector, entity_ids, r);
+            }
         }
     }
 }
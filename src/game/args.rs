@@ -15,10 +15,14 @@ pub fn make_options() -> getopts::Options {
 
     let frontends = format!("[ {} ]", FRONTEND_STRINGS.join(" | "));
 
-    opts.optflag("d", "debug", "enable debugging output");
+    opts.optflag("d", "debug", "enable debugging output, including a trace of rule checks");
     opts.optopt("f", "frontend", "specify frontend", frontends.as_ref());
     opts.optflag("h", "help", "print this help menu");
     opts.optopt("r", "rngseed", "seed the random number generator with a non-negative integer", "SEED");
+    opts.optopt("", "record", "record the rng seed and all inputs to a file", "FILE");
+    opts.optopt("", "replay", "replay a file created with --record", "FILE");
+    opts.optopt("", "export-save", "write the current save as json to a file and exit", "FILE");
+    opts.optopt("s", "script", "file of inputs to replay, one per line (headless frontend only)", "FILE");
 
     opts
 }
@@ -31,6 +35,10 @@ pub struct Arguments {
     pub resource_path: path::PathBuf,
     pub user_path: path::PathBuf,
     pub config: GameConfig,
+    pub script_path: Option<path::PathBuf>,
+    pub record_path: Option<path::PathBuf>,
+    pub replay_path: Option<path::PathBuf>,
+    pub export_save_path: Option<path::PathBuf>,
 }
 
 impl Arguments {
@@ -67,6 +75,25 @@ impl Arguments {
             return Err("Could not find suitable frontend".to_string());
         }
 
+        if let Some(script_path) = matches.opt_str("script") {
+            args.script_path = Some(path::PathBuf::from(script_path));
+        }
+
+        if let Some(record_path) = matches.opt_str("record") {
+            args.record_path = Some(path::PathBuf::from(record_path));
+        }
+
+        if let Some(replay_path) = matches.opt_str("replay") {
+            if args.record_path.is_some() {
+                return Err("Can't record and replay at the same time".to_string());
+            }
+            args.replay_path = Some(path::PathBuf::from(replay_path));
+        }
+
+        if let Some(export_save_path) = matches.opt_str("export-save") {
+            args.export_save_path = Some(path::PathBuf::from(export_save_path));
+        }
+
         if let Some(path) = resource_dir_path() {
             args.resource_path = path;
         }
@@ -106,6 +133,8 @@ fn user_dir_path() -> Option<path::PathBuf> {
 fn sane_frontend() -> Option<Frontend> {
     if cfg!(feature = "sdl2") {
         Some(Frontend::Sdl)
+    } else if cfg!(all(unix, feature = "rustty")) {
+        Some(Frontend::Ansi)
     } else {
         None
     }
@@ -120,6 +149,10 @@ impl Default for Arguments {
             resource_path: path::PathBuf::new(),
             user_path: path::PathBuf::new(),
             config: GameConfig::default(),
+            script_path: None,
+            record_path: None,
+            replay_path: None,
+            export_save_path: None,
         }
     }
 }
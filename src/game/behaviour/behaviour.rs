@@ -13,6 +13,7 @@ pub struct BehaviourInput<'a, R: 'a + KnowledgeRenderer> {
     pub entity: EntityRef<'a>,
     pub ecs: &'a EcsCtx,
     pub spatial_hash: &'a SpatialHashTable,
+    pub weather: Weather,
     pub level_id: LevelId,
     pub action_env: ActionEnv<'a>,
     pub renderer: &'a RefCell<R>,
@@ -26,6 +27,7 @@ impl<'a, R: KnowledgeRenderer> Clone for BehaviourInput<'a, R> {
             entity: self.entity,
             ecs: self.ecs,
             spatial_hash: self.spatial_hash,
+            weather: self.weather,
             level_id: self.level_id,
             action_env: self.action_env,
             renderer: self.renderer,
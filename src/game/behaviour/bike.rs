@@ -1,12 +1,16 @@
 use game::*;
 use game::data::*;
+use game::behaviour::vehicle::*;
+use game::behaviour::squad::*;
 use behaviour::LeafResolution;
 use direction::Direction;
-use coord::Coord;
+use search::GridSearchCtx;
 
 const RANGE: isize = 5;
 
 pub fn bike_chace<K: KnowledgeRenderer>() -> BehaviourLeaf<K> {
+    let search_ctx = GridSearchCtx::new();
+
     BehaviourLeaf::new(move |input| {
 
         let position = input.entity.position().unwrap();
@@ -23,109 +27,73 @@ pub fn bike_chace<K: KnowledgeRenderer>() -> BehaviourLeaf<K> {
             return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Decelerate)))
         };
 
-        let next_coord = position + Coord::new(1, 0);
-        if level_knowledge.get_with_default(next_coord).solid() {
-            if position.y > target.y + RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
+        if position.y == target.y && position.x != target.x && (position.x - target.x).abs() < RANGE {
+            // in line with the target, so shoot along the road at it
+            let gun_id = *weapon_slots.get(Direction::North).unwrap();
+            let direction = if position.x > target.x {
+                Direction::West
             } else {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
-            }
+                Direction::East
+            };
+            return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::FireGun {
+                gun_id: gun_id,
+                shooter_id: input.entity.id(),
+                direction: direction,
+            }));
         }
 
-        if position.x > target.x {
-            // we are in front of the target
+        if position.x == target.x {
+            // beside the target, so shoot across the road at it
 
-            if position.y == target.y && position.x - target.x < RANGE {
+            if position.y > target.y + 1 && position.y <= target.y + RANGE {
                 let gun_id = *weapon_slots.get(Direction::North).unwrap();
                 return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::FireGun {
                     gun_id: gun_id,
                     shooter_id: input.entity.id(),
-                    direction: Direction::West,
+                    direction: Direction::North,
                 }));
-            }
-
-            if position.x - target.x > (position.y - target.y).abs() as isize {
-                if position.y > target.y {
-                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-                } else if position.y < target.y {
-                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
-                }
-            }
-
-            if speed > 1 {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Decelerate)))
-            }
-
-            if position.y > target.y + RANGE {
-                if speed == 0 {
-                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
-                }
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-            } else if position.y < target.y - RANGE {
-                if speed == 0 {
-                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
-                }
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
-            }
-
-        } else if position.x < target.x {
-            // we are behind the target
-
-            if position.y == target.y && target.x - position.x < RANGE {
-                let gun_id = *weapon_slots.get(Direction::North).unwrap();
+            } else if position.y < target.y - 1 && position.y >= target.y - RANGE {
+                let gun_id = *weapon_slots.get(Direction::South).unwrap();
                 return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::FireGun {
                     gun_id: gun_id,
                     shooter_id: input.entity.id(),
-                    direction: Direction::East,
+                    direction: Direction::South,
                 }));
             }
+        }
+
+        let station = station(input.entity, position, target, target.y);
 
-            if position.x - target.x > (position.y - target.y).abs() as isize {
-                if position.y > target.y {
-                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-                } else if position.y < target.y {
-                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
+        if speed > 0 {
+            match plan_route(&search_ctx, level_knowledge, position, station.row, speed) {
+                Route::Steer(direction) => {
+                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), direction)))
                 }
+                Route::Blocked => {
+                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Decelerate)))
+                }
+                Route::Straight => {}
             }
+        }
 
-            // speed up to our max speed
-            if speed < max_speed {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
-            }
+        if position.x > station.column {
+            // we are in front of our station
 
-            if position.y > target.y + RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-            } else if position.y < target.y - RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
+            if speed > 1 {
+                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Decelerate)))
             }
 
-        } else {
-            // we are lined up with the target
-
-            if position.y > target.y + RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-            } else if position.y < target.y - RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
+            if speed == 0 && position.y != station.row {
+                // can't steer while stopped
+                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
             }
 
-            let weapon_slots = input.entity.weapon_slots_borrow().unwrap();
+        } else {
+            // we are behind or at our station
 
-            if position.y > target.y + 1 {
-                let gun_id = *weapon_slots.get(Direction::North).unwrap();
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::FireGun {
-                    gun_id: gun_id,
-                    shooter_id: input.entity.id(),
-                    direction: Direction::North,
-                }));
-            } else if position.y < target.y - 1 {
-                let gun_id = *weapon_slots.get(Direction::South).unwrap();
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::FireGun {
-                    gun_id: gun_id,
-                    shooter_id: input.entity.id(),
-                    direction: Direction::South,
-                }));
-            } else {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
+            // speed up to our max speed
+            if speed < max_speed {
+                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
             }
         }
 
@@ -1,13 +1,20 @@
 use game::*;
 use game::data::*;
+use game::behaviour::vehicle::*;
+use game::behaviour::squad::*;
 use behaviour::LeafResolution;
 use direction::Direction;
-use coord::Coord;
+use search::GridSearchCtx;
 
 const X_RANGE: isize = 2;
 const Y_RANGE: isize = 3;
 
+/// Rows between a car and its target when it's in position to shoot
+const FIRING_OFFSET: isize = 2;
+
 pub fn car_chace<K: KnowledgeRenderer>() -> BehaviourLeaf<K> {
+    let search_ctx = GridSearchCtx::new();
+
     BehaviourLeaf::new(move |input| {
 
         let position = input.entity.position().unwrap();
@@ -23,75 +30,69 @@ pub fn car_chace<K: KnowledgeRenderer>() -> BehaviourLeaf<K> {
             return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Decelerate)))
         };
 
-        let next_coord = position + Coord::new(1, 0);
-        if level_knowledge.get_with_default(next_coord).solid() {
-            if position.y > target.y + Y_RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-            } else {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
+        // drive alongside the target, on whichever side we're already on
+        let firing_row = if position.y > target.y {
+            target.y + FIRING_OFFSET
+        } else {
+            target.y - FIRING_OFFSET
+        };
+        let station = station(input.entity, position, target, firing_row);
+
+        if speed > 0 {
+            match plan_route(&search_ctx, level_knowledge, position, station.row, speed) {
+                Route::Steer(direction) => {
+                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), direction)))
+                }
+                Route::Blocked => {
+                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Decelerate)))
+                }
+                Route::Straight => {}
             }
         }
 
-        if position.x > target.x + X_RANGE {
-            // we are in front of the target
+        if position.x > station.column + X_RANGE {
+            // we are in front of our station
 
             if speed > 1 {
                 return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Decelerate)))
             }
 
-            if position.y > target.y + Y_RANGE {
-                if speed == 0 {
-                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
-                }
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-            } else if position.y < target.y - Y_RANGE {
-                if speed == 0 {
-                    return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
-                }
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
+            if speed == 0 && position.y != station.row {
+                // can't steer while stopped
+                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
             }
 
-        } else if position.x < target.x - X_RANGE {
-            // we are behind the target
+        } else if position.x < station.column - X_RANGE {
+            // we are behind our station
 
             // speed up to our max speed
             if speed < max_speed {
                 return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
             }
 
-            if position.y > target.y + Y_RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-            } else if position.y < target.y - Y_RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
-            }
-
         } else {
-            // we are lined up with the target
-
-            if position.y > target.y + Y_RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
-            } else if position.y < target.y - Y_RANGE {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Down)))
-            }
+            // we are at our station
 
             let weapon_slots = input.entity.weapon_slots_borrow().unwrap();
 
-            if position.y > target.y + 1 {
+            if position.y > target.y + 1 && position.y <= target.y + Y_RANGE {
                 let gun_id = *weapon_slots.get(Direction::North).unwrap();
                 return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::FireGun {
                     gun_id: gun_id,
                     shooter_id: input.entity.id(),
                     direction: Direction::North,
                 }));
-            } else if position.y < target.y - 1 {
+            } else if position.y < target.y - 1 && position.y >= target.y - Y_RANGE {
                 let gun_id = *weapon_slots.get(Direction::South).unwrap();
                 return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::FireGun {
                     gun_id: gun_id,
                     shooter_id: input.entity.id(),
                     direction: Direction::South,
                 }));
-            } else {
-                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::Steer(input.entity.id(), SteerDirection::Up)))
+            }
+
+            if speed == 0 {
+                return LeafResolution::Yield(MetaAction::ActionArgs(ActionArgs::ChangeSpeed(input.entity.id(), ChangeSpeed::Accelerate)))
             }
         }
 
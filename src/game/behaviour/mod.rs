@@ -14,4 +14,9 @@ mod acid_animation;
 mod physics;
 mod car;
 mod bike;
+mod vehicle;
+mod squad;
 mod zombie;
+
+#[cfg(test)]
+mod tests;
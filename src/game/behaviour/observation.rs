@@ -1,6 +1,31 @@
 use game::*;
+use game::data::*;
+use ecs::*;
+use spatial_hash::*;
+use coord::Coord;
 use behaviour::SwitchResolution;
 
+/// Tells the other members of a squad where targets were seen by one of
+/// its members during the action with id `action_id`
+pub fn share_targets(ecs: &EcsCtx, spatial_hash: &SpatialHashTable, level_id: LevelId, action_id: u64,
+                     sharer_id: EntityId, squad_id: SquadId, targets: &[Coord]) {
+
+    for (member_id, member_squad_id) in ecs.squad_id_iter() {
+        if member_id == sharer_id || member_squad_id != squad_id {
+            continue;
+        }
+
+        if let Some(mut knowledge) = ecs.simple_npc_knowledge_borrow_mut(member_id) {
+            let level_knowledge = knowledge.level_mut_or_insert_size(level_id,
+                                                                     spatial_hash.width(),
+                                                                     spatial_hash.height());
+            for target in targets {
+                level_knowledge.add_target(*target, action_id);
+            }
+        }
+    }
+}
+
 pub fn simple_npc_shadowcast<K: KnowledgeRenderer>(child: BehaviourNodeIndex) -> BehaviourSwitch<K> {
 
     let shadowcast = Shadowcast::new();
@@ -8,17 +33,30 @@ pub fn simple_npc_shadowcast<K: KnowledgeRenderer>(child: BehaviourNodeIndex) ->
     BehaviourSwitch::new_returning(move |input| {
 
         let eye = input.entity.position().unwrap();
-        let vision_distance = input.entity.vision_distance().unwrap();
-        let mut knowledge = input.entity.simple_npc_knowledge_borrow_mut().unwrap();
-        let level_knowledge = knowledge.level_mut_or_insert_size(input.level_id,
-                                                                 input.spatial_hash.width(),
-                                                                 input.spatial_hash.height());
+        let vision_distance = input.weather.vision_distance(input.entity.vision_distance().unwrap());
+
+        let targets = {
+            let mut knowledge = input.entity.simple_npc_knowledge_borrow_mut().unwrap();
+            let level_knowledge = knowledge.level_mut_or_insert_size(input.level_id,
+                                                                     input.spatial_hash.width(),
+                                                                     input.spatial_hash.height());
+
+            shadowcast.observe(eye, input.spatial_hash, vision_distance, input.weather.opacity(),
+                               level_knowledge, input.action_env);
 
-        shadowcast.observe(eye, input.spatial_hash, vision_distance,
-                           level_knowledge, input.action_env);
+            if level_knowledge.last_target_update() == input.action_env.id {
+                // the targets have changed
+                Some(level_knowledge.targets())
+            } else {
+                None
+            }
+        };
 
-        if level_knowledge.last_target_update() == input.action_env.id {
-            // the targets have changed
+        if let Some(targets) = targets {
+            if let Some(squad_id) = input.entity.squad_id() {
+                share_targets(input.ecs, input.spatial_hash, input.level_id, input.action_env.id,
+                              input.entity.id(), squad_id, &targets);
+            }
             SwitchResolution::Reset(child)
         } else {
             SwitchResolution::Select(child)
@@ -7,6 +7,7 @@ use game::data::*;
 use ecs::*;
 use behaviour::LeafResolution;
 use direction::{self, Direction};
+use coord::{Coord, StraightLine};
 
 pub fn player_input<K: KnowledgeRenderer, I: 'static + InputSource + Clone>(input_source: I) -> BehaviourLeaf<K> {
     BehaviourLeaf::new(move |input| {
@@ -51,6 +52,10 @@ fn display_message_log<K: KnowledgeRenderer, I: InputSource>(input: BehaviourInp
         renderer.publish_fullscreen_log(message_log.deref(), offset, input.language);
 
         if let Some(event) = input_source.next_input() {
+            if event == InputEvent::Quit {
+                break;
+            }
+
             if let Some(control) = map.get(event) {
                 match control {
                     Control::Pause |
@@ -77,10 +82,24 @@ fn aim<R: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<R>, map: &Con
     let mut renderer = input.renderer.borrow_mut();
     let mut message_log = input.entity.message_log_borrow_mut().expect("Expected component message_log");
 
+    // preview what every mounted gun would hit if fired along its mount
+    let preview = {
+        let position = input.entity.position().expect("Expected component position");
+        let weapon_slots = input.entity.weapon_slots_borrow().expect("Expected component weapon_slots");
+        let knowledge = input.entity.drawable_knowledge_borrow().expect("Expected component drawable_knowledge");
+        let level = knowledge.level(input.level_id);
+
+        RenderOverlay::Layers(direction::iter().filter_map(|direction| {
+            weapon_slots.get(direction).map(|gun_id| {
+                line_of_fire(input.ecs.entity(*gun_id), position, direction.vector(),
+                             input.spatial_hash, level, input.action_env)
+            })
+        }).collect())
+    };
+
     message_log.add_temporary(MessageType::ChooseDirection);
     renderer.update_log_buffer(message_log.deref(), input.language);
-    renderer.draw_log();
-    renderer.publish_all_windows(input.entity, input.language);
+    renderer.publish_all_windows_with_overlay(input.entity, input.language, &preview);
 
     let mut should_clear_log = true;
 
@@ -89,6 +108,28 @@ fn aim<R: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<R>, map: &Con
             match control {
                 Control::Direction(direction) => {
                     let weapon_slots = input.entity.weapon_slots_borrow().expect("Expected component weapon_slots");
+
+                    // with a gun on a diagonal beside the chosen direction, a
+                    // second direction picks which mount to fire
+                    let direction = if beside_diagonal_weapons(&weapon_slots, direction) {
+                        message_log.add_temporary(MessageType::ChooseSecondDirection);
+                        renderer.update_log_buffer(message_log.deref(), input.language);
+                        renderer.publish_all_windows_with_overlay(input.entity, input.language, &preview);
+
+                        match input_source.next_input().and_then(|event| map.get(event)) {
+                            Some(Control::Direction(second)) if second == direction => direction,
+                            Some(Control::Direction(second)) => {
+                                match combine_directions(direction, second) {
+                                    Some(diagonal) => diagonal,
+                                    None => return None,
+                                }
+                            }
+                            _ => return None,
+                        }
+                    } else {
+                        direction
+                    };
+
                     if let Some(weapon) = weapon_slots.get(direction) {
                         Some((*weapon, direction))
                     } else {
@@ -112,6 +153,179 @@ fn aim<R: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<R>, map: &Con
     ret
 }
 
+/// Cycles through the visible enemies, closest first, previewing what the
+/// best-mounted gun would hit. Returns that gun and the vector to fire it along.
+fn target<R: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<R>, map: &ControlMap, mut input_source: I) -> Option<(EntityId, Coord)> {
+
+    let position = input.entity.position().expect("Expected component position");
+
+    let (targets, names) = {
+        let mut knowledge = input.entity.drawable_knowledge_borrow_mut().expect("Expected component drawable_knowledge");
+        let level = knowledge.level_mut(input.level_id);
+        let sorted = level.sort_targets(position).to_vec();
+        let targets = sorted.into_iter()
+            .filter(|coord| level.can_see(*coord, input.action_env))
+            .collect::<Vec<_>>();
+        let names = targets.iter().map(|coord| level.get_with_default(*coord).name()).collect::<Vec<_>>();
+        (targets, names)
+    };
+
+    let mut renderer = input.renderer.borrow_mut();
+    let mut message_log = input.entity.message_log_borrow_mut().expect("Expected component message_log");
+
+    if targets.is_empty() {
+        message_log.add_temporary(MessageType::NoTargets);
+        renderer.update_log_buffer(message_log.deref(), input.language);
+        renderer.draw_log();
+        renderer.publish_all_windows(input.entity, input.language);
+        return None;
+    }
+
+    let weapon_slots = input.entity.weapon_slots_borrow().expect("Expected component weapon_slots");
+    let knowledge = input.entity.drawable_knowledge_borrow().expect("Expected component drawable_knowledge");
+    let level = knowledge.level(input.level_id);
+
+    let mut index = 0;
+    let mut chosen = None;
+
+    loop {
+        let vector = targets[index] - position;
+        let gun_id = best_mounted_weapon(input.ecs, &weapon_slots, vector);
+        let line = StraightLine::new(position, targets[index]).iter().skip(1).collect::<Vec<_>>();
+
+        // preview what the gun would hit beneath the line to the target
+        let overlay = match gun_id {
+            Some(gun_id) => {
+                let preview = line_of_fire(input.ecs.entity(gun_id), position, vector, input.spatial_hash, level, input.action_env);
+                RenderOverlay::Layers(vec![preview, RenderOverlay::AimLine(line)])
+            }
+            None => RenderOverlay::AimLine(line),
+        };
+
+        message_log.add_temporary(MessageType::ChooseTarget(names[index]));
+        renderer.update_log_buffer(message_log.deref(), input.language);
+        renderer.publish_all_windows_with_overlay(input.entity, input.language, &overlay);
+
+        match input_source.next_input().and_then(|event| map.get(event)) {
+            Some(Control::Direction(Direction::North)) |
+                Some(Control::Direction(Direction::West)) => {
+                index = (index + targets.len() - 1) % targets.len();
+            }
+            Some(Control::Direction(_)) => {
+                index = (index + 1) % targets.len();
+            }
+            Some(Control::Fire) |
+                Some(Control::Target) => {
+                chosen = Some((gun_id, vector));
+                break;
+            }
+            _ => break,
+        }
+    }
+
+    let ret = match chosen {
+        Some((Some(gun_id), vector)) => {
+            message_log.add_temporary(MessageType::Empty);
+            Some((gun_id, vector))
+        }
+        Some((None, _)) => {
+            message_log.add_temporary(MessageType::NoWeaponFacingTarget);
+            None
+        }
+        None => {
+            message_log.add_temporary(MessageType::Empty);
+            None
+        }
+    };
+
+    renderer.update_log_buffer(message_log.deref(), input.language);
+    renderer.draw_log();
+    renderer.publish_all_windows(input.entity, input.language);
+
+    ret
+}
+
+/// The gun whose mount faces most directly along a vector, preferring
+/// loaded guns. Guns mounted facing away from the vector are never chosen.
+pub fn best_mounted_weapon(ecs: &EcsCtx, weapon_slots: &DirectionTable<EntityId>, vector: Coord) -> Option<EntityId> {
+    let vector_length = (vector.length_squared() as f64).sqrt();
+    let mut best: Option<(bool, f64, EntityId)> = None;
+
+    for direction in direction::iter() {
+        if let Some(gun_id) = weapon_slots.get(direction) {
+            let mount = direction.vector();
+            let dot = mount.x * vector.x + mount.y * vector.y;
+            if dot <= 0 {
+                continue;
+            }
+
+            let alignment = dot as f64 / ((mount.length_squared() as f64).sqrt() * vector_length);
+            let loaded = ecs.ammo(*gun_id).map(|ammo| ammo > 0).unwrap_or(true);
+
+            let better = best.map(|(best_loaded, best_alignment, _)| {
+                (loaded, alignment) > (best_loaded, best_alignment)
+            }).unwrap_or(true);
+
+            if better {
+                best = Some((loaded, alignment, *gun_id));
+            }
+        }
+    }
+
+    best.map(|(_, _, gun_id)| gun_id)
+}
+
+/// Describes what the player can see, or remembers, at a coordinate
+pub fn examine_message(level: &DrawableKnowledgeLevel, coord: Coord, action_env: ActionEnv) -> MessageType {
+    let cell = level.get_with_default(coord);
+
+    if cell.last_updated() == 0 {
+        MessageType::Unseen
+    } else if !level.can_see(coord, action_env) {
+        MessageType::YouRemember(cell.name())
+    } else if let Some(name) = cell.name() {
+        MessageType::Examine {
+            name: name,
+            health: cell.health_overlay().map(|hit_points| (hit_points.current(), hit_points.max())),
+            description: cell.description(),
+        }
+    } else {
+        MessageType::NoDescription
+    }
+}
+
+/// Moves a cursor around the game window, describing the cell beneath it
+fn examine<R: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<R>, map: &ControlMap, mut input_source: I) {
+
+    let mut renderer = input.renderer.borrow_mut();
+    let mut message_log = input.entity.message_log_borrow_mut().expect("Expected component message_log");
+    let knowledge = input.entity.drawable_knowledge_borrow().expect("Expected component drawable_knowledge");
+    let level = knowledge.level(input.level_id);
+
+    let mut cursor = input.entity.position().expect("Expected component position");
+
+    loop {
+        message_log.add_temporary(examine_message(level, cursor, input.action_env));
+        renderer.update_log_buffer(message_log.deref(), input.language);
+        renderer.publish_all_windows_with_overlay(input.entity, input.language, &RenderOverlay::ExamineCursor(cursor));
+
+        match input_source.next_input().and_then(|event| map.get(event)) {
+            Some(Control::Direction(direction)) => {
+                let next = cursor + direction.vector();
+                if renderer.contains_world_coord(next) {
+                    cursor = next;
+                }
+            }
+            _ => break,
+        }
+    }
+
+    message_log.add_temporary(MessageType::Empty);
+    renderer.update_log_buffer(message_log.deref(), input.language);
+    renderer.draw_log();
+    renderer.publish_all_windows(input.entity, input.language);
+}
+
 fn inventory<K: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<K>, mut input_source: I) -> Option<EntityId> {
 
     let mut menu = SelectMenu::new();
@@ -142,6 +356,11 @@ fn inventory<K: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<K>, mut
 }
 
 fn try_consume_item<K: KnowledgeRenderer>(input: BehaviourInput<K>, item_id: EntityId) -> Option<ActionArgs> {
+    // guns and ammo stay in the inventory until they're equipped or loaded
+    if !input.ecs.contains_consumable_type(item_id) {
+        return None;
+    }
+
     let speed = input.entity.current_speed().expect("Missing component current_speed");
     if speed == 0 {
         let mut inv = input.entity.inventory_borrow_mut().expect("Missing component inventory");
@@ -157,30 +376,36 @@ fn try_consume_item<K: KnowledgeRenderer>(input: BehaviourInput<K>, item_id: Ent
     None
 }
 
-fn direction_to_relative_message(direction: Direction) -> MessageType {
-    match direction {
-        Direction::East => MessageType::Front,
-        Direction::West => MessageType::Rear,
-        Direction::North => MessageType::Left,
-        Direction::South => MessageType::Right,
-        _ => panic!(),
-    }
+/// The diagonal between two perpendicular directions
+fn combine_directions(a: Direction, b: Direction) -> Option<Direction> {
+    let vector = a.vector() + b.vector();
+    direction::iter().find(|d| d.vector() == vector)
+}
+
+fn beside_diagonal_weapons(weapon_slots: &DirectionTable<EntityId>, direction: Direction) -> bool {
+    [direction.left90(), direction.right90()].iter().any(|side| {
+        combine_directions(direction, *side).map(|diagonal| weapon_slots.get(diagonal).is_some()).unwrap_or(false)
+    })
 }
 
 fn display_status<K: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<K>, mut input_source: I) {
     let mut renderer = input.renderer.borrow_mut();
     let weapon_slots = input.entity.weapon_slots_borrow().expect("Expected component weapon_slots");
+    let extra_mounts = input.entity.extra_weapon_mounts().unwrap_or(0);
 
     let mut message = Message::new();
 
-    for d in direction::cardinal_direction_iter() {
-        let m = direction_to_relative_message(d);
+    for d in direction::cardinal_direction_iter().chain(EXTRA_WEAPON_MOUNTS.iter().cloned().take(extra_mounts)) {
+        let m = MessageType::RelativeDirection(RelativeDirection::from(d));
         input.language.translate(m, &mut message);
         message.push(MessagePart::plain(": "));
 
         if let Some(weapon_id) = weapon_slots.get(d) {
             let name = input.ecs.name(*weapon_id).expect("Expected component name");
             input.language.translate(MessageType::Name(name), &mut message);
+            if let (Some(ammo), Some(capacity)) = (input.ecs.ammo(*weapon_id), input.ecs.ammo_capacity(*weapon_id)) {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!(" ({}/{})", ammo, capacity))));
+            }
         } else {
             input.language.translate(MessageType::EmptyWeaponSlot, &mut message);
         }
@@ -214,6 +439,20 @@ fn get_meta_action<K: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<K
                             })
                         })
                     }
+                    Control::Target => {
+                        target(input, map, input_source).map(|(gun_id, vector)| {
+                            MetaAction::ActionArgs(ActionArgs::FireGunAlong {
+                                gun_id: gun_id,
+                                shooter_id: input.entity.id(),
+                                vector: vector,
+                            })
+                        })
+                    }
+                    Control::Reload => {
+                        aim(input, map, input_source).map(|(gun_id, _)| {
+                            MetaAction::ActionArgs(ActionArgs::Reload(input.entity.id(), gun_id))
+                        })
+                    }
                     Control::Inventory => {
                         inventory(input, input_source).and_then(|item| try_consume_item(input, item)).map(MetaAction::ActionArgs)
                     }
@@ -229,6 +468,10 @@ fn get_meta_action<K: KnowledgeRenderer, I: InputSource>(input: BehaviourInput<K
                         display_status(input, input_source);
                         None
                     }
+                    Control::Examine => {
+                        examine(input, map, input_source);
+                        None
+                    }
                     _ => None,
                 }
             })
@@ -0,0 +1,46 @@
+use game::*;
+use game::data::*;
+use coord::Coord;
+
+/// Columns a blocker stays ahead of its target
+const BLOCK_DISTANCE: isize = 3;
+
+/// Where a vehicle tries to drive relative to its target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Station {
+    pub row: isize,
+    pub column: isize,
+}
+
+/// Chooses where a vehicle at `position` should drive to fill its role in
+/// a squad. Vehicles with no role, and shooters, drive in `default_row`
+/// level with the target.
+pub fn station(entity: EntityRef, position: Coord, target: Coord, default_row: isize) -> Station {
+    match entity.squad_role() {
+        Some(SquadRole::Blocker) => {
+            // get in front of the target, in its lane
+            Station {
+                row: target.y,
+                column: target.x + BLOCK_DISTANCE,
+            }
+        }
+        Some(SquadRole::Flanker) => {
+            // drive in the lane beside the target so it can't steer that way
+            let row = if position.y > target.y {
+                target.y + 1
+            } else {
+                target.y - 1
+            };
+            Station {
+                row: row,
+                column: target.x,
+            }
+        }
+        Some(SquadRole::Shooter) | None => {
+            Station {
+                row: default_row,
+                column: target.x,
+            }
+        }
+    }
+}
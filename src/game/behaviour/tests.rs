@@ -0,0 +1,332 @@
+use ecs::*;
+use game::*;
+use game::data::*;
+use game::tests::prototypes;
+use spatial_hash::*;
+use util::TwoDimensionalCons;
+use search::GridSearchCtx;
+use direction::Direction;
+use coord::Coord;
+use super::player_input::{best_mounted_weapon, examine_message};
+use super::observation::share_targets;
+use super::squad::{station, Station};
+use super::vehicle::{plan_route, Route};
+
+/// Mounts a pistol in each direction, loaded with the given number of rounds
+fn mount(slots: &[(Direction, usize)]) -> (EcsCtx, DirectionTable<EntityId>) {
+    let prototypes = prototypes();
+    let ids = EntityIdReserver::new();
+
+    let mut action = EcsAction::new();
+    let mut weapon_slots = DirectionTable::new();
+
+    for &(direction, ammo) in slots {
+        let gun_id = ids.new_id();
+        prototypes::pistol(action.entity_mut(gun_id), &prototypes);
+        action.insert_ammo(gun_id, ammo);
+        weapon_slots.insert(direction, gun_id);
+    }
+
+    let mut ecs = EcsCtx::new();
+    ecs.commit(&mut action);
+
+    (ecs, weapon_slots)
+}
+
+#[test]
+fn best_mounted_weapon_faces_target() {
+    let (ecs, slots) = mount(&[(Direction::North, 8), (Direction::East, 8)]);
+
+    assert_eq!(best_mounted_weapon(&ecs, &slots, Coord::new(5, 1)), slots.get(Direction::East).cloned());
+    assert_eq!(best_mounted_weapon(&ecs, &slots, Coord::new(1, -5)), slots.get(Direction::North).cloned());
+}
+
+#[test]
+fn best_mounted_weapon_prefers_closest_alignment() {
+    let (ecs, slots) = mount(&[(Direction::East, 8), (Direction::NorthEast, 8)]);
+
+    assert_eq!(best_mounted_weapon(&ecs, &slots, Coord::new(3, -3)), slots.get(Direction::NorthEast).cloned());
+    assert_eq!(best_mounted_weapon(&ecs, &slots, Coord::new(6, -1)), slots.get(Direction::East).cloned());
+}
+
+#[test]
+fn best_mounted_weapon_prefers_loaded_guns() {
+    let (ecs, slots) = mount(&[(Direction::East, 0), (Direction::NorthEast, 8)]);
+
+    assert_eq!(best_mounted_weapon(&ecs, &slots, Coord::new(6, -1)), slots.get(Direction::NorthEast).cloned());
+}
+
+#[test]
+fn best_mounted_weapon_falls_back_to_empty_gun() {
+    let (ecs, slots) = mount(&[(Direction::East, 0)]);
+
+    assert_eq!(best_mounted_weapon(&ecs, &slots, Coord::new(6, -1)), slots.get(Direction::East).cloned());
+}
+
+#[test]
+fn best_mounted_weapon_ignores_guns_facing_away() {
+    let (ecs, slots) = mount(&[(Direction::West, 8), (Direction::North, 8), (Direction::South, 8)]);
+
+    assert_eq!(best_mounted_weapon(&ecs, &slots, Coord::new(4, 0)), None);
+}
+
+const WIDTH: usize = 10;
+const HEIGHT: usize = 10;
+
+/// A level containing a zombie, seen by the player during the given action
+fn look_at_zombie(zombie: Coord, action_id: u64) -> (EcsCtx, DrawableKnowledgeLevel) {
+    let ids = EntityIdReserver::new();
+    let mut sh = SpatialHashTable::new(WIDTH, HEIGHT);
+    let mut ecs = EcsCtx::new();
+    let mut knowledge = DrawableKnowledgeLevel::new(WIDTH, HEIGHT);
+
+    let mut action = EcsAction::new();
+    prototypes::zombie(action.entity_mut(ids.new_id()), zombie, &prototypes());
+    sh.update(&ecs, &action, action_id);
+    ecs.commit(&mut action);
+
+    {
+        let action_env = ActionEnv::new(&ecs, action_id);
+        for y in 0..HEIGHT as isize {
+            for x in 0..WIDTH as isize {
+                let coord = Coord::new(x, y);
+                knowledge.update_cell(coord, sh.get(coord), 1.0, action_env);
+            }
+        }
+    }
+
+    (ecs, knowledge)
+}
+
+#[test]
+fn examine_describes_visible_entity() {
+    let (ecs, knowledge) = look_at_zombie(Coord::new(3, 4), 1);
+
+    assert_eq!(examine_message(&knowledge, Coord::new(3, 4), ActionEnv::new(&ecs, 1)), MessageType::Examine {
+        name: NameMessageType::Zombie,
+        health: Some((2, 2)),
+        description: None,
+    });
+}
+
+#[test]
+fn examine_visible_empty_cell() {
+    let (ecs, knowledge) = look_at_zombie(Coord::new(3, 4), 1);
+
+    assert_eq!(examine_message(&knowledge, Coord::new(5, 5), ActionEnv::new(&ecs, 1)), MessageType::NoDescription);
+}
+
+#[test]
+fn examine_remembers_entity_out_of_sight() {
+    let (ecs, knowledge) = look_at_zombie(Coord::new(3, 4), 1);
+
+    assert_eq!(examine_message(&knowledge, Coord::new(3, 4), ActionEnv::new(&ecs, 2)),
+               MessageType::YouRemember(Some(NameMessageType::Zombie)));
+}
+
+#[test]
+fn examine_unseen_cell() {
+    let ecs = EcsCtx::new();
+    let knowledge = DrawableKnowledgeLevel::new(WIDTH, HEIGHT);
+
+    assert_eq!(examine_message(&knowledge, Coord::new(3, 4), ActionEnv::new(&ecs, 1)), MessageType::Unseen);
+}
+
+/// Station of a vehicle with the given role at `position`, chasing a target
+/// at (10, 5), whose default row is 2
+fn station_for(role: Option<SquadRole>, position: Coord) -> Station {
+    let ids = EntityIdReserver::new();
+    let id = ids.new_id();
+
+    let mut action = EcsAction::new();
+    action.insert_position(id, position);
+    if let Some(role) = role {
+        action.insert_squad_role(id, role);
+    }
+    let mut ecs = EcsCtx::new();
+    ecs.commit(&mut action);
+
+    station(ecs.entity(id), position, Coord::new(10, 5), 2)
+}
+
+#[test]
+fn blocker_drives_ahead_of_target_in_its_lane() {
+    assert_eq!(station_for(Some(SquadRole::Blocker), Coord::new(4, 8)), Station { row: 5, column: 13 });
+}
+
+#[test]
+fn flanker_drives_beside_target_on_its_own_side() {
+    assert_eq!(station_for(Some(SquadRole::Flanker), Coord::new(4, 8)), Station { row: 6, column: 10 });
+    assert_eq!(station_for(Some(SquadRole::Flanker), Coord::new(4, 1)), Station { row: 4, column: 10 });
+}
+
+#[test]
+fn shooter_drives_level_with_target_in_default_row() {
+    assert_eq!(station_for(Some(SquadRole::Shooter), Coord::new(4, 8)), Station { row: 2, column: 10 });
+}
+
+#[test]
+fn vehicle_without_role_drives_like_shooter() {
+    assert_eq!(station_for(None, Coord::new(4, 8)), Station { row: 2, column: 10 });
+}
+
+const SQUAD_LEVEL_ID: LevelId = 0;
+const OLD_TARGET: Coord = Coord { x: 1, y: 1 };
+
+/// Two members of a squad, and a vehicle from another squad, each knowing
+/// of a target seen during action 5
+struct Squads {
+    ecs: EcsCtx,
+    sh: SpatialHashTable,
+    sharer_id: EntityId,
+    member_id: EntityId,
+    outsider_id: EntityId,
+}
+
+impl Squads {
+    fn new() -> Self {
+        let ids = EntityIdReserver::new();
+        let sharer_id = ids.new_id();
+        let member_id = ids.new_id();
+        let outsider_id = ids.new_id();
+        let sh = SpatialHashTable::new(10, 10);
+
+        let mut action = EcsAction::new();
+        for &(id, squad_id) in &[(sharer_id, sharer_id), (member_id, sharer_id), (outsider_id, outsider_id)] {
+            let mut knowledge = SimpleNpcKnowledge::new();
+            knowledge.level_mut_or_insert_size(SQUAD_LEVEL_ID, sh.width(), sh.height())
+                .add_target(OLD_TARGET, 5);
+            action.insert_simple_npc_knowledge(id, knowledge);
+            action.insert_squad_id(id, squad_id);
+        }
+        let mut ecs = EcsCtx::new();
+        ecs.commit(&mut action);
+
+        Squads {
+            ecs: ecs,
+            sh: sh,
+            sharer_id: sharer_id,
+            member_id: member_id,
+            outsider_id: outsider_id,
+        }
+    }
+
+    fn share(&self, target: Coord, action_id: u64) {
+        share_targets(&self.ecs, &self.sh, SQUAD_LEVEL_ID, action_id, self.sharer_id, self.sharer_id, &[target]);
+    }
+
+    fn targets(&self, id: EntityId) -> Vec<Coord> {
+        self.ecs.simple_npc_knowledge_borrow(id).expect("Missing component simple_npc_knowledge")
+            .level(SQUAD_LEVEL_ID).targets()
+    }
+}
+
+#[test]
+fn squad_members_receive_shared_targets() {
+    let squads = Squads::new();
+    squads.share(Coord::new(6, 3), 7);
+
+    assert_eq!(squads.targets(squads.member_id), vec![Coord::new(6, 3)]);
+}
+
+#[test]
+fn other_squads_and_sharer_are_not_told() {
+    let squads = Squads::new();
+    squads.share(Coord::new(6, 3), 7);
+
+    assert_eq!(squads.targets(squads.outsider_id), vec![OLD_TARGET]);
+    assert_eq!(squads.targets(squads.sharer_id), vec![OLD_TARGET]);
+}
+
+#[test]
+fn targets_shared_in_same_action_are_combined() {
+    let squads = Squads::new();
+    squads.share(Coord::new(6, 3), 5);
+
+    let targets = squads.targets(squads.member_id);
+    assert_eq!(targets.len(), 2);
+    assert!(targets.contains(&OLD_TARGET));
+    assert!(targets.contains(&Coord::new(6, 3)));
+}
+
+#[test]
+fn stale_targets_do_not_replace_newer_ones() {
+    let squads = Squads::new();
+    squads.share(Coord::new(6, 3), 4);
+
+    assert_eq!(squads.targets(squads.member_id), vec![OLD_TARGET]);
+}
+
+/// What a vehicle knows of a level containing wrecks, barrels and acid at
+/// the given cells
+fn road(wrecks: &[Coord], barrels: &[Coord], acid: &[Coord]) -> SimpleNpcKnowledgeLevel {
+    const ACTION_ID: u64 = 1;
+
+    let ids = EntityIdReserver::new();
+    let rng = GameRng::new(0);
+    let prototypes = prototypes();
+    let mut sh = SpatialHashTable::new(WIDTH, HEIGHT);
+    let mut ecs = EcsCtx::new();
+    let mut knowledge = SimpleNpcKnowledgeLevel::new(WIDTH, HEIGHT);
+
+    let mut action = EcsAction::new();
+    for coord in wrecks {
+        prototypes::wreck(action.entity_mut(ids.new_id()), *coord, &rng, &prototypes);
+    }
+    for coord in barrels {
+        prototypes::barrel(action.entity_mut(ids.new_id()), *coord, &prototypes);
+    }
+    for coord in acid {
+        prototypes::acid(action.entity_mut(ids.new_id()), *coord, &rng);
+    }
+    sh.update(&ecs, &action, ACTION_ID);
+    ecs.commit(&mut action);
+
+    let action_env = ActionEnv::new(&ecs, ACTION_ID);
+    for y in 0..HEIGHT as isize {
+        for x in 0..WIDTH as isize {
+            let coord = Coord::new(x, y);
+            knowledge.update_cell(coord, sh.get(coord), 1.0, action_env);
+        }
+    }
+
+    knowledge
+}
+
+/// Route of a slow vehicle at (2, 5) which prefers to stay in its row
+fn route(level: &SimpleNpcKnowledgeLevel) -> Route {
+    plan_route(&GridSearchCtx::new(), level, Coord::new(2, 5), 5, 1)
+}
+
+#[test]
+fn route_drives_straight_on_clear_road() {
+    assert_eq!(route(&road(&[], &[], &[])), Route::Straight);
+}
+
+#[test]
+fn route_steers_around_wreck() {
+    let route = route(&road(&[Coord::new(3, 5)], &[], &[]));
+
+    assert!(route == Route::Steer(SteerDirection::Up) || route == Route::Steer(SteerDirection::Down));
+}
+
+#[test]
+fn route_steers_around_barrel() {
+    let route = route(&road(&[], &[Coord::new(3, 5)], &[]));
+
+    assert!(route == Route::Steer(SteerDirection::Up) || route == Route::Steer(SteerDirection::Down));
+}
+
+#[test]
+fn route_avoids_acid_with_clean_lane() {
+    let acid = (3..WIDTH as isize).flat_map(|x| vec![Coord::new(x, 5), Coord::new(x, 6)]).collect::<Vec<_>>();
+
+    assert_eq!(route(&road(&[], &[], &acid)), Route::Steer(SteerDirection::Up));
+}
+
+#[test]
+fn route_is_blocked_when_every_lane_is_solid() {
+    let wrecks = (0..HEIGHT as isize).map(|y| Coord::new(4, y)).collect::<Vec<_>>();
+
+    assert_eq!(route(&road(&wrecks, &[], &[])), Route::Blocked);
+}
@@ -1,4 +1,4 @@
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum BehaviourType {
     Null,
     PlayerInput,
@@ -8,3 +8,20 @@ pub enum BehaviourType {
     Car,
     Bike,
 }
+
+impl BehaviourType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Null" => BehaviourType::Null,
+            "PlayerInput" => BehaviourType::PlayerInput,
+            "Zombie" => BehaviourType::Zombie,
+            "AcidAnimate" => BehaviourType::AcidAnimate,
+            "Physics" => BehaviourType::Physics,
+            "Car" => BehaviourType::Car,
+            "Bike" => BehaviourType::Bike,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
@@ -0,0 +1,92 @@
+use std::cmp;
+
+use game::*;
+use game::data::*;
+use search::{GridSearchCfg, GridSearchCtx, GridPath, TraverseCost};
+use direction::Direction;
+use coord::Coord;
+use grid::Grid;
+
+/// Vehicles face east, so each cell they move into is either straight
+/// ahead or in the lane above or below after steering
+static VEHICLE_DIRECTIONS: [Direction; 3] = [
+    Direction::East,
+    Direction::NorthEast,
+    Direction::SouthEast,
+];
+
+/// Number of turns of driving to plan for
+const TURNS_AHEAD: usize = 3;
+
+/// Stopped and slow vehicles still look this many cells ahead
+const MIN_LOOKAHEAD: usize = 4;
+
+/// Extra cost of changing lane for each point of speed, since a fast
+/// vehicle covers more ground before it can steer again
+const STEER_COST_PER_SPEED: f64 = 0.5;
+
+/// Cost per cell of being out of the preferred row
+const ROW_COST: f64 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Route {
+    Straight,
+    Steer(SteerDirection),
+    /// No known way forward
+    Blocked,
+}
+
+/// Plans a route a few turns ahead of a vehicle which avoids anything
+/// solid it knows about, preferring to drive in `preferred_row`. Returns
+/// how the vehicle should steer this turn to follow the route.
+pub fn plan_route(search_ctx: &GridSearchCtx, level: &SimpleNpcKnowledgeLevel,
+                  position: Coord, preferred_row: isize, speed: usize) -> Route {
+
+    let grid = level.grid();
+    let lookahead = cmp::max(speed * TURNS_AHEAD, MIN_LOOKAHEAD) as isize;
+    let horizon = cmp::min(position.x + lookahead, grid.x_max());
+
+    if horizon <= position.x {
+        // at the end of the level
+        return Route::Straight;
+    }
+
+    let steer_cost = STEER_COST_PER_SPEED * speed as f64;
+    let config = GridSearchCfg { directions: &VEHICLE_DIRECTIONS };
+    let mut path = GridPath::new();
+
+    let result = search_ctx.search_predicate_cost(
+        grid, position,
+        |info| info.coord.x >= horizon,
+        |info, direction| {
+            let cost = if let Some(cost) = info.cell.traverse_cost() {
+                cost * direction.multiplier()
+            } else {
+                return None;
+            };
+
+            let cost = if direction == Direction::East {
+                cost
+            } else {
+                // steering moves the vehicle sideways before it drives forward
+                let beside = info.coord - Direction::East.vector();
+                if !level.get_with_default(beside).is_traversable() {
+                    return None;
+                }
+                cost + steer_cost
+            };
+
+            Some(cost + (info.coord.y - preferred_row).abs() as f64 * ROW_COST)
+        },
+        &config, &mut path);
+
+    if result.is_err() {
+        return Route::Blocked;
+    }
+
+    match path.get_node(0).map(|node| node.direction_to) {
+        Some(Direction::NorthEast) => Route::Steer(SteerDirection::Up),
+        Some(Direction::SouthEast) => Route::Steer(SteerDirection::Down),
+        _ => Route::Straight,
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::{hash_map, HashMap};
 use std::slice;
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
 
 use game::InputEvent;
 use game::control_spec::ControlSpec;
@@ -14,9 +15,12 @@ pub enum Control {
     DisplayMessageLog,
     Pause,
     Status,
+    Reload,
+    Target,
+    Examine,
 }
 
-const NUM_CONTROLS: usize = 10;
+const NUM_CONTROLS: usize = 13;
 const CONTROL_ORDER: [Control; NUM_CONTROLS] = [
     Control::Direction(Direction::North),
     Control::Direction(Direction::South),
@@ -24,8 +28,11 @@ const CONTROL_ORDER: [Control; NUM_CONTROLS] = [
     Control::Direction(Direction::West),
     Control::Wait,
     Control::Fire,
+    Control::Target,
+    Control::Reload,
     Control::Inventory,
     Control::Status,
+    Control::Examine,
     Control::DisplayMessageLog,
     Control::Pause,
 ];
@@ -40,9 +47,20 @@ impl<'a> Iterator for ControlMapIter<'a> {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControlMap {
+    #[serde(serialize_with = "serialize_bindings", deserialize_with = "deserialize_bindings")]
     map: HashMap<InputEvent, Control>,
 }
 
+// Bindings are serialized as a sequence of pairs rather than a map, as json
+// only allows strings as map keys. Bincode encodes both the same way.
+fn serialize_bindings<S: Serializer>(map: &HashMap<InputEvent, Control>, serializer: S) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<Vec<_>>().serialize(serializer)
+}
+
+fn deserialize_bindings<D: Deserializer>(deserializer: D) -> Result<HashMap<InputEvent, Control>, D::Error> {
+    Vec::<(InputEvent, Control)>::deserialize(deserializer).map(|pairs| pairs.into_iter().collect())
+}
+
 impl ControlMap {
     pub fn get(&self, event: InputEvent) -> Option<Control> {
         self.map.get(&event).map(|r| *r)
@@ -78,6 +96,12 @@ impl ControlMap {
 
         self.insert(InputEvent::Char('f'), Control::Fire);
 
+        self.insert(InputEvent::Char('a'), Control::Target);
+
+        self.insert(InputEvent::Char('r'), Control::Reload);
+
+        self.insert(InputEvent::Char('x'), Control::Examine);
+
         self.insert(InputEvent::Char('t'), Control::DisplayMessageLog);
     }
 
@@ -102,8 +102,11 @@ impl<'a> From<&'a String> for Control {
             "Decelerate" => Control::Direction(Direction::West),
             "Wait" => Control::Wait,
             "Fire" => Control::Fire,
+            "Target" => Control::Target,
+            "Reload" => Control::Reload,
             "Inventory" => Control::Inventory,
             "Status" => Control::Status,
+            "Examine" => Control::Examine,
             "DisplayMessageLog" => Control::DisplayMessageLog,
             "Pause" => Control::Pause,
             _ => panic!("No such control: {}", s),
@@ -120,8 +123,11 @@ impl From<Control> for String {
             Control::Direction(Direction::West) => "Decelerate",
             Control::Wait => "Wait",
             Control::Fire => "Fire",
+            Control::Target => "Target",
+            Control::Reload => "Reload",
             Control::Inventory => "Inventory",
             Control::Status => "Status",
+            Control::Examine => "Examine",
             Control::DisplayMessageLog => "DisplayMessageLog",
             Control::Pause => "Pause",
             _ => panic!("Unencodable control: {:?}", control),
@@ -145,25 +151,29 @@ impl From<InputEvent> for String {
     }
 }
 
-impl<'a> From<&'a String> for InputEvent {
-    fn from(s: &'a String) -> Self {
-        match s.as_ref() {
-            "Up" => return InputEvent::Up,
-            "Down" => return InputEvent::Down,
-            "Left" => return InputEvent::Left,
-            "Right" => return InputEvent::Right,
-            "Escape" => return InputEvent::Escape,
-            "Return" => return InputEvent::Return,
-            "Space" => return InputEvent::Space,
+impl InputEvent {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "Up" => return Some(InputEvent::Up),
+            "Down" => return Some(InputEvent::Down),
+            "Left" => return Some(InputEvent::Left),
+            "Right" => return Some(InputEvent::Right),
+            "Escape" => return Some(InputEvent::Escape),
+            "Return" => return Some(InputEvent::Return),
+            "Space" => return Some(InputEvent::Space),
             _ => (),
         }
 
-        if s.len() == 1 {
-            if let Some(c) = s.chars().next() {
-                return InputEvent::Char(c);
-            }
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(InputEvent::Char(c)),
+            _ => None,
         }
+    }
+}
 
-        panic!("No such input: {}", s);
+impl<'a> From<&'a String> for InputEvent {
+    fn from(s: &'a String) -> Self {
+        InputEvent::from_str(s.as_ref()).unwrap_or_else(|| panic!("No such input: {}", s))
     }
 }
@@ -1,6 +1,7 @@
 use std::cell::RefCell;
 use std::ops::DerefMut;
-use rand::Rng;
+use std::path;
+use std::cmp;
 
 use game::*;
 use game::data::*;
@@ -9,6 +10,11 @@ use util::{LeakyReserver, Schedule};
 use coord::Coord;
 use direction::Direction;
 
+const MIN_ROUTES: usize = 2;
+const MAX_ROUTES: usize = 3;
+const MAX_ROUTE_RISK: usize = 2;
+const ROUTE_TERRAIN_TYPES: [TerrainType; 2] = [TerrainType::Road, TerrainType::City];
+
 pub struct EntityIdReserver(RefCell<LeakyReserver<EntityId>>);
 
 impl EntityIdReserver {
@@ -41,11 +47,20 @@ enum MainMenuSelection {
     Quit,
     Continue,
     SaveAndQuit,
+    SavedGames,
     Controls,
 }
 
+enum SaveSlotSelection {
+    Load,
+    Save,
+    Delete,
+    Back,
+}
+
 pub enum GameOverReason {
     PlayerDied,
+    OutOfFuel,
 }
 
 pub enum ExitReason {
@@ -62,6 +77,7 @@ pub enum BetwenLevelsResolution {
 
 pub enum BetweenLevelsSelection {
     NextDelivery,
+    Contracts,
     Shop,
     Garage,
     Inventory,
@@ -82,11 +98,30 @@ enum WeaponMenuError {
     InventoryFull,
 }
 
+enum GarageSelection {
+    WeaponSlot(Direction),
+    CargoHold,
+    Upgrades,
+}
+
+enum UpgradeError {
+    CantAfford,
+    Locked,
+    Owned,
+}
+
+enum CargoError {
+    CargoFull,
+    InventoryFull,
+}
+
 pub struct GameCtx<Renderer: KnowledgeRenderer, Input: InputSource> {
     renderer: RefCell<Renderer>,
     input_source: Input,
     pc_observer: Shadowcast,
     behaviour_ctx: BehaviourCtx<Renderer>,
+    rules: RuleRegistry,
+    rule_trace: bool,
     rule_reactions: Vec<Reaction>,
     ecs_action: EcsAction,
     action_schedule: Schedule<ActionArgs>,
@@ -94,6 +129,8 @@ pub struct GameCtx<Renderer: KnowledgeRenderer, Input: InputSource> {
     height: usize,
     rng: GameRng,
     language: Box<Language>,
+    prototypes: PrototypeTable,
+    economy: Economy,
 }
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
@@ -112,6 +149,20 @@ pub struct GameState {
     between_levels: bool,
     staging: EcsCtx,
     staged: Option<EntityId>,
+    /// Routes offered for the next delivery
+    routes: Vec<Route>,
+    /// Route of the current delivery, if one was chosen
+    route: Option<Route>,
+    /// Contracts offered for the next delivery
+    contracts: Vec<Contract>,
+    /// Contract accepted for the next or current delivery
+    contract: Option<Contract>,
+    /// How the contract of the last delivery turned out
+    contract_outcome: Option<ContractOutcome>,
+    /// Ids of the upgrades bought in the garage
+    upgrades: Vec<String>,
+    /// Turns the player has taken since the current delivery started
+    delivery_turns: u64,
 }
 
 impl GameState {
@@ -125,8 +176,63 @@ impl GameState {
             between_levels: false,
             staging: EcsCtx::new(),
             staged: None,
+            routes: Vec::new(),
+            route: None,
+            contracts: Vec::new(),
+            contract: None,
+            contract_outcome: None,
+            upgrades: Vec::new(),
+            delivery_turns: 0,
+        }
+    }
+
+    /// Behaviour states refer to nodes of the behaviour graph, which is
+    /// rebuilt rather than saved, so a loaded game restarts every behaviour
+    fn reset_behaviour_states(ecs: &mut EcsCtx) {
+        let ids = ecs.behaviour_type_iter().map(|(id, _)| id).collect::<Vec<_>>();
+        for id in ids {
+            ecs.insert_behaviour_state(id, BehaviourState::new());
         }
     }
+
+    pub fn reset_behaviour(&mut self) {
+        for level in self.levels.iter_mut() {
+            Self::reset_behaviour_states(&mut level.ecs);
+        }
+        Self::reset_behaviour_states(&mut self.staging);
+    }
+
+    /// The player's id, and the ecs containing the player
+    fn pc_ecs(&self) -> Option<(EntityId, &EcsCtx)> {
+        self.global_ids.map(|GlobalIds { pc_id, level_id, .. }| {
+            // between deliveries the player lives in the staging area
+            let ecs = if self.staged.is_some() {
+                &self.staging
+            } else {
+                &self.levels.level(level_id).ecs
+            };
+
+            (pc_id, ecs)
+        })
+    }
+
+    /// Adds a message to the player's message log, if there is a player
+    pub fn add_message(&self, message: MessageType) {
+        if let Some((pc_id, ecs)) = self.pc_ecs() {
+            ecs.message_log_borrow_mut(pc_id).expect("Missing component message_log").add(message);
+        }
+    }
+
+    pub fn metadata(&self, timestamp: u64) -> Option<SaveMetadata> {
+        self.pc_ecs().map(|(pc_id, ecs)| {
+            SaveMetadata {
+                delivery: level_id + 1,
+                bank: ecs.bank(pc_id).expect("Missing component bank"),
+                letters: ecs.letter_count(pc_id).expect("Missing component letter_count"),
+                timestamp: timestamp,
+            }
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -139,11 +245,19 @@ pub struct SerializableGameState {
     between_levels: bool,
     staging: SerializableEcsCtx,
     staged: Option<EntityId>,
+    routes: Vec<Route>,
+    route: Option<Route>,
+    contracts: Vec<Contract>,
+    contract: Option<Contract>,
+    contract_outcome: Option<ContractOutcome>,
+    upgrades: Vec<String>,
+    delivery_turns: u64,
 }
 
 impl From<GameState> for SerializableGameState {
     fn from(game_state: GameState) -> Self {
-        let GameState { levels, global_ids, entity_ids, turn_id, action_id, between_levels, staging, staged } = game_state;
+        let GameState { levels, global_ids, entity_ids, turn_id, action_id, between_levels, staging, staged,
+                        routes, route, contracts, contract, contract_outcome, upgrades, delivery_turns } = game_state;
         SerializableGameState {
             levels: SerializableLevelTable::from(levels),
             global_ids: global_ids,
@@ -153,13 +267,21 @@ impl From<GameState> for SerializableGameState {
             between_levels: between_levels,
             staging: SerializableEcsCtx::from(staging),
             staged: staged,
+            routes: routes,
+            route: route,
+            contracts: contracts,
+            contract: contract,
+            contract_outcome: contract_outcome,
+            upgrades: upgrades,
+            delivery_turns: delivery_turns,
         }
     }
 }
 
 impl From<SerializableGameState> for GameState {
     fn from(game_state: SerializableGameState) -> Self {
-        let SerializableGameState { levels, global_ids, entity_ids, turn_id, action_id, between_levels, staging, staged } = game_state;
+        let SerializableGameState { levels, global_ids, entity_ids, turn_id, action_id, between_levels, staging, staged,
+                                    routes, route, contracts, contract, contract_outcome, upgrades, delivery_turns } = game_state;
         GameState {
             levels: LevelTable::from(levels),
             global_ids: global_ids,
@@ -169,48 +291,26 @@ impl From<SerializableGameState> for GameState {
             between_levels: between_levels,
             staging: EcsCtx::from(staging),
             staged: staged,
+            routes: routes,
+            route: route,
+            contracts: contracts,
+            contract: contract,
+            contract_outcome: contract_outcome,
+            upgrades: upgrades,
+            delivery_turns: delivery_turns,
         }
     }
 }
 
-#[derive(Clone, Copy)]
-enum ShopItemType {
-    Pistol,
-    Shotgun,
-    MachineGun,
-    Railgun,
-    EngineRepair,
-    TyresRepair,
-    Armour(usize),
-    SpareTyre,
-    EngineRepairKit,
-}
-
-const RANDOM_SHOP_ITEM_TYPES: [ShopItemType; 12] = [
-    ShopItemType::Pistol,
-    ShopItemType::Shotgun,
-    ShopItemType::MachineGun,
-    ShopItemType::Railgun,
-    ShopItemType::EngineRepair,
-    ShopItemType::TyresRepair,
-    ShopItemType::Armour(1),
-    ShopItemType::Armour(2),
-    ShopItemType::Armour(3),
-    ShopItemType::Armour(4),
-    ShopItemType::SpareTyre,
-    ShopItemType::EngineRepairKit,
-];
-const RANDOM_SHOP_ITEM_WEIGHTS: [usize; 12] = [10, 9, 8, 7, 8, 8, 8, 6, 4, 2, 6, 6];
-const SHOP_MAX_ITEMS: usize = 12;
-const SHOP_MIN_ITEMS: usize = 6;
-
 impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<Renderer, Input> {
-    pub fn new(renderer: Renderer, input_source: Input, seed: usize, width: usize, height: usize) -> Self {
+    pub fn new(renderer: Renderer, input_source: Input, prototypes: PrototypeTable, economy: Economy, seed: usize, width: usize, height: usize) -> Self {
         GameCtx {
             renderer: RefCell::new(renderer),
             input_source: input_source.clone(),
             pc_observer: Shadowcast::new(),
             behaviour_ctx: BehaviourCtx::new(input_source),
+            rules: RuleRegistry::standard(),
+            rule_trace: false,
             rule_reactions: Vec::new(),
             ecs_action: EcsAction::new(),
             action_schedule: Schedule::new(),
@@ -218,12 +318,25 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
             height: height,
             rng: GameRng::new(seed),
             language: Box::new(languages::English),
+            prototypes: prototypes,
+            economy: economy,
         }
     }
 
+    /// Rules checked against every action, which may be changed before the
+    /// game is run to add, remove or reorder rules
+    pub fn rules_mut(&mut self) -> &mut RuleRegistry {
+        &mut self.rules
+    }
+
     pub fn run(&mut self, args: Arguments) -> GameResult<()> {
 
-        let mut current_game_state = save_file::load(args.user_path.as_path());
+        // trace rule checks to the debug writer
+        self.rule_trace = args.debug;
+
+        let mut current_slot = save_file::most_recent_slot(args.user_path.as_path()).unwrap_or(0);
+        // a save which can't be loaded is listed as unreadable in the saved games menu
+        let mut current_game_state = save_file::load(args.user_path.as_path(), current_slot).unwrap_or(None);
         let mut current_menu_state = None;
 
         loop {
@@ -239,6 +352,7 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
             }
 
             menu.push(SelectMenuItem::new(MenuMessageType::NewGame, MainMenuSelection::NewGame));
+            menu.push(SelectMenuItem::new(MenuMessageType::SavedGames, MainMenuSelection::SavedGames));
             menu.push(SelectMenuItem::new(MenuMessageType::Controls, MainMenuSelection::Controls));
 
             if current_game_state.is_some() {
@@ -259,10 +373,16 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                     current_menu_state,
                     None);
 
-                if current_game_state.is_some() {
-                    menu_op.run_can_escape().unwrap_or((MainMenuSelection::Continue, SelectMenuState::new()))
+                let quit = if current_game_state.is_some() {
+                    MainMenuSelection::SaveAndQuit
                 } else {
-                    menu_op.run()
+                    MainMenuSelection::Quit
+                };
+
+                match menu_op.run_resolution(current_game_state.is_some()) {
+                    SelectMenuResolution::Select(item, menu_state) => (item, menu_state),
+                    SelectMenuResolution::Escape => (MainMenuSelection::Continue, SelectMenuState::new()),
+                    SelectMenuResolution::Quit => (quit, SelectMenuState::new()),
                 }
             };
 
@@ -274,10 +394,23 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                 }
                 MainMenuSelection::SaveAndQuit => {
                     let game_state = current_game_state.take().expect("Missing game state");
-                    save_file::save(args.user_path.as_path(), game_state);
-                    return Ok(());
+                    let (game_state, result) = save_file::save(args.user_path.as_path(), current_slot, game_state);
+                    if result.is_ok() {
+                        return Ok(());
+                    }
+
+                    // resume the game so the player sees the failure in the
+                    // message log rather than losing their progress
+                    game_state
                 }
                 MainMenuSelection::NewGame => {
+                    if let Some(slot) = self.new_game_slot(args.user_path.as_path()) {
+                        current_slot = slot;
+                    } else {
+                        current_menu_state = Some(menu_state);
+                        continue;
+                    }
+
                     let mut game_state = GameState::new();
 
                     self.init_demo(&mut game_state);
@@ -285,6 +418,16 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                     game_state
                 }
                 MainMenuSelection::Continue => current_game_state.take().expect("Missing game state"),
+                MainMenuSelection::SavedGames => {
+                    if let Some(game_state) = self.saved_games_menu(args.user_path.as_path(),
+                                                                    &mut current_slot,
+                                                                    &mut current_game_state) {
+                        game_state
+                    } else {
+                        current_menu_state = Some(menu_state);
+                        continue;
+                    }
+                }
                 MainMenuSelection::Controls => {
                     self.configure_controls(&mut control_map);
                     control_file::to_file(args.user_path.join(user_files::CONTROL), &control_map);
@@ -297,24 +440,30 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
 
             loop {
                 self.renderer.borrow_mut().reset_buffers();
-                match self.game_loop(&mut game_state)? {
+                match self.game_loop(&mut game_state, args.user_path.as_path(), current_slot)? {
                     ExitReason::Pause => {
                         current_game_state = Some(game_state);
                         break;
                     }
                     ExitReason::Quit => {
-                        save_file::save(args.user_path.as_path(), game_state);
+                        // there's nothing to return to once the player has
+                        // quit, so a failed save is only noted in the log
+                        save_file::save(args.user_path.as_path(), current_slot, game_state);
                         return Ok(());
                     }
                     ExitReason::GameOver(reason) => {
                         match reason {
                             GameOverReason::PlayerDied => {
-                                self.death_message(&game_state);
+                                self.game_over_message(&game_state, MessageType::YouDied);
+                                self.input_source.next_input();
+                            }
+                            GameOverReason::OutOfFuel => {
+                                self.game_over_message(&game_state, MessageType::YouRanOutOfFuel);
                                 self.input_source.next_input();
                             }
                         }
                         current_game_state = None;
-                        save_file::delete(args.user_path.as_path());
+                        save_file::delete(args.user_path.as_path(), current_slot);
                         break;
                     }
                     ExitReason::BetweenLevels => {
@@ -334,6 +483,132 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
         }
     }
 
+    fn save_slot_message(user_path: &path::Path, slot: usize) -> MenuMessageType {
+        match save_file::slot_metadata(user_path, slot) {
+            Ok(Some(metadata)) => MenuMessageType::SaveSlot(slot, metadata),
+            Ok(None) => MenuMessageType::EmptySaveSlot(slot),
+            Err(_) => MenuMessageType::UnreadableSaveSlot(slot),
+        }
+    }
+
+    /// The slot to save a new game in. When every slot is in use, the player
+    /// chooses a game to overwrite, or `None` if they change their mind.
+    fn new_game_slot(&mut self, user_path: &path::Path) -> Option<usize> {
+        if let Some(slot) = save_file::first_empty_slot(user_path) {
+            return Some(slot);
+        }
+
+        let mut menu = SelectMenu::new();
+
+        for slot in 0..save_file::NUM_SAVE_SLOTS {
+            menu.push(SelectMenuItem::new(Self::save_slot_message(user_path, slot), slot));
+        }
+
+        SelectMenuOperation::new(
+            self.renderer.borrow_mut().deref_mut(),
+            &mut self.input_source,
+            Some(MessageType::OverwriteSaveSlot),
+            &self.language,
+            menu,
+            None,
+            None).run_can_escape().map(|(slot, _)| slot)
+    }
+
+    fn saved_games_menu(&mut self,
+                        user_path: &path::Path,
+                        current_slot: &mut usize,
+                        current_game_state: &mut Option<GameState>) -> Option<GameState> {
+
+        let mut current_menu_state = None;
+        let mut load_failed = None;
+
+        loop {
+            let mut menu = SelectMenu::new();
+            let mut readable = Vec::new();
+
+            for slot in 0..save_file::NUM_SAVE_SLOTS {
+                let message = Self::save_slot_message(user_path, slot);
+
+                readable.push(match message {
+                    MenuMessageType::SaveSlot(..) => true,
+                    _ => false,
+                });
+
+                menu.push(SelectMenuItem::new(message, slot));
+            }
+
+            let title = match load_failed.take() {
+                Some(slot) => MessageType::SavedGamesLoadFailed(slot),
+                None => MessageType::SavedGames,
+            };
+
+            let maybe_slot = SelectMenuOperation::new(
+                self.renderer.borrow_mut().deref_mut(),
+                &mut self.input_source,
+                Some(title),
+                &self.language,
+                menu,
+                current_menu_state,
+                None).run_can_escape();
+
+            let slot = if let Some((slot, menu_state)) = maybe_slot {
+                current_menu_state = Some(menu_state);
+                slot
+            } else {
+                return None;
+            };
+
+            let exists = save_file::slot_metadata(user_path, slot).map(|m| m.is_some()).unwrap_or(true);
+
+            let mut menu = SelectMenu::new();
+
+            if readable[slot] {
+                menu.push(SelectMenuItem::new(MenuMessageType::Load, SaveSlotSelection::Load));
+            }
+            if current_game_state.is_some() {
+                menu.push(SelectMenuItem::new(MenuMessageType::Save, SaveSlotSelection::Save));
+            }
+            if exists {
+                menu.push(SelectMenuItem::new(MenuMessageType::Delete, SaveSlotSelection::Delete));
+            }
+            menu.push(SelectMenuItem::new(MenuMessageType::Back, SaveSlotSelection::Back));
+
+            let maybe_selection = SelectMenuOperation::new(
+                self.renderer.borrow_mut().deref_mut(),
+                &mut self.input_source,
+                Some(MessageType::SaveSlotTitle(slot)),
+                &self.language,
+                menu,
+                None,
+                None).run_can_escape();
+
+            match maybe_selection.map(|(selection, _)| selection) {
+                Some(SaveSlotSelection::Load) => {
+                    match save_file::load(user_path, slot) {
+                        Ok(Some(game_state)) => {
+                            *current_slot = slot;
+                            return Some(game_state);
+                        }
+                        Ok(None) => {}
+                        Err(_) => load_failed = Some(slot),
+                    }
+                }
+                Some(SaveSlotSelection::Save) => {
+                    let game_state = current_game_state.take().expect("Missing game state");
+                    let (game_state, result) = save_file::save(user_path, slot, game_state);
+                    *current_game_state = Some(game_state);
+                    if result.is_ok() {
+                        *current_slot = slot;
+                    }
+                }
+                Some(SaveSlotSelection::Delete) => {
+                    save_file::delete(user_path, slot);
+                }
+                Some(SaveSlotSelection::Back) | None => {}
+            }
+        }
+    }
+
     fn between_levels_menu(&mut self, game_state: &mut GameState) -> BetwenLevelsResolution {
         let GlobalIds { pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
         let mut current_menu_state = None;
@@ -341,15 +616,21 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
         loop {
             let mut menu = SelectMenu::new();
 
+            menu.push(SelectMenuItem::new(MenuMessageType::Contracts, BetweenLevelsSelection::Contracts));
             menu.push(SelectMenuItem::new(MenuMessageType::Shop, BetweenLevelsSelection::Shop));
             menu.push(SelectMenuItem::new(MenuMessageType::Garage, BetweenLevelsSelection::Garage));
             menu.push(SelectMenuItem::new(MenuMessageType::Inventory, BetweenLevelsSelection::Inventory));
             menu.push(SelectMenuItem::new(MenuMessageType::NextDelivery, BetweenLevelsSelection::NextDelivery));
 
+            let title = match game_state.contract_outcome {
+                Some(outcome) => MessageType::SurvivorCampContract(outcome),
+                None => MessageType::SurvivorCamp,
+            };
+
             let maybe_selection = SelectMenuOperation::new(
                 self.renderer.borrow_mut().deref_mut(),
                 &mut self.input_source,
-                Some(MessageType::SurvivorCamp),
+                Some(title),
                 &self.language,
                 menu,
                 current_menu_state,
@@ -363,6 +644,9 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                             return BetwenLevelsResolution::Start;
                         }
                     }
+                    BetweenLevelsSelection::Contracts => {
+                        self.contracts_menu(game_state);
+                    }
                     BetweenLevelsSelection::Shop => {
                         self.shop_menu(game_state);
                     }
@@ -379,6 +663,30 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
         }
     }
 
+    fn contracts_menu(&mut self, game_state: &mut GameState) {
+        let GlobalIds { pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
+
+        let mut menu = SelectMenu::new();
+
+        menu.push(SelectMenuItem::new(MenuMessageType::NoContract, None));
+        for contract in game_state.contracts.iter() {
+            menu.push(SelectMenuItem::new(MenuMessageType::Contract(*contract), Some(*contract)));
+        }
+
+        let maybe_selection = SelectMenuOperation::new(
+            self.renderer.borrow_mut().deref_mut(),
+            &mut self.input_source,
+            Some(MessageType::Contracts(game_state.contract)),
+            &self.language,
+            menu,
+            None,
+            Some(game_state.staging.entity(pc_id))).run_can_escape();
+
+        if let Some((selection, _)) = maybe_selection {
+            game_state.contract = selection;
+        }
+    }
+
     fn shop_menu(&mut self, game_state: &mut GameState) {
         let GlobalIds { shop_id, pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
         let mut buy_result = Ok(());
@@ -522,13 +830,17 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                 let mut menu = SelectMenu::new();
 
                 let weapon_slots = game_state.staging.weapon_slots_borrow(pc_id).expect("Expected component weapon_slots");
+                let extra_mounts = game_state.staging.extra_weapon_mounts(pc_id).unwrap_or(0);
 
-                for d in DIRECTIONS.iter() {
+                for d in DIRECTIONS.iter().chain(EXTRA_WEAPON_MOUNTS.iter().take(extra_mounts)) {
                     let maybe_name = weapon_slots.get(*d).and_then(|id| game_state.staging.name(*id));
                     let message = MenuMessageType::WeaponSlot(RelativeDirection::from(*d), maybe_name);
-                    menu.push(SelectMenuItem::new(message, *d));
+                    menu.push(SelectMenuItem::new(message, GarageSelection::WeaponSlot(*d)));
                 }
 
+                menu.push(SelectMenuItem::new(MenuMessageType::CargoHold, GarageSelection::CargoHold));
+                menu.push(SelectMenuItem::new(MenuMessageType::Upgrades, GarageSelection::Upgrades));
+
                 menu
             };
 
@@ -547,13 +859,220 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                 Some(game_state.staging.entity(pc_id))).run_can_escape();
 
             if let Some((selection, _)) = maybe_selection {
-                result = self.weapon_slot_menu(game_state, selection);
+                result = match selection {
+                    GarageSelection::WeaponSlot(slot) => self.weapon_slot_menu(game_state, slot),
+                    GarageSelection::CargoHold => {
+                        self.cargo_menu(game_state);
+                        Ok(())
+                    }
+                    GarageSelection::Upgrades => {
+                        self.upgrades_menu(game_state);
+                        Ok(())
+                    }
+                };
             } else {
                 break;
             }
         }
     }
 
+    /// Shows every upgrade as a tree, with each upgrade beneath the first
+    /// upgrade it requires
+    fn upgrades_menu(&mut self, game_state: &mut GameState) {
+        let GlobalIds { pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
+        let mut result = Ok(());
+        let mut current_menu_state = None;
+
+        loop {
+            let mut menu = SelectMenu::new();
+
+            for (index, node) in self.economy.upgrades().iter().enumerate() {
+                let message = MenuMessageType::Upgrade {
+                    upgrade: node.upgrade,
+                    depth: node.depth,
+                    status: node.status(&game_state.upgrades),
+                };
+                menu.push(SelectMenuItem::new(message, index));
+            }
+
+            let bank = game_state.staging.bank(pc_id).expect("Missing component bank");
+
+            let title = match result {
+                Ok(()) => MessageType::Upgrades(bank),
+                Err(UpgradeError::CantAfford) => MessageType::UpgradesInsufficientFunds(bank),
+                Err(UpgradeError::Locked) => MessageType::UpgradesLocked(bank),
+                Err(UpgradeError::Owned) => MessageType::UpgradesOwned(bank),
+            };
+
+            let maybe_selection = SelectMenuOperation::new(
+                self.renderer.borrow_mut().deref_mut(),
+                &mut self.input_source,
+                Some(title),
+                &self.language,
+                menu,
+                current_menu_state,
+                Some(game_state.staging.entity(pc_id))).run_can_escape();
+
+            if let Some((index, menu_state)) = maybe_selection {
+                result = self.buy_upgrade(game_state, index);
+                current_menu_state = Some(menu_state);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn buy_upgrade(&self, game_state: &mut GameState, index: usize) -> Result<(), UpgradeError> {
+        let GlobalIds { pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
+        let node = &self.economy.upgrades()[index];
+
+        match node.status(&game_state.upgrades) {
+            UpgradeStatus::Owned => return Err(UpgradeError::Owned),
+            UpgradeStatus::Locked => return Err(UpgradeError::Locked),
+            UpgradeStatus::Available => {}
+        }
+
+        let bank = game_state.staging.bank(pc_id).expect("Missing component bank");
+        if node.upgrade.price > bank {
+            return Err(UpgradeError::CantAfford);
+        }
+
+        game_state.staging.insert_bank(pc_id, bank - node.upgrade.price);
+        game_state.upgrades.push(node.id.clone());
+
+        let amount = node.upgrade.amount;
+        let staging = &mut game_state.staging;
+
+        match node.upgrade.upgrade_type {
+            UpgradeType::Engine => {
+                let mut engine_health = staging.engine_health(pc_id).expect("Missing component engine_health");
+                engine_health.raise_max(amount * ENGINE_HEALTH_PER_SPEED);
+                staging.insert_engine_health(pc_id, engine_health);
+
+                let redline_speed = staging.redline_speed(pc_id).expect("Missing component redline_speed");
+                staging.insert_redline_speed(pc_id, redline_speed + amount);
+            }
+            UpgradeType::Tyres => {
+                let mut tyre_health = staging.tyre_health(pc_id).expect("Missing component tyre_health");
+                tyre_health.raise_max(amount);
+                staging.insert_tyre_health(pc_id, tyre_health);
+            }
+            UpgradeType::WeaponMounts => {
+                let extra_mounts = staging.extra_weapon_mounts(pc_id).unwrap_or(0) + amount;
+                staging.insert_extra_weapon_mounts(pc_id, cmp::min(extra_mounts, EXTRA_WEAPON_MOUNTS.len()));
+            }
+            UpgradeType::Inventory => {
+                let capacity = staging.inventory_capacity(pc_id).expect("Missing component inventory_capacity");
+                staging.insert_inventory_capacity(pc_id, capacity + amount);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lists the items in the cargo hold, which can be unloaded into the
+    /// inventory, followed by the items in the inventory which can be loaded
+    fn cargo_menu(&mut self, game_state: &mut GameState) {
+        let GlobalIds { pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
+        let mut result = Ok(());
+        let mut current_menu_state = None;
+
+        loop {
+            let menu = {
+                let mut menu = SelectMenu::new();
+
+                let cargo = game_state.staging.cargo_borrow(pc_id).expect("Missing component cargo");
+                for entity_id in cargo.iter() {
+                    let name = game_state.staging.name(entity_id).expect("Missing component name");
+                    let weight = game_state.staging.weight(entity_id).expect("Missing component weight");
+                    menu.push(SelectMenuItem::new(MenuMessageType::Unload(name, weight), entity_id));
+                }
+
+                let inventory = game_state.staging.inventory_borrow(pc_id).expect("Missing component inventory");
+                for entity_id in inventory.iter() {
+                    if let Some(weight) = game_state.staging.weight(entity_id) {
+                        let name = game_state.staging.name(entity_id).expect("Missing component name");
+                        menu.push(SelectMenuItem::new(MenuMessageType::Load(name, weight), entity_id));
+                    }
+                }
+
+                menu
+            };
+
+            let weight = game_state.staging.entity(pc_id).cargo_weight();
+            let capacity = game_state.staging.cargo_capacity(pc_id).expect("Missing component cargo_capacity");
+
+            let title = match result {
+                Err(CargoError::CargoFull) => MessageType::CargoHoldFull { weight: weight, capacity: capacity },
+                Err(CargoError::InventoryFull) => MessageType::CargoHoldInventoryFull { weight: weight, capacity: capacity },
+                Ok(()) => MessageType::CargoHold { weight: weight, capacity: capacity },
+            };
+
+            let maybe_selection = SelectMenuOperation::new(
+                self.renderer.borrow_mut().deref_mut(),
+                &mut self.input_source,
+                Some(title),
+                &self.language,
+                menu,
+                current_menu_state,
+                Some(game_state.staging.entity(pc_id))).run_can_escape();
+
+            if let Some((item_id, menu_state)) = maybe_selection {
+                let in_cargo = game_state.staging.cargo_borrow(pc_id).expect("Missing component cargo").contains(item_id);
+                result = if in_cargo {
+                    self.unload_cargo(game_state, item_id)
+                } else {
+                    self.load_cargo(game_state, item_id)
+                };
+
+                // the selected item moves to the other part of the menu
+                current_menu_state = if result.is_ok() { None } else { Some(menu_state) };
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn load_cargo(&mut self, game_state: &mut GameState, item_id: EntityId) -> Result<(), CargoError> {
+        let GlobalIds { pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
+
+        let weight = game_state.staging.entity(pc_id).cargo_weight();
+        let capacity = game_state.staging.cargo_capacity(pc_id).expect("Missing component cargo_capacity");
+        let item_weight = game_state.staging.weight(item_id).expect("Missing component weight");
+
+        if weight + item_weight > capacity {
+            return Err(CargoError::CargoFull);
+        }
+
+        game_state.staging.inventory_borrow_mut(pc_id).expect("Missing component inventory").remove(item_id);
+        game_state.staging.cargo_borrow_mut(pc_id).expect("Missing component cargo").insert(item_id, item_weight);
+
+        // the extra weight may have lowered the van's top speed
+        let max_speed = game_state.staging.entity(pc_id).player_max_speed().expect("Missing components for max speed");
+        let current_speed = game_state.staging.current_speed(pc_id).expect("Missing component current_speed");
+        if current_speed > max_speed {
+            game_state.staging.insert_current_speed(pc_id, max_speed);
+        }
+
+        Ok(())
+    }
+
+    fn unload_cargo(&mut self, game_state: &mut GameState, item_id: EntityId) -> Result<(), CargoError> {
+        let GlobalIds { pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
+
+        let max_inventory = game_state.staging.inventory_capacity(pc_id).expect("Missing component inventory_capacity");
+        let current_inventory = game_state.staging.inventory_borrow(pc_id).expect("Missing component inventory").len();
+
+        if current_inventory >= max_inventory {
+            return Err(CargoError::InventoryFull);
+        }
+
+        game_state.staging.cargo_borrow_mut(pc_id).expect("Missing component cargo").remove(item_id);
+        game_state.staging.inventory_borrow_mut(pc_id).expect("Missing component inventory").insert(item_id);
+
+        Ok(())
+    }
+
     fn weapon_slot_menu(&mut self, game_state: &mut GameState, slot: Direction) -> Result<(), WeaponMenuError> {
         let GlobalIds { pc_id, .. } = game_state.global_ids.expect("Uninitialised game state");
 
@@ -732,76 +1251,96 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
     }
 
     fn next_level_menu(&mut self, game_state: &mut GameState) -> bool {
+        let GlobalIds { pc_id, level_id, .. } = game_state.global_ids.expect("Uninitialised game state");
 
-        self.unstage(TerrainType::Road, game_state);
+        // saves from before routes existed have none planned
+        if game_state.routes.is_empty() {
+            game_state.routes = self.plan_routes(level_id + 1);
+        }
 
-        true
-    }
+        let mut menu = SelectMenu::new();
+        for route in game_state.routes.iter() {
+            menu.push(SelectMenuItem::new(MenuMessageType::Route(*route), *route));
+        }
 
-    fn prepare_between_levels(&mut self, game_state: &mut GameState) {
-        let GlobalIds { pc_id, shop_id, .. } = game_state.global_ids.expect("Uninitialised game state");
+        let maybe_selection = SelectMenuOperation::new(
+            self.renderer.borrow_mut().deref_mut(),
+            &mut self.input_source,
+            Some(MessageType::WorldMap),
+            &self.language,
+            menu,
+            None,
+            Some(game_state.staging.entity(pc_id))).run_can_escape();
 
-        let mut rng_borrow = self.rng.inner_mut();
-        let mut rng = rng_borrow.deref_mut();
+        if let Some((route, _)) = maybe_selection {
+            game_state.routes.clear();
+            game_state.route = Some(route);
+            self.unstage(route, game_state);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Chooses the routes offered for the delivery after the given number
+    /// of deliveries
+    fn plan_routes(&self, delivery: usize) -> Vec<Route> {
+        let num_routes = MIN_ROUTES + self.rng.gen_usize_below(MAX_ROUTES - MIN_ROUTES + 1);
+        let payout = self.economy.payout();
+
+        (0..num_routes).map(|_| {
+            let risk = self.rng.gen_usize_below(MAX_ROUTE_RISK + 1);
+            Route {
+                terrain_type: *self.rng.select_uniform(&ROUTE_TERRAIN_TYPES),
+                risk: risk,
+                difficulty: delivery + risk,
+                bonus: payout.route_bonus(risk),
+            }
+        }).collect()
+    }
 
-        let total_shop_roll = RANDOM_SHOP_ITEM_WEIGHTS.iter().fold(0, |acc, &x| acc + x);
-        let num_shop_items = rng.gen::<usize>() % (SHOP_MAX_ITEMS - SHOP_MIN_ITEMS) + SHOP_MIN_ITEMS;
-        let mut inventory = EntitySet::new();
+    fn prepare_between_levels(&mut self, game_state: &mut GameState, user_path: &path::Path, slot: usize) {
+        let GlobalIds { pc_id, shop_id, level_id } = game_state.global_ids.expect("Uninitialised game state");
 
-        for _ in 0..num_shop_items {
-            let mut roll = rng.gen::<usize>() % total_shop_roll;
-            for (weight, item_type) in izip!(RANDOM_SHOP_ITEM_WEIGHTS.iter(), RANDOM_SHOP_ITEM_TYPES.iter()) {
-                if roll < *weight {
-                    let id = game_state.entity_ids.new_id();
-                    inventory.insert(id);
+        // level ids start at 0, so this counts the delivery just completed
+        let delivery = level_id + 1;
 
-                    match *item_type {
-                        ShopItemType::Pistol => {
-                            prototypes::pistol(game_state.staging.entity_mut(id));
-                        }
-                        ShopItemType::Shotgun => {
-                            prototypes::shotgun(game_state.staging.entity_mut(id));
-                        }
-                        ShopItemType::MachineGun => {
-                            prototypes::machine_gun(game_state.staging.entity_mut(id));
-                        }
-                        ShopItemType::Railgun => {
-                            prototypes::railgun(game_state.staging.entity_mut(id));
-                        }
-                        ShopItemType::EngineRepair => {
-                            prototypes::engine_repair(game_state.staging.entity_mut(id));
-                        }
-                        ShopItemType::TyresRepair => {
-                            prototypes::tyres_repair(game_state.staging.entity_mut(id));
-                        }
-                        ShopItemType::Armour(amount) => {
-                            prototypes::armour_upgrade(game_state.staging.entity_mut(id), amount);
-                        }
-                        ShopItemType::SpareTyre => {
-                            prototypes::spare_tyre(game_state.staging.entity_mut(id));
-                        }
-                        ShopItemType::EngineRepairKit => {
-                            prototypes::engine_repair_kit(game_state.staging.entity_mut(id));
-                        }
-                    }
+        let inventory = {
+            let mut rng_borrow = self.rng.inner_mut();
+            let rng = rng_borrow.deref_mut();
 
-                    break;
-                }
+            game_state.contracts = self.economy.offer_contracts(delivery, rng);
 
-                roll -= *weight;
-            }
-        }
+            self.economy.stock_shop(delivery,
+                                    &mut game_state.staging,
+                                    &game_state.entity_ids,
+                                    &self.prototypes,
+                                    rng)
+        };
 
         prototypes::shop(game_state.staging.entity_mut(shop_id), inventory);
 
+        let route_bonus = game_state.route.take().map(|route| route.bonus).unwrap_or(0);
+        game_state.routes = self.plan_routes(delivery);
+
+        let cargo_damage = game_state.staging.cargo_damage(pc_id).unwrap_or(0);
+        let delivery_turns = game_state.delivery_turns;
+        game_state.contract_outcome = game_state.contract.take().map(|contract| contract.outcome(delivery_turns, cargo_damage));
+        let contract_reward = game_state.contract_outcome.map(|outcome| outcome.reward()).unwrap_or(0);
+
         let bank = game_state.staging.bank(pc_id).expect("Missing component bank");
         let letter_count = game_state.staging.letter_count(pc_id).expect("Missing component letter_count");
-        game_state.staging.insert_bank(pc_id, bank + 20 + letter_count * 40);
+        let payout = self.economy.payout().amount(delivery, letter_count) + route_bonus + contract_reward;
+        game_state.staging.insert_bank(pc_id, bank + payout);
         game_state.staging.insert_letter_count(pc_id, 0);
         let mut hit_points = game_state.staging.hit_points(pc_id).expect("Missing component hit_points");
         hit_points.fill();
         game_state.staging.insert_hit_points(pc_id, hit_points);
         game_state.staging.insert_message_log(pc_id, MessageLog::new());
+
+        // resuming this save should return to the garage rather than the level just left
+        game_state.between_levels = true;
+        save_file::autosave(user_path, slot, game_state);
     }
 
     fn install_control_map(game_state: &mut GameState, control_map: ControlMap) {
@@ -811,7 +1350,7 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
         level.ecs.insert_control_map(pc_id, control_map);
     }
 
-    fn game_loop(&mut self, game_state: &mut GameState) -> GameResult<ExitReason> {
+    fn game_loop(&mut self, game_state: &mut GameState, user_path: &path::Path, slot: usize) -> GameResult<ExitReason> {
 
         if game_state.between_levels {
             return Ok(ExitReason::BetweenLevels);
@@ -837,7 +1376,10 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                         renderer: &self.renderer,
                         ecs: &mut level.ecs,
                         spatial_hash: &mut level.spatial_hash,
+                        weather: level.weather,
                         behaviour_ctx: &self.behaviour_ctx,
+                        rules: &self.rules,
+                        rule_trace: self.rule_trace,
                         rule_reactions: &mut self.rule_reactions,
                         ecs_action: &mut self.ecs_action,
                         action_schedule: &mut self.action_schedule,
@@ -862,6 +1404,11 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                     return Ok(reason);
                 }
                 TurnResolution::Schedule(entity_id, delay) => {
+                    // contract time limits are measured in the player's turns
+                    if entity_id == pc_id {
+                        game_state.delivery_turns += 1;
+                    }
+
                     let level = game_state.levels.level_mut(level_id);
                     let ticket = level.turn_schedule.insert(entity_id, delay);
                     level.ecs.insert_schedule_ticket(entity_id, ticket);
@@ -870,7 +1417,7 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                 TurnResolution::LevelSwitch { entity_id, exit_id, level_switch } => {
                     self.switch_level(entity_id, exit_id, level_switch, game_state);
                     if level_switch == LevelSwitch::LeaveLevel {
-                        self.prepare_between_levels(game_state);
+                        self.prepare_between_levels(game_state, user_path, slot);
                     }
                     return Ok(ExitReason::BetweenLevels);
                 }
@@ -881,9 +1428,9 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
         }
     }
 
-    fn death_message(&self, game_state: &GameState) {
+    fn game_over_message(&self, game_state: &GameState, message: MessageType) {
         let GlobalIds { pc_id, level_id, .. } = game_state.global_ids.expect("Unitialised game state");
-        self.add_message(game_state, MessageType::YouDied);
+        self.add_message(game_state, message);
         let entity = game_state.levels.level(level_id).ecs.entity(pc_id);
         self.renderer.borrow_mut().update_and_publish_all_windows_for_entity_with_overlay(
             game_state.action_id,
@@ -955,6 +1502,10 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                     None).publish();
 
                 if let Some(input) = self.input_source.next_input() {
+                    if input == InputEvent::Quit {
+                        break;
+                    }
+
                     ControlSpec::from(&*control_map).get(control_to_change).map(|input| {
                         control_map.remove(input);
                     });
@@ -972,10 +1523,10 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
         let pc_id = game_state.entity_ids.new_id();
 
         let mut action = EcsAction::new();
-        prototypes::pc(action.entity_mut(pc_id), Coord::new(0, 0));
+        prototypes::pc(action.entity_mut(pc_id), Coord::new(0, 0), &self.prototypes);
 
         let pistol_id = game_state.entity_ids.new_id();
-        prototypes::pistol(action.entity_mut(pistol_id));
+        prototypes::pistol(action.entity_mut(pistol_id), &self.prototypes);
 
         action.weapon_slots_mut(pc_id).expect("Missing component weapon_slots")
             .insert(Direction::East, pistol_id);
@@ -986,6 +1537,7 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                                                 &mut action,
                                                 &game_state.entity_ids,
                                                 &self.rng,
+                                                &self.prototypes,
                                                 game_state.action_id,
                                                 None,
                                                 0);
@@ -1001,37 +1553,31 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
         });
     }
 
-    fn unstage(&mut self, terrain_type: TerrainType, game_state: &mut GameState) {
+    fn unstage(&mut self, route: Route, game_state: &mut GameState) {
         let entity_id = game_state.staged.take().expect("No staged entity");
         let global_ids = game_state.global_ids.as_mut().expect("Unitialised game state");
 
         let mut entity_remove = EcsAction::new();
         let mut entity_insert = EcsAction::new();
 
-        if let Some(weapon_slots) = game_state.staging.weapon_slots_borrow(entity_id) {
-            for (_, id) in weapon_slots.iter() {
-                entity_remove.remove_entity_by_id(*id, &game_state.staging);
-            }
-        }
-
-        if let Some(inventory) = game_state.staging.inventory_borrow(entity_id) {
-            for id in inventory.iter() {
-                entity_remove.remove_entity_by_id(id, &game_state.staging);
-            }
-        }
-
-        entity_remove.remove_entity_by_id(entity_id, &game_state.staging);
+        remove_entity_and_contents(&mut entity_remove, entity_id, &game_state.staging);
         game_state.staging.commit_into(&mut entity_remove, &mut entity_insert);
         game_state.action_id += 1;
 
-        let (level, _) = Level::new_with_entity(terrain_type,
+        // the cargo of the new delivery starts out undamaged
+        entity_insert.insert_cargo_damage(entity_id, 0);
+        game_state.delivery_turns = 0;
+        game_state.contract_outcome = None;
+
+        let (level, _) = Level::new_with_entity(route.terrain_type,
                                                 entity_id,
                                                 &mut entity_insert,
                                                 &game_state.entity_ids,
                                                 &self.rng,
+                                                &self.prototypes,
                                                 game_state.action_id,
                                                 None,
-                                                global_ids.level_id + 1);
+                                                route.difficulty);
         game_state.action_id += 1;
 
         // can't go back to previous levels
@@ -1077,6 +1623,7 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                                            &mut entity_insert,
                                            &game_state.entity_ids,
                                            &self.rng,
+                                           &self.prototypes,
                                            game_state.action_id,
                                            Some(parent_ctx),
                                            global_ids.level_id + 1)
@@ -1117,14 +1664,26 @@ impl<Renderer: KnowledgeRenderer, Input: 'static + InputSource + Clone> GameCtx<
                               level_id: LevelId, level: &Level, action_id: ActionId) {
 
         let position = action.position(entity_id).expect("Missing component position");
-        let vision_distance = action.vision_distance(entity_id).expect("Missing component vision_distance");
+        let vision_distance = level.weather.lit_vision_distance(action.vision_distance(entity_id).expect("Missing component vision_distance"));
+
+        let action_env = ActionEnv::new(&level.ecs, action_id);
+
+        let opacity = level.weather.opacity();
+        let mut light_map = LightMap::with_lights(level.weather, &level.ecs, &level.spatial_hash, &self.pc_observer, action_env);
+
+        // the entity isn't in the level yet, so its own light must be added separately
+        if let Some(light) = action.light(entity_id) {
+            light_map.add_light(position, light, action.facing(entity_id),
+                                &level.spatial_hash, &self.pc_observer, opacity, action_env);
+        }
+
         let knowledge = action.drawable_knowledge_mut(entity_id).expect("Missing component drawable_knowledge");
         let level_knowledge = knowledge.level_mut_or_insert_size(level_id,
                                                                  level.spatial_hash.width(),
                                                                  level.spatial_hash.height());
+        level_knowledge.set_weather(level.weather);
 
-        let action_env = ActionEnv::new(&level.ecs, action_id);
-
-        self.pc_observer.observe(position, &level.spatial_hash, vision_distance, level_knowledge, action_env);
+        self.pc_observer.observe(position, &level.spatial_hash, vision_distance, opacity,
+                                 &mut level_knowledge.lit(&light_map, position, vision_distance), action_env);
     }
 }
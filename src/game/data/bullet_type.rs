@@ -1,5 +1,27 @@
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BulletType {
     Bullet,
     RailgunSlug,
+    Shell,
+    Napalm,
+    Mine,
+    Harpoon,
+    Rocket,
+}
+
+impl BulletType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Bullet" => BulletType::Bullet,
+            "RailgunSlug" => BulletType::RailgunSlug,
+            "Shell" => BulletType::Shell,
+            "Napalm" => BulletType::Napalm,
+            "Mine" => BulletType::Mine,
+            "Harpoon" => BulletType::Harpoon,
+            "Rocket" => BulletType::Rocket,
+            _ => return None,
+        };
+
+        Some(value)
+    }
 }
@@ -0,0 +1,46 @@
+use std::collections::{btree_map, BTreeMap};
+use std::iter;
+
+use ecs::*;
+
+pub type CargoHoldIter<'a> = iter::Cloned<btree_map::Keys<'a, EntityId, usize>>;
+
+/// Items loaded into a vehicle's cargo hold. An item's weight never changes,
+/// so the hold notes each item's weight as it's loaded, and its total weight
+/// is worked out from those.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CargoHold {
+    items: BTreeMap<EntityId, usize>,
+}
+
+impl CargoHold {
+    pub fn new() -> Self {
+        CargoHold {
+            items: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, id: EntityId, weight: usize) {
+        self.items.insert(id, weight);
+    }
+
+    pub fn remove(&mut self, id: EntityId) {
+        self.items.remove(&id);
+    }
+
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.items.contains_key(&id)
+    }
+
+    pub fn iter(&self) -> CargoHoldIter {
+        self.items.keys().cloned()
+    }
+
+    /// Total weight of the items in the hold
+    pub fn weight(&self) -> usize {
+        self.items.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,49 @@
+use ecs::*;
+
+use super::*;
+
+#[test]
+fn empty_hold_weighs_nothing() {
+    let hold = CargoHold::new();
+
+    assert_eq!(hold.weight(), 0);
+    assert_eq!(hold.iter().count(), 0);
+}
+
+#[test]
+fn weight_is_total_of_loaded_items() {
+    let ids = EntityIdReserver::new();
+    let (a, b) = (ids.new_id(), ids.new_id());
+    let mut hold = CargoHold::new();
+    hold.insert(a, 5);
+    hold.insert(b, 8);
+
+    assert_eq!(hold.weight(), 13);
+    assert!(hold.contains(a));
+    assert!(hold.contains(b));
+}
+
+#[test]
+fn unloading_removes_item_weight() {
+    let ids = EntityIdReserver::new();
+    let (a, b) = (ids.new_id(), ids.new_id());
+    let mut hold = CargoHold::new();
+    hold.insert(a, 5);
+    hold.insert(b, 8);
+    hold.remove(a);
+
+    assert_eq!(hold.weight(), 8);
+    assert!(!hold.contains(a));
+    assert_eq!(hold.iter().collect::<Vec<_>>(), vec![b]);
+}
+
+#[test]
+fn loading_item_twice_counts_it_once() {
+    let ids = EntityIdReserver::new();
+    let a = ids.new_id();
+    let mut hold = CargoHold::new();
+    hold.insert(a, 5);
+    hold.insert(a, 5);
+
+    assert_eq!(hold.weight(), 5);
+}
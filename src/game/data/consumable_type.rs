@@ -2,4 +2,18 @@
 pub enum ConsumableType {
     SpareTyre,
     EngineRepairKit,
+    FuelCan,
+}
+
+impl ConsumableType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "SpareTyre" => ConsumableType::SpareTyre,
+            "EngineRepairKit" => ConsumableType::EngineRepairKit,
+            "FuelCan" => ConsumableType::FuelCan,
+            _ => return None,
+        };
+
+        Some(value)
+    }
 }
@@ -0,0 +1,83 @@
+/// What a contract asks the player to deliver. This describes the job rather
+/// than an item, so it isn't loaded into the cargo hold: it can't be unloaded
+/// or sold, it doesn't count towards the hold's weight, and the damage it
+/// takes is tracked by the cargo_damage component instead.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum CargoType {
+    Mail,
+    Medicine,
+    Glassware,
+    Explosives,
+}
+
+impl CargoType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Mail" => CargoType::Mail,
+            "Medicine" => CargoType::Medicine,
+            "Glassware" => CargoType::Glassware,
+            "Explosives" => CargoType::Explosives,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Destination {
+    Farmstead,
+    Refinery,
+    Harbour,
+    Bunker,
+    Monastery,
+}
+
+pub const DESTINATIONS: [Destination; 5] = [
+    Destination::Farmstead,
+    Destination::Refinery,
+    Destination::Harbour,
+    Destination::Bunker,
+    Destination::Monastery,
+];
+
+/// A delivery the player may agree to make, paying a reward if the cargo
+/// arrives on time and intact
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Contract {
+    pub destination: Destination,
+    pub cargo: CargoType,
+    /// Number of the player's turns in which the delivery must be made
+    pub time_limit: Option<u64>,
+    /// Number of times the van can be damaged before the cargo is ruined
+    pub fragility: Option<usize>,
+    pub reward: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ContractOutcome {
+    Completed(Contract),
+    Late(Contract),
+    CargoRuined(Contract),
+}
+
+impl Contract {
+    pub fn outcome(self, turns_taken: u64, cargo_damage: usize) -> ContractOutcome {
+        if self.fragility.map(|fragility| cargo_damage >= fragility).unwrap_or(false) {
+            ContractOutcome::CargoRuined(self)
+        } else if self.time_limit.map(|time_limit| turns_taken > time_limit).unwrap_or(false) {
+            ContractOutcome::Late(self)
+        } else {
+            ContractOutcome::Completed(self)
+        }
+    }
+}
+
+impl ContractOutcome {
+    pub fn reward(self) -> usize {
+        match self {
+            ContractOutcome::Completed(contract) => contract.reward,
+            ContractOutcome::Late(_) | ContractOutcome::CargoRuined(_) => 0,
+        }
+    }
+}
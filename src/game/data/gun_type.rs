@@ -4,4 +4,26 @@ pub enum GunType {
     Shotgun,
     MachineGun,
     Railgun,
+    Flamethrower,
+    MineDropper,
+    Harpoon,
+    RocketLauncher,
+}
+
+impl GunType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Pistol" => GunType::Pistol,
+            "Shotgun" => GunType::Shotgun,
+            "MachineGun" => GunType::MachineGun,
+            "Railgun" => GunType::Railgun,
+            "Flamethrower" => GunType::Flamethrower,
+            "MineDropper" => GunType::MineDropper,
+            "Harpoon" => GunType::Harpoon,
+            "RocketLauncher" => GunType::RocketLauncher,
+            _ => return None,
+        };
+
+        Some(value)
+    }
 }
@@ -60,6 +60,12 @@ impl HitPoints {
         self.current >= self.max
     }
 
+    /// Raises the maximum by `amount`, healing by the same amount
+    pub fn raise_max(&mut self, amount: usize) {
+        self.max += amount as isize;
+        self.current += amount as isize;
+    }
+
     pub fn fill(&mut self) {
         self.current = self.max;
     }
@@ -0,0 +1,68 @@
+use direction::Direction;
+use coord::Coord;
+use math::Dot;
+
+/// Which way a light shines
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum LightDirection {
+    /// Shines equally in all directions
+    All,
+    /// Shines in a cone ahead of the entity, in the direction it is facing
+    Facing,
+}
+
+impl LightDirection {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "All" => LightDirection::All,
+            "Facing" => LightDirection::Facing,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Light {
+    pub radius: usize,
+    pub direction: LightDirection,
+}
+
+impl Light {
+    pub fn new(radius: usize, direction: LightDirection) -> Self {
+        Light {
+            radius: radius,
+            direction: direction,
+        }
+    }
+
+    /// Brightness, between 0 and 1, of this light at `offset` from its
+    /// source. `visibility` is the fraction of light which reaches the
+    /// cell, and `facing` is the direction the source entity is facing.
+    pub fn brightness(&self, offset: Coord, facing: Option<Direction>, visibility: f64) -> f64 {
+        let distance_squared = offset.length_squared();
+
+        if distance_squared >= (self.radius * self.radius) as isize {
+            return 0.0;
+        }
+
+        if let LightDirection::Facing = self.direction {
+            if let Some(facing) = facing {
+                // the cone spans 45 degrees either side of the facing direction
+                let facing = facing.vector();
+                let dot = offset.dot(facing);
+                if distance_squared != 0 && (dot <= 0 || 2 * dot * dot < distance_squared * facing.length_squared()) {
+                    return 0.0;
+                }
+            }
+        }
+
+        let falloff = 1.0 - (distance_squared as f64).sqrt() / self.radius as f64;
+
+        falloff * visibility
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,49 @@
+use direction::Direction;
+use coord::Coord;
+
+use super::*;
+
+#[test]
+fn brightness_falls_off_with_distance() {
+    let light = Light::new(4, LightDirection::All);
+
+    assert_eq!(light.brightness(Coord::new(0, 0), None, 1.0), 1.0);
+    assert_eq!(light.brightness(Coord::new(1, 0), None, 1.0), 0.75);
+    assert_eq!(light.brightness(Coord::new(0, -2), None, 1.0), 0.5);
+    assert_eq!(light.brightness(Coord::new(-3, 0), None, 1.0), 0.25);
+}
+
+#[test]
+fn brightness_is_zero_at_radius() {
+    let light = Light::new(4, LightDirection::All);
+
+    assert_eq!(light.brightness(Coord::new(4, 0), None, 1.0), 0.0);
+    assert_eq!(light.brightness(Coord::new(3, 3), None, 1.0), 0.0);
+}
+
+#[test]
+fn brightness_is_scaled_by_visibility() {
+    let light = Light::new(4, LightDirection::All);
+
+    assert_eq!(light.brightness(Coord::new(0, 2), None, 0.5), 0.25);
+}
+
+#[test]
+fn facing_light_only_shines_ahead() {
+    let light = Light::new(4, LightDirection::Facing);
+    let facing = Some(Direction::East);
+
+    assert_eq!(light.brightness(Coord::new(0, 0), facing, 1.0), 1.0);
+    assert_eq!(light.brightness(Coord::new(2, 0), facing, 1.0), 0.5);
+    assert!(light.brightness(Coord::new(2, 1), facing, 1.0) > 0.0);
+    assert_eq!(light.brightness(Coord::new(1, 2), facing, 1.0), 0.0);
+    assert_eq!(light.brightness(Coord::new(0, 1), facing, 1.0), 0.0);
+    assert_eq!(light.brightness(Coord::new(-2, 0), facing, 1.0), 0.0);
+}
+
+#[test]
+fn facing_light_without_facing_shines_everywhere() {
+    let light = Light::new(4, LightDirection::Facing);
+
+    assert_eq!(light.brightness(Coord::new(-2, 0), None, 1.0), 0.5);
+}
@@ -15,6 +15,14 @@ mod bullet_type;
 mod damage_type;
 mod repair_type;
 mod consumable_type;
+mod save_metadata;
+mod route;
+mod contract;
+mod weather;
+mod light;
+mod upgrade;
+mod squad;
+mod cargo_hold;
 
 pub use self::speed::*;
 pub use self::path_traverse::*;
@@ -33,3 +41,11 @@ pub use self::bullet_type::*;
 pub use self::damage_type::*;
 pub use self::repair_type::*;
 pub use self::consumable_type::*;
+pub use self::save_metadata::*;
+pub use self::route::*;
+pub use self::contract::*;
+pub use self::weather::*;
+pub use self::light::*;
+pub use self::upgrade::*;
+pub use self::squad::*;
+pub use self::cargo_hold::*;
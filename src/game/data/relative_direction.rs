@@ -6,6 +6,10 @@ pub enum RelativeDirection {
     Rear,
     Left,
     Right,
+    FrontLeft,
+    FrontRight,
+    RearLeft,
+    RearRight,
 }
 
 impl From<Direction> for RelativeDirection {
@@ -15,7 +19,10 @@ impl From<Direction> for RelativeDirection {
             Direction::South => RelativeDirection::Right,
             Direction::East => RelativeDirection::Front,
             Direction::West => RelativeDirection::Rear,
-            _ => panic!("Invalid direction"),
+            Direction::NorthEast => RelativeDirection::FrontLeft,
+            Direction::SouthEast => RelativeDirection::FrontRight,
+            Direction::NorthWest => RelativeDirection::RearLeft,
+            Direction::SouthWest => RelativeDirection::RearRight,
         }
     }
 }
@@ -3,3 +3,15 @@ pub enum RepairType {
     Tyres,
     Engine,
 }
+
+impl RepairType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Tyres" => RepairType::Tyres,
+            "Engine" => RepairType::Engine,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
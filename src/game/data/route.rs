@@ -0,0 +1,12 @@
+use game::*;
+
+/// A route the player may take on their next delivery
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Route {
+    pub terrain_type: TerrainType,
+    /// How much more dangerous than usual this route is
+    pub risk: usize,
+    pub difficulty: usize,
+    /// Paid on top of the usual payout on completing a delivery by this route
+    pub bonus: usize,
+}
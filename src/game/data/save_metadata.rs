@@ -0,0 +1,44 @@
+/// Summary of a saved game, shown when choosing a save slot
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    pub delivery: usize,
+    pub bank: usize,
+    pub letters: usize,
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UtcDateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl SaveMetadata {
+    pub fn utc_date_time(&self) -> UtcDateTime {
+        let seconds_of_day = self.timestamp % 86400;
+
+        // convert days since the epoch to a date in the proleptic gregorian calendar
+        // (algorithm from http://howardhinnant.github.io/date_algorithms.html)
+        let z = (self.timestamp / 86400) as i64 + 719468;
+        let era = z / 146097;
+        let day_of_era = z - era * 146097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        UtcDateTime {
+            year: year,
+            month: month as u32,
+            day: day as u32,
+            hour: (seconds_of_day / 3600) as u32,
+            minute: ((seconds_of_day % 3600) / 60) as u32,
+        }
+    }
+}
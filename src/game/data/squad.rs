@@ -0,0 +1,16 @@
+use ecs::*;
+
+/// Identifies a group of npcs which share what they know about their
+/// target. By convention this is the id of the squad's leader.
+pub type SquadId = EntityId;
+
+/// Where a member of a squad positions itself relative to the target
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SquadRole {
+    /// Gets in front of the target to slow it down
+    Blocker,
+    /// Drives beside the target to stop it changing lanes
+    Flanker,
+    /// Keeps its distance and fires from the side
+    Shooter,
+}
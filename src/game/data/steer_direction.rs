@@ -1,6 +1,6 @@
 use direction::Direction;
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SteerDirection {
     Up,
     Down,
@@ -0,0 +1,54 @@
+use direction::Direction;
+
+/// Diagonal weapon mounts, in the order they are fitted by upgrades
+pub const EXTRA_WEAPON_MOUNTS: [Direction; 4] = [
+    Direction::NorthEast,
+    Direction::SouthEast,
+    Direction::NorthWest,
+    Direction::SouthWest,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum UpgradeType {
+    /// Raises the van's top speed
+    Engine,
+    /// Raises the maximum health of the tyres
+    Tyres,
+    /// Fits extra weapon mounts on the diagonals
+    WeaponMounts,
+    /// Raises the capacity of the inventory
+    Inventory,
+}
+
+impl UpgradeType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Engine" => UpgradeType::Engine,
+            "Tyres" => UpgradeType::Tyres,
+            "WeaponMounts" => UpgradeType::WeaponMounts,
+            "Inventory" => UpgradeType::Inventory,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// A permanent improvement to the van which can be bought in the garage
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Upgrade {
+    pub upgrade_type: UpgradeType,
+    /// Position of this upgrade in the chain of upgrades of its type,
+    /// starting at 1
+    pub tier: usize,
+    pub amount: usize,
+    pub price: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum UpgradeStatus {
+    Owned,
+    Available,
+    /// Some prerequisite of the upgrade hasn't been bought
+    Locked,
+}
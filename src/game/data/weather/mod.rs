@@ -0,0 +1,100 @@
+/// Conditions which last for the whole of a level
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Weather {
+    Clear,
+    Fog,
+    Night,
+    DustStorm,
+    AcidRain,
+}
+
+impl Weather {
+    /// Distance an entity can see a brightly lit cell, given how far it could
+    /// see in clear weather
+    pub fn lit_vision_distance(self, distance: usize) -> usize {
+        match self {
+            Weather::Fog => distance / 3,
+            Weather::Clear | Weather::Night | Weather::DustStorm | Weather::AcidRain => distance,
+        }
+    }
+
+    /// Distance an entity which ignores light sources can see, given how far
+    /// it could see in clear weather. Dim ambient light shortens this, except
+    /// at night where such entities are assumed to see in the dark.
+    pub fn vision_distance(self, distance: usize) -> usize {
+        let lit_distance = self.lit_vision_distance(distance);
+
+        match self {
+            Weather::Night => lit_distance,
+            Weather::Clear | Weather::Fog | Weather::DustStorm | Weather::AcidRain =>
+                (lit_distance as f64 * self.ambient_light()) as usize,
+        }
+    }
+
+    /// Opacity of the air in every cell, on top of the opacity of the cell's contents
+    pub fn opacity(self) -> f64 {
+        match self {
+            Weather::Fog => 0.1,
+            Weather::DustStorm => 0.15,
+            Weather::AcidRain => 0.05,
+            Weather::Clear | Weather::Night => 0.0,
+        }
+    }
+
+    /// Light level, between 0 and 1, of cells not lit by any light source
+    pub fn ambient_light(self) -> f64 {
+        match self {
+            Weather::Clear | Weather::Fog => 1.0,
+            Weather::AcidRain => 0.75,
+            Weather::DustStorm => 0.5,
+            Weather::Night => 0.0,
+        }
+    }
+
+    /// Multiplier of the chance of successfully steering
+    pub fn grip(self) -> f64 {
+        match self {
+            Weather::Clear | Weather::Fog => 1.0,
+            Weather::Night => 0.95,
+            Weather::DustStorm => 0.85,
+            Weather::AcidRain => 0.75,
+        }
+    }
+
+    /// Percentage by which the chance of a zombie appearing in a cell is scaled
+    pub fn zombie_spawn_percent(self) -> usize {
+        match self {
+            Weather::Night => 150,
+            Weather::Fog => 125,
+            Weather::Clear | Weather::DustStorm | Weather::AcidRain => 100,
+        }
+    }
+
+    /// Percentage by which the chance of a car or bike appearing in a cell is scaled
+    pub fn vehicle_spawn_percent(self) -> usize {
+        match self {
+            Weather::Clear => 100,
+            Weather::Night | Weather::AcidRain => 75,
+            Weather::Fog | Weather::DustStorm => 50,
+        }
+    }
+
+    /// Weight of a zombie appearing in a cell, given its weight in clear weather
+    pub fn zombie_spawn_weight(self, weight: usize) -> usize {
+        scale_spawn_weight(weight, self.zombie_spawn_percent())
+    }
+
+    /// Weight of a car or bike appearing in a cell, given its weight in clear weather
+    pub fn vehicle_spawn_weight(self, weight: usize) -> usize {
+        scale_spawn_weight(weight, self.vehicle_spawn_percent())
+    }
+}
+
+/// Rounds up, so that anything which can appear in clear weather can appear
+/// in any weather
+fn scale_spawn_weight(weight: usize, percent: usize) -> usize {
+    (weight * percent + 99) / 100
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,68 @@
+use super::*;
+
+const ALL: [Weather; 5] = [
+    Weather::Clear,
+    Weather::Fog,
+    Weather::Night,
+    Weather::DustStorm,
+    Weather::AcidRain,
+];
+
+#[test]
+fn clear_weather_changes_nothing() {
+    assert_eq!(Weather::Clear.lit_vision_distance(12), 12);
+    assert_eq!(Weather::Clear.vision_distance(12), 12);
+    assert_eq!(Weather::Clear.opacity(), 0.0);
+    assert_eq!(Weather::Clear.ambient_light(), 1.0);
+    assert_eq!(Weather::Clear.grip(), 1.0);
+    assert_eq!(Weather::Clear.zombie_spawn_weight(7), 7);
+    assert_eq!(Weather::Clear.vehicle_spawn_weight(7), 7);
+}
+
+#[test]
+fn fog_shortens_vision() {
+    assert_eq!(Weather::Fog.lit_vision_distance(12), 4);
+    assert_eq!(Weather::Fog.vision_distance(12), 4);
+}
+
+#[test]
+fn dim_weather_shortens_vision_without_lights() {
+    assert_eq!(Weather::DustStorm.lit_vision_distance(12), 12);
+    assert_eq!(Weather::DustStorm.vision_distance(12), 6);
+    assert_eq!(Weather::AcidRain.vision_distance(12), 9);
+}
+
+#[test]
+fn night_vision_ignores_darkness() {
+    assert_eq!(Weather::Night.ambient_light(), 0.0);
+    assert_eq!(Weather::Night.vision_distance(12), 12);
+}
+
+#[test]
+fn weather_never_improves_conditions() {
+    for weather in ALL.iter() {
+        assert!(weather.lit_vision_distance(12) <= 12);
+        assert!(weather.vision_distance(12) <= weather.lit_vision_distance(12));
+        assert!(weather.opacity() >= 0.0);
+        assert!(weather.ambient_light() >= 0.0 && weather.ambient_light() <= 1.0);
+        assert!(weather.grip() > 0.0 && weather.grip() <= 1.0);
+        assert!(weather.vehicle_spawn_percent() <= 100);
+        assert!(weather.zombie_spawn_percent() >= 100);
+    }
+}
+
+#[test]
+fn spawn_weights_round_up() {
+    assert_eq!(Weather::Fog.vehicle_spawn_weight(1), 1);
+    assert_eq!(Weather::Fog.vehicle_spawn_weight(3), 2);
+    assert_eq!(Weather::Night.vehicle_spawn_weight(4), 3);
+    assert_eq!(Weather::Night.zombie_spawn_weight(3), 5);
+}
+
+#[test]
+fn zero_spawn_weight_stays_zero() {
+    for weather in ALL.iter() {
+        assert_eq!(weather.zombie_spawn_weight(0), 0);
+        assert_eq!(weather.vehicle_spawn_weight(0), 0);
+    }
+}
@@ -0,0 +1,372 @@
+use std::path;
+use std::result;
+use rand::Rng;
+
+use ecs::*;
+use game::*;
+use game::data::*;
+
+pub const ECONOMY_FILE: &'static str = "economy.toml";
+
+/// Item name which stocks an armour upgrade rather than a prototype
+const ARMOUR_UPGRADE_ITEM: &'static str = "armour_upgrade";
+
+#[derive(Debug)]
+pub enum EconomyError {
+    FailedToRead(game_file::FileError),
+    InvalidShopSize,
+    UnknownItem(String),
+    MissingArmourAmount,
+    InvalidContractCount,
+    UnknownCargo(String),
+    UnknownUpgradeType(String),
+    DuplicateUpgrade(String),
+    UnknownPrerequisite(String),
+}
+
+pub type EconomyResult<T> = result::Result<T, EconomyError>;
+
+#[derive(Debug, Deserialize)]
+struct EconomySpec {
+    shop: ShopSpec,
+    payout: Payout,
+    contracts: ContractsSpec,
+    #[serde(default)]
+    upgrades: Vec<UpgradeSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShopSpec {
+    min_items: usize,
+    max_items: usize,
+    items: Vec<ShopItemSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShopItemSpec {
+    item: String,
+    amount: Option<usize>,
+    price: usize,
+    weight: usize,
+    #[serde(default)]
+    weight_per_delivery: isize,
+    #[serde(default)]
+    min_delivery: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContractsSpec {
+    min_offered: usize,
+    max_offered: usize,
+    offers: Vec<ContractSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContractSpec {
+    cargo: String,
+    reward: usize,
+    #[serde(default)]
+    reward_per_delivery: usize,
+    time_limit: Option<u64>,
+    fragility: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpgradeSpec {
+    id: String,
+    upgrade: String,
+    amount: usize,
+    price: usize,
+    #[serde(default)]
+    requires: Vec<String>,
+}
+
+#[derive(Debug)]
+struct ContractOffer {
+    cargo: CargoType,
+    reward: usize,
+    reward_per_delivery: usize,
+    time_limit: Option<u64>,
+    fragility: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum ShopItemType {
+    Prototype(String),
+    ArmourUpgrade(usize),
+}
+
+#[derive(Debug)]
+struct ShopItem {
+    item_type: ShopItemType,
+    price: usize,
+    weight: usize,
+    weight_per_delivery: isize,
+    min_delivery: usize,
+}
+
+impl ShopItem {
+    /// Relative chance of stocking this item in the shop visited after the
+    /// given number of deliveries
+    fn weight(&self, delivery: usize) -> usize {
+        if delivery < self.min_delivery {
+            return 0;
+        }
+
+        let weight = self.weight as isize + self.weight_per_delivery * delivery as isize;
+        if weight < 0 { 0 } else { weight as usize }
+    }
+}
+
+/// An upgrade sold in the garage, and where it appears in the tree of
+/// upgrades
+#[derive(Debug)]
+pub struct UpgradeNode {
+    pub id: String,
+    pub upgrade: Upgrade,
+    /// Ids of upgrades which must be bought before this one
+    pub requires: Vec<String>,
+    /// Number of ancestors of this upgrade in the tree
+    pub depth: usize,
+}
+
+impl UpgradeNode {
+    pub fn status(&self, owned: &[String]) -> UpgradeStatus {
+        if owned.contains(&self.id) {
+            UpgradeStatus::Owned
+        } else if self.requires.iter().all(|id| owned.contains(id)) {
+            UpgradeStatus::Available
+        } else {
+            UpgradeStatus::Locked
+        }
+    }
+}
+
+/// Money paid to the player after each delivery
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Payout {
+    pub base: usize,
+    pub per_letter: usize,
+    #[serde(default)]
+    pub per_delivery: usize,
+    #[serde(default)]
+    pub per_risk: usize,
+}
+
+impl Payout {
+    pub fn amount(&self, delivery: usize, letter_count: usize) -> usize {
+        self.base + letter_count * self.per_letter + delivery * self.per_delivery
+    }
+
+    /// Extra money offered for taking a route with the given risk
+    pub fn route_bonus(&self, risk: usize) -> usize {
+        risk * self.per_risk
+    }
+}
+
+/// Shop stock and delivery payout, loaded from a resource file
+#[derive(Debug)]
+pub struct Economy {
+    shop_min_items: usize,
+    shop_max_items: usize,
+    shop_items: Vec<ShopItem>,
+    payout: Payout,
+    min_contracts: usize,
+    max_contracts: usize,
+    contract_offers: Vec<ContractOffer>,
+    /// Upgrades in the order they appear in the tree, with each upgrade
+    /// following its first prerequisite
+    upgrades: Vec<UpgradeNode>,
+}
+
+/// Arranges upgrades into a tree, where each upgrade is a child of its first
+/// prerequisite. Prerequisites must be defined before the upgrades which
+/// require them, which rules out cycles.
+fn upgrade_tree(specs: Vec<UpgradeSpec>) -> EconomyResult<Vec<UpgradeNode>> {
+    let mut nodes: Vec<UpgradeNode> = Vec::new();
+    let mut parents = Vec::new();
+
+    for spec in specs {
+        if nodes.iter().any(|node| node.id == spec.id) {
+            return Err(EconomyError::DuplicateUpgrade(spec.id));
+        }
+
+        let mut parent = None;
+        for id in spec.requires.iter() {
+            let index = nodes.iter().position(|node| &node.id == id)
+                .ok_or_else(|| EconomyError::UnknownPrerequisite(id.clone()))?;
+            if parent.is_none() {
+                parent = Some(index);
+            }
+        }
+
+        let upgrade_type = UpgradeType::from_str(&spec.upgrade)
+            .ok_or_else(|| EconomyError::UnknownUpgradeType(spec.upgrade.clone()))?;
+        let tier = nodes.iter().filter(|node| node.upgrade.upgrade_type == upgrade_type).count() + 1;
+        let depth = parent.map(|index| nodes[index].depth + 1).unwrap_or(0);
+
+        nodes.push(UpgradeNode {
+            id: spec.id,
+            upgrade: Upgrade {
+                upgrade_type: upgrade_type,
+                tier: tier,
+                amount: spec.amount,
+                price: spec.price,
+            },
+            requires: spec.requires,
+            depth: depth,
+        });
+        parents.push(parent);
+    }
+
+    // depth-first, so children are listed directly beneath their parent
+    let mut order = Vec::new();
+    let mut stack: Vec<usize> = (0..nodes.len()).filter(|&i| parents[i].is_none()).rev().collect();
+    while let Some(index) = stack.pop() {
+        order.push(index);
+        stack.extend((0..nodes.len()).filter(|&i| parents[i] == Some(index)).rev());
+    }
+
+    let mut nodes: Vec<Option<UpgradeNode>> = nodes.into_iter().map(Some).collect();
+
+    Ok(order.into_iter().map(|index| nodes[index].take().expect("Upgrade listed twice")).collect())
+}
+
+impl Economy {
+    fn new(spec: EconomySpec, prototypes: &PrototypeTable) -> EconomyResult<Self> {
+        let EconomySpec { shop, payout, contracts, upgrades } = spec;
+
+        if shop.max_items < shop.min_items {
+            return Err(EconomyError::InvalidShopSize);
+        }
+
+        if contracts.max_offered < contracts.min_offered ||
+            (contracts.max_offered > 0 && contracts.offers.is_empty()) {
+            return Err(EconomyError::InvalidContractCount);
+        }
+
+        let mut shop_items = Vec::new();
+
+        for item in shop.items {
+            let item_type = if item.item == ARMOUR_UPGRADE_ITEM {
+                ShopItemType::ArmourUpgrade(item.amount.ok_or(EconomyError::MissingArmourAmount)?)
+            } else if prototypes.get(&item.item).is_some() {
+                ShopItemType::Prototype(item.item)
+            } else {
+                return Err(EconomyError::UnknownItem(item.item));
+            };
+
+            shop_items.push(ShopItem {
+                item_type: item_type,
+                price: item.price,
+                weight: item.weight,
+                weight_per_delivery: item.weight_per_delivery,
+                min_delivery: item.min_delivery,
+            });
+        }
+
+        let mut contract_offers = Vec::new();
+
+        for offer in contracts.offers {
+            contract_offers.push(ContractOffer {
+                cargo: CargoType::from_str(&offer.cargo).ok_or_else(|| EconomyError::UnknownCargo(offer.cargo.clone()))?,
+                reward: offer.reward,
+                reward_per_delivery: offer.reward_per_delivery,
+                time_limit: offer.time_limit,
+                fragility: offer.fragility,
+            });
+        }
+
+        Ok(Economy {
+            shop_min_items: shop.min_items,
+            shop_max_items: shop.max_items,
+            shop_items: shop_items,
+            payout: payout,
+            min_contracts: contracts.min_offered,
+            max_contracts: contracts.max_offered,
+            contract_offers: contract_offers,
+            upgrades: upgrade_tree(upgrades)?,
+        })
+    }
+
+    pub fn from_file<P: AsRef<path::Path>>(path: P, prototypes: &PrototypeTable) -> EconomyResult<Self> {
+        let spec = game_file::read_toml(path).map_err(EconomyError::FailedToRead)?;
+        Self::new(spec, prototypes)
+    }
+
+    pub fn payout(&self) -> Payout {
+        self.payout
+    }
+
+    pub fn upgrades(&self) -> &[UpgradeNode] {
+        &self.upgrades
+    }
+
+    /// Creates the items for sale in the shop visited after the given
+    /// number of deliveries, returning their ids
+    pub fn stock_shop<R: Rng>(&self,
+                              delivery: usize,
+                              ecs: &mut EcsCtx,
+                              ids: &EntityIdReserver,
+                              prototypes: &PrototypeTable,
+                              rng: &mut R) -> EntitySet {
+
+        let mut inventory = EntitySet::new();
+
+        let weights: Vec<usize> = self.shop_items.iter().map(|item| item.weight(delivery)).collect();
+        let total_weight = weights.iter().fold(0, |acc, &x| acc + x);
+
+        if total_weight == 0 {
+            return inventory;
+        }
+
+        let num_items = rng.gen_range(self.shop_min_items, self.shop_max_items + 1);
+
+        for _ in 0..num_items {
+            let mut roll = rng.gen::<usize>() % total_weight;
+            for (weight, item) in izip!(weights.iter(), self.shop_items.iter()) {
+                if roll < *weight {
+                    let id = ids.new_id();
+                    inventory.insert(id);
+
+                    match item.item_type {
+                        ShopItemType::Prototype(ref name) => {
+                            prototypes.populate(name, &mut ecs.entity_mut(id));
+                        }
+                        ShopItemType::ArmourUpgrade(amount) => {
+                            prototypes::armour_upgrade(ecs.entity_mut(id), amount);
+                        }
+                    }
+
+                    ecs.insert_price(id, item.price);
+
+                    break;
+                }
+
+                roll -= *weight;
+            }
+        }
+
+        inventory
+    }
+
+    /// Chooses the contracts offered for the delivery after the given
+    /// number of deliveries
+    pub fn offer_contracts<R: Rng>(&self, delivery: usize, rng: &mut R) -> Vec<Contract> {
+        let num_contracts = rng.gen_range(self.min_contracts, self.max_contracts + 1);
+
+        (0..num_contracts).map(|_| {
+            let offer = rng.choose(&self.contract_offers).expect("No contracts to offer");
+            Contract {
+                destination: *rng.choose(&DESTINATIONS).expect("No destinations"),
+                cargo: offer.cargo,
+                time_limit: offer.time_limit,
+                fragility: offer.fragility,
+                reward: offer.reward + delivery * offer.reward_per_delivery,
+            }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,96 @@
+use game::*;
+use game::data::*;
+use game::tests::{prototypes, resource_path};
+use super::*;
+
+fn spec(id: &str, upgrade: &str, requires: &[&str]) -> UpgradeSpec {
+    UpgradeSpec {
+        id: id.to_string(),
+        upgrade: upgrade.to_string(),
+        amount: 1,
+        price: 10,
+        requires: requires.iter().map(|id| id.to_string()).collect(),
+    }
+}
+
+fn owned(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+#[test]
+fn children_follow_their_parent() {
+    let tree = upgrade_tree(vec![
+        spec("engine_1", "Engine", &[]),
+        spec("tyres_1", "Tyres", &[]),
+        spec("engine_2", "Engine", &["engine_1"]),
+        spec("tyres_2", "Tyres", &["tyres_1"]),
+        spec("engine_3", "Engine", &["engine_2"]),
+    ]).unwrap();
+
+    let ids: Vec<&str> = tree.iter().map(|node| node.id.as_str()).collect();
+    let depths: Vec<usize> = tree.iter().map(|node| node.depth).collect();
+    let tiers: Vec<usize> = tree.iter().map(|node| node.upgrade.tier).collect();
+
+    assert_eq!(ids, vec!["engine_1", "engine_2", "engine_3", "tyres_1", "tyres_2"]);
+    assert_eq!(depths, vec![0, 1, 2, 0, 1]);
+    assert_eq!(tiers, vec![1, 2, 3, 1, 2]);
+}
+
+#[test]
+fn first_prerequisite_is_the_parent() {
+    let tree = upgrade_tree(vec![
+        spec("engine_1", "Engine", &[]),
+        spec("tyres_1", "Tyres", &[]),
+        spec("mounts_1", "WeaponMounts", &["tyres_1", "engine_1"]),
+    ]).unwrap();
+
+    let ids: Vec<&str> = tree.iter().map(|node| node.id.as_str()).collect();
+
+    assert_eq!(ids, vec!["engine_1", "tyres_1", "mounts_1"]);
+    assert_eq!(tree[2].depth, 1);
+}
+
+#[test]
+fn duplicate_upgrade_is_rejected() {
+    match upgrade_tree(vec![spec("engine_1", "Engine", &[]), spec("engine_1", "Engine", &[])]) {
+        Err(EconomyError::DuplicateUpgrade(id)) => assert_eq!(id, "engine_1"),
+        other => panic!("Unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn prerequisite_must_be_defined_first() {
+    // this also rules out cycles, which would need a forward reference
+    match upgrade_tree(vec![spec("engine_2", "Engine", &["engine_1"]), spec("engine_1", "Engine", &[])]) {
+        Err(EconomyError::UnknownPrerequisite(id)) => assert_eq!(id, "engine_1"),
+        other => panic!("Unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn unknown_upgrade_type_is_rejected() {
+    match upgrade_tree(vec![spec("wings_1", "Wings", &[])]) {
+        Err(EconomyError::UnknownUpgradeType(upgrade)) => assert_eq!(upgrade, "Wings"),
+        other => panic!("Unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn status_depends_on_prerequisites() {
+    let tree = upgrade_tree(vec![
+        spec("engine_1", "Engine", &[]),
+        spec("engine_2", "Engine", &["engine_1"]),
+    ]).unwrap();
+
+    assert_eq!(tree[0].status(&owned(&[])), UpgradeStatus::Available);
+    assert_eq!(tree[1].status(&owned(&[])), UpgradeStatus::Locked);
+    assert_eq!(tree[0].status(&owned(&["engine_1"])), UpgradeStatus::Owned);
+    assert_eq!(tree[1].status(&owned(&["engine_1"])), UpgradeStatus::Available);
+}
+
+#[test]
+fn resource_file_is_valid() {
+    let economy = Economy::from_file(resource_path(ECONOMY_FILE), &prototypes()).expect("Failed to load economy");
+
+    assert!(!economy.upgrades().is_empty());
+}
@@ -1,22 +1,48 @@
+use std::cmp;
 use rand::Rng;
 
 use game::data::*;
 use ecs::*;
 
+/// Each full multiple of this much weight in the cargo hold reduces the
+/// van's top speed by 1
+const CARGO_WEIGHT_PER_SPEED: usize = 10;
+
+/// Chance of failing to accelerate with a full cargo hold
+const FULL_LOAD_ACCELERATE_FAIL_CHANCE: f64 = 0.5;
+
+/// Engine health needed for each unit of top speed
+pub const ENGINE_HEALTH_PER_SPEED: usize = 2;
+
+/// Top speed of a vehicle with the given engine health and cargo weight. A
+/// working engine can always move the vehicle, however heavily it's loaded.
+pub fn engine_max_speed(engine: HitPoints, cargo_weight: usize) -> usize {
+    let max_speed = (engine.ucurrent() + 1) / ENGINE_HEALTH_PER_SPEED;
+    let penalty = cargo_weight / CARGO_WEIGHT_PER_SPEED;
+    cmp::max(max_speed.saturating_sub(penalty), cmp::min(max_speed, 1))
+}
+
 pub trait EntityExtra {
     fn player_max_speed(&self) -> Option<usize>;
     fn general_max_speed(&self) -> Option<usize>;
     fn armour_hit_chance(&self) -> Option<Option<f64>>;
     fn steer_chance(&self) -> Option<f64>;
     fn damage_type<R: Rng>(&self, r: &mut R) -> Option<DamageType>;
-    fn steer_check<R: Rng>(&self, r: &mut R) -> Option<bool> {
-        self.steer_chance().map(|chance| r.next_f64() < chance)
+    fn out_of_fuel(&self) -> bool;
+    fn cargo_weight(&self) -> usize;
+    fn load(&self) -> f64;
+    fn accelerate_check<R: Rng>(&self, r: &mut R) -> bool {
+        r.next_f64() >= self.load() * FULL_LOAD_ACCELERATE_FAIL_CHANCE
+    }
+    fn steer_check<R: Rng>(&self, grip: f64, r: &mut R) -> bool {
+        // entities without tyre health steer as if their tyres were undamaged
+        r.next_f64() < self.steer_chance().unwrap_or(1.0) * grip
     }
 }
 
 impl<'a> EntityExtra for EntityRef<'a> {
     fn player_max_speed(&self) -> Option<usize> {
-        self.engine_health().map(|hp| (hp.ucurrent() + 1) / 2)
+        self.engine_health().map(|hp| engine_max_speed(hp, self.cargo_weight()))
     }
     fn general_max_speed(&self) -> Option<usize> {
         self.max_speed().or_else(|| self.player_max_speed())
@@ -33,6 +59,21 @@ impl<'a> EntityExtra for EntityRef<'a> {
     fn steer_chance(&self) -> Option<f64> {
         self.tyre_health().map(|t| (t.ucurrent() + 1) as f64 / (t.umax() + 1) as f64)
     }
+    fn out_of_fuel(&self) -> bool {
+        // entities without fuel never run out
+        self.fuel().map(|f| !f.is_positive()).unwrap_or(false)
+    }
+    fn cargo_weight(&self) -> usize {
+        // entities without a cargo hold carry no weight
+        self.cargo_borrow().map(|cargo| cargo.weight()).unwrap_or(0)
+    }
+    fn load(&self) -> f64 {
+        // fraction of the cargo hold's capacity in use
+        match self.cargo_capacity() {
+            Some(capacity) if capacity > 0 => (self.cargo_weight() as f64 / capacity as f64).min(1.0),
+            _ => 0.0,
+        }
+    }
     fn damage_type<R: Rng>(&self, r: &mut R) -> Option<DamageType> {
         self.armour_hit_chance().and_then(|maybe_chance| {
             if let Some(chance) = maybe_chance {
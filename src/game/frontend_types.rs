@@ -1,16 +1,20 @@
-pub const NUM_FRONTENDS: usize = 1;
-pub const FRONTENDS: [Frontend; NUM_FRONTENDS] = [Frontend::Sdl];
-pub const FRONTEND_STRINGS: [&'static str; NUM_FRONTENDS] = ["sdl"];
+pub const NUM_FRONTENDS: usize = 3;
+pub const FRONTENDS: [Frontend; NUM_FRONTENDS] = [Frontend::Sdl, Frontend::Ansi, Frontend::Headless];
+pub const FRONTEND_STRINGS: [&'static str; NUM_FRONTENDS] = ["sdl", "ansi", "headless"];
 
 #[derive(Debug)]
 pub enum Frontend {
     Sdl,
+    Ansi,
+    Headless,
 }
 
 impl Frontend {
     pub fn from_string(string: &str) -> Option<Self> {
         match string {
             "sdl" => Some(Frontend::Sdl),
+            "ansi" => Some(Frontend::Ansi),
+            "headless" => Some(Frontend::Headless),
             _ => None,
         }
     }
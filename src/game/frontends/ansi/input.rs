@@ -0,0 +1,80 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::time::Duration;
+
+use rustty::{Terminal, Event};
+
+use game::*;
+
+const ESCAPE: char = '\x1b';
+const ETX: char = '\x03';
+
+/// Time to wait after an escape character for the rest of an escape sequence
+/// before treating it as a lone press of the escape key
+const ESCAPE_SEQUENCE_TIMEOUT_MS: u64 = 20;
+
+#[derive(Clone)]
+pub struct AnsiInputSource {
+    terminal: Rc<RefCell<Terminal>>,
+}
+
+enum RawInput {
+    Char(char),
+    Timeout,
+    Error,
+}
+
+impl AnsiInputSource {
+    pub fn new(terminal: Rc<RefCell<Terminal>>) -> Self {
+        AnsiInputSource {
+            terminal: terminal,
+        }
+    }
+
+    fn get_char(&self, timeout: Option<Duration>) -> RawInput {
+        match self.terminal.borrow_mut().get_event(timeout) {
+            Ok(Some(Event::Key(ch))) => RawInput::Char(ch),
+            Ok(None) => RawInput::Timeout,
+            Err(_) => RawInput::Error,
+        }
+    }
+
+    fn escape_sequence(&self) -> Option<InputEvent> {
+        let timeout = Some(Duration::from_millis(ESCAPE_SEQUENCE_TIMEOUT_MS));
+
+        match self.get_char(timeout) {
+            RawInput::Char('[') | RawInput::Char('O') => (),
+            RawInput::Timeout => return Some(InputEvent::Escape),
+            _ => return None,
+        }
+
+        match self.get_char(timeout) {
+            RawInput::Char('A') => Some(InputEvent::Up),
+            RawInput::Char('B') => Some(InputEvent::Down),
+            RawInput::Char('C') => Some(InputEvent::Right),
+            RawInput::Char('D') => Some(InputEvent::Left),
+            _ => None,
+        }
+    }
+}
+
+impl InputSource for AnsiInputSource {
+    fn next_input(&mut self) -> Option<InputEvent> {
+        loop {
+            let ch = match self.get_char(None) {
+                RawInput::Char(ch) => ch,
+                RawInput::Timeout => continue,
+                RawInput::Error => return Some(InputEvent::Quit),
+            };
+
+            return match ch {
+                ESCAPE => self.escape_sequence(),
+                ETX => Some(InputEvent::Quit),
+                '\r' | '\n' => Some(InputEvent::Return),
+                ' ' => Some(InputEvent::Space),
+                ch if !ch.is_control() => Some(InputEvent::Char(ch)),
+                _ => None,
+            };
+        }
+    }
+}
@@ -0,0 +1,487 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::result;
+use std::cmp;
+
+use rustty::{Terminal, Cell, Color, Attr};
+
+use ecs::*;
+use game::*;
+use game::data::*;
+use game::frontends::ansi::{AnsiTileset, rgb24_to_ansi_colour};
+
+use coord::Coord;
+use colour::Rgb24;
+
+const MESSAGE_LOG_NUM_LINES: usize = 4;
+const MESSAGE_LOG_PLAIN_COLOUR: Rgb24 = Rgb24 { red: 255, green: 255, blue: 255 };
+
+const HUD_TEXT_COLOUR: Rgb24 = Rgb24 { red: 255, green: 255, blue: 255 };
+const HUD_LABEL_COLOUR: Rgb24 = Rgb24 { red: 127, green: 127, blue: 127 };
+
+const UNSEEN_FOREGROUND: Rgb24 = Rgb24 { red: 63, green: 63, blue: 63 };
+const UNSEEN_BACKGROUND: Rgb24 = Rgb24 { red: 15, green: 15, blue: 15 };
+const DEATH_BACKGROUND: Rgb24 = Rgb24 { red: 127, green: 0, blue: 0 };
+const AIM_LINE_BACKGROUND: Rgb24 = Rgb24 { red: 63, green: 63, blue: 0 };
+const AIM_TARGET_BACKGROUND: Rgb24 = Rgb24 { red: 127, green: 127, blue: 0 };
+const SPREAD_CONE_BACKGROUND: Rgb24 = Rgb24 { red: 95, green: 63, blue: 0 };
+const EXPLOSION_RADIUS_BACKGROUND: Rgb24 = Rgb24 { red: 95, green: 31, blue: 0 };
+const EXAMINE_CURSOR_BACKGROUND: Rgb24 = Rgb24 { red: 127, green: 127, blue: 127 };
+const HEALTH_LOW_BACKGROUND: Rgb24 = Rgb24 { red: 127, green: 0, blue: 0 };
+const HEALTH_HIGH_BACKGROUND: Rgb24 = Rgb24 { red: 127, green: 127, blue: 0 };
+
+const SCROLL_BAR_CHAR: char = '|';
+const SCROLL_BAR_COLOUR: Rgb24 = Rgb24 { red: 255, green: 255, blue: 255 };
+
+const MENU_SELECTED_COLOUR: Rgb24 = Rgb24 { red: 255, green: 255, blue: 255 };
+const MENU_DESELECTED_COLOUR: Rgb24 = Rgb24 { red: 127, green: 127, blue: 127 };
+
+#[derive(Debug)]
+pub enum AnsiKnowledgeRendererError {
+    TerminalTooSmall,
+}
+
+struct AnsiCellInfo {
+    ch: char,
+    fg: Color,
+    bg: Color,
+}
+
+pub struct AnsiKnowledgeRenderer {
+    terminal: Rc<RefCell<Terminal>>,
+    buffers: RendererBuffers,
+    tileset: AnsiTileset,
+    width: usize,
+    height: usize,
+    scroll_position: Coord,
+    hud_position: Coord,
+    message_log_position: Coord,
+}
+
+fn put_char(terminal: &mut Terminal, coord: Coord, ch: char, fg: Color, bg: Color) {
+    if coord.x < 0 || coord.y < 0 {
+        return;
+    }
+
+    if let Some(cell) = terminal.get_mut(coord.x as usize, coord.y as usize) {
+        *cell = Cell::new(ch, fg, bg, Attr::Default);
+    }
+}
+
+fn put_string(terminal: &mut Terminal, mut cursor: Coord, string: &str, colour: Rgb24) -> Coord {
+    let fg = rgb24_to_ansi_colour(colour);
+    for ch in string.chars() {
+        put_char(terminal, cursor, ch, fg, Color::Default);
+        cursor.x += 1;
+    }
+
+    cursor
+}
+
+fn put_text_message_part(terminal: &mut Terminal, plain_colour: Rgb24, part: &TextMessagePart, cursor: Coord) -> Coord {
+    let (colour, string) = match *part {
+        TextMessagePart::Plain(ref s) => (plain_colour, s),
+        TextMessagePart::Colour(c, ref s) => (c, s),
+    };
+
+    put_string(terminal, cursor, string, colour)
+}
+
+fn put_message(terminal: &mut Terminal, message: &Message, cursor: Coord) -> Coord {
+    let mut tmp_cursor = cursor;
+    for part in message {
+        if let Some(text_part) = part.as_text() {
+            tmp_cursor = put_text_message_part(terminal, MESSAGE_LOG_PLAIN_COLOUR, text_part, tmp_cursor);
+        }
+    }
+
+    Coord::new(cursor.x, cursor.y + 1)
+}
+
+fn put_text_message(terminal: &mut Terminal, plain_colour: Rgb24, message: &TextMessage, cursor: Coord) -> Coord {
+    let mut tmp_cursor = cursor;
+    for part in message {
+        tmp_cursor = put_text_message_part(terminal, plain_colour, part, tmp_cursor);
+    }
+
+    Coord::new(cursor.x, cursor.y + 1)
+}
+
+fn clear_rows(terminal: &mut Terminal, start: usize, num_rows: usize) {
+    let cols = terminal.cols();
+    for y in start..(start + num_rows) {
+        for x in 0..cols {
+            put_char(terminal, Coord::new(x as isize, y as isize), ' ', Color::Default, Color::Default);
+        }
+    }
+}
+
+fn clear_screen(terminal: &mut Terminal) {
+    let rows = terminal.rows();
+    clear_rows(terminal, 0, rows);
+}
+
+fn put_scroll_bar(terminal: &mut Terminal, num_messages: usize, offset: usize, from_top: bool) {
+    let num_rows = terminal.rows();
+    if num_messages > num_rows {
+        let bar_height = cmp::max((num_rows * num_rows) / num_messages, 1);
+        let remaining = num_rows - bar_height;
+        let max_offset = num_messages - num_rows;
+        let scroll = (offset * remaining) / max_offset;
+        let bar_top = if from_top {
+            scroll
+        } else {
+            remaining - scroll
+        };
+
+        let x = terminal.cols() as isize - 1;
+        let fg = rgb24_to_ansi_colour(SCROLL_BAR_COLOUR);
+        for y in bar_top..(bar_top + bar_height) {
+            put_char(terminal, Coord::new(x, y as isize), SCROLL_BAR_CHAR, fg, Color::Default);
+        }
+    }
+}
+
+impl AnsiKnowledgeRenderer {
+    pub fn new(terminal: Rc<RefCell<Terminal>>,
+               game_width: usize,
+               game_height: usize) -> result::Result<Self, AnsiKnowledgeRendererError> {
+
+        let (cols, rows) = {
+            let terminal = terminal.borrow();
+            (terminal.cols(), terminal.rows())
+        };
+
+        // game window, followed by a line of hud, followed by the message log
+        if cols < game_width || rows < game_height + 1 + MESSAGE_LOG_NUM_LINES {
+            return Err(AnsiKnowledgeRendererError::TerminalTooSmall);
+        }
+
+        Ok(AnsiKnowledgeRenderer {
+            terminal: terminal,
+            buffers: RendererBuffers::new(game_width, game_height, MESSAGE_LOG_NUM_LINES),
+            tileset: AnsiTileset::new(),
+            width: game_width,
+            height: game_height,
+            scroll_position: Coord::new(0, 0),
+            hud_position: Coord::new(0, game_height as isize),
+            message_log_position: Coord::new(0, game_height as isize + 1),
+        })
+    }
+
+    fn to_ansi_info(&self, cell: &CellDrawInfo) -> AnsiCellInfo {
+        let mut info = AnsiCellInfo {
+            ch: ' ',
+            fg: Color::Default,
+            bg: Color::Default,
+        };
+
+        if let Some(bg_type) = cell.background {
+            let tile = self.tileset.resolve(bg_type);
+            info.ch = tile.ch;
+            info.fg = rgb24_to_ansi_colour(tile.foreground);
+            if let Some(bg) = tile.background {
+                info.bg = rgb24_to_ansi_colour(bg);
+            }
+        }
+
+        if let Some(fg_type) = cell.foreground {
+            let tile = self.tileset.resolve(fg_type);
+            info.ch = tile.ch;
+            info.fg = rgb24_to_ansi_colour(tile.foreground);
+            if let Some(bg) = tile.background {
+                info.bg = rgb24_to_ansi_colour(bg);
+            }
+        }
+
+        if !cell.visible {
+            info.fg = rgb24_to_ansi_colour(UNSEEN_FOREGROUND);
+            info.bg = rgb24_to_ansi_colour(UNSEEN_BACKGROUND);
+        }
+
+        if let Some(health_overlay) = cell.health_overlay {
+            if !health_overlay.is_full() {
+                let bg = if health_overlay.ucurrent() * 2 < health_overlay.umax() {
+                    HEALTH_LOW_BACKGROUND
+                } else {
+                    HEALTH_HIGH_BACKGROUND
+                };
+                info.bg = rgb24_to_ansi_colour(bg);
+            }
+        }
+
+        info
+    }
+
+    fn draw_internal(&mut self) {
+        let mut terminal = self.terminal.borrow_mut();
+        for (coord, cell) in izip!(self.buffers.tiles.coord_iter(), self.buffers.tiles.iter()) {
+            let info = self.to_ansi_info(cell);
+            put_char(&mut terminal, coord, info.ch, info.fg, info.bg);
+        }
+    }
+
+    fn draw_overlay_internal(&mut self, overlay: &RenderOverlay) {
+        match *overlay {
+            RenderOverlay::Death => {
+                let mut terminal = self.terminal.borrow_mut();
+                let bg = rgb24_to_ansi_colour(DEATH_BACKGROUND);
+                for (coord, cell) in izip!(self.buffers.tiles.coord_iter(), self.buffers.tiles.iter()) {
+                    let info = self.to_ansi_info(cell);
+                    put_char(&mut terminal, coord, info.ch, info.fg, bg);
+                }
+            }
+            RenderOverlay::AimLine(ref line) => {
+                self.draw_overlay_cells(line, AIM_LINE_BACKGROUND);
+                if let Some(target) = line.last() {
+                    self.draw_overlay_cells(&[*target], AIM_TARGET_BACKGROUND);
+                }
+            }
+            RenderOverlay::BulletPath(ref path) => {
+                self.draw_overlay_cells(path, AIM_LINE_BACKGROUND);
+            }
+            RenderOverlay::SpreadCone(ref cells) => {
+                self.draw_overlay_cells(cells, SPREAD_CONE_BACKGROUND);
+            }
+            RenderOverlay::ExplosionRadius(ref cells) => {
+                self.draw_overlay_cells(cells, EXPLOSION_RADIUS_BACKGROUND);
+            }
+            RenderOverlay::ExamineCursor(coord) => {
+                self.draw_overlay_cells(&[coord], EXAMINE_CURSOR_BACKGROUND);
+            }
+            RenderOverlay::Layers(ref overlays) => {
+                for overlay in overlays {
+                    self.draw_overlay_internal(overlay);
+                }
+            }
+        }
+    }
+
+    /// Redraws the given cells (in world-space) with a different background
+    fn draw_overlay_cells(&mut self, world_coords: &[Coord], background: Rgb24) {
+        let mut terminal = self.terminal.borrow_mut();
+        let bg = rgb24_to_ansi_colour(background);
+        for world_coord in world_coords {
+            let coord = self.world_to_screen(*world_coord);
+            if let Some(cell) = self.buffers.tiles.get(coord) {
+                let info = self.to_ansi_info(cell);
+                put_char(&mut terminal, coord, info.ch, info.fg, bg);
+            }
+        }
+    }
+
+    fn draw_message_log_internal(&mut self) {
+        let mut terminal = self.terminal.borrow_mut();
+        clear_rows(&mut terminal, self.message_log_position.y as usize, MESSAGE_LOG_NUM_LINES);
+
+        let mut cursor = self.message_log_position;
+        for line in &self.buffers.message_log {
+            cursor = put_message(&mut terminal, line, cursor);
+        }
+    }
+
+    fn draw_hud_component(terminal: &mut Terminal, label: &str, text: String, cursor: Coord) -> Coord {
+        let cursor = put_string(terminal, cursor, label, HUD_LABEL_COLOUR);
+        let cursor = put_string(terminal, cursor, text.as_ref(), HUD_TEXT_COLOUR);
+        cursor + Coord::new(1, 0)
+    }
+
+    fn draw_hud_internal(&mut self, entity: EntityRef, y: usize, language: &Box<Language>) {
+        let mut terminal = self.terminal.borrow_mut();
+        clear_rows(&mut terminal, y, 1);
+
+        let mut cursor = Coord::new(0, y as isize);
+
+        let hit_points = entity.hit_points().expect("Entity missing hit_points");
+        let hit_points_text = format!("{}/{}", hit_points.current(), hit_points.max());
+        cursor = Self::draw_hud_component(&mut terminal, "HP:", hit_points_text, cursor);
+
+        let engine = entity.engine_health().expect("Entity missing engine_health");
+        let engine_text = format!("{}/{}", engine.current(), engine.max());
+        cursor = Self::draw_hud_component(&mut terminal, "Eng:", engine_text, cursor);
+
+        let tyres = entity.tyre_health().expect("Entity missing tyre_health");
+        let tyres_text = format!("{}/{}", tyres.current(), tyres.max());
+        cursor = Self::draw_hud_component(&mut terminal, "Tyr:", tyres_text, cursor);
+
+        let armour = entity.armour().expect("Entity missing armour");
+        let armour_text = format!("{}", armour);
+        cursor = Self::draw_hud_component(&mut terminal, "Arm:", armour_text, cursor);
+
+        let speed = entity.current_speed().expect("Entity missing current_speed");
+        let max_speed = entity.player_max_speed().expect("Entity missing max_speed");
+        let speed_text = format!("{}/{}", speed, max_speed);
+        cursor = Self::draw_hud_component(&mut terminal, "Spd:", speed_text, cursor);
+
+        let fuel_text = entity.fuel().map(|fuel| format!("{}/{}", fuel.current(), fuel.max())).unwrap_or_else(|| "-".to_string());
+        cursor = Self::draw_hud_component(&mut terminal, "Fuel:", fuel_text, cursor);
+
+        let letters = entity.letter_count().expect("Entity missing letter_count");
+        let letters_text = format!("{}", letters);
+        cursor = Self::draw_hud_component(&mut terminal, "Ltr:", letters_text, cursor);
+
+        let bank = entity.bank().expect("Entity missing bank");
+        let bank_text = format!("{}", bank);
+        cursor = Self::draw_hud_component(&mut terminal, "$", bank_text, cursor);
+
+        let weather = self.buffers.weather;
+        let mut weather_message = Message::new();
+        language.translate(MessageType::Weather(weather), &mut weather_message);
+        let weather_text = weather_message.iter()
+            .filter_map(MessagePart::as_text)
+            .map(TextMessagePart::as_str)
+            .collect::<String>();
+        Self::draw_hud_component(&mut terminal, "Wx:", weather_text, cursor);
+    }
+
+    fn display_wrapped_message_fullscreen_internal(&mut self, wrapped: &Vec<TextMessage>, offset: usize) -> Coord {
+        let mut terminal = self.terminal.borrow_mut();
+        let mut cursor = Coord::new(0, 0);
+
+        let end_idx = cmp::min(wrapped.len(), offset + terminal.rows());
+
+        for line in &wrapped[offset..end_idx] {
+            cursor = put_text_message(&mut terminal, MESSAGE_LOG_PLAIN_COLOUR, line, cursor);
+        }
+
+        put_scroll_bar(&mut terminal, wrapped.len(), offset, true);
+
+        cursor
+    }
+}
+
+impl KnowledgeRenderer for AnsiKnowledgeRenderer {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn world_offset(&self) -> Coord {
+        self.scroll_position
+    }
+
+    fn update_game_window_buffer(&mut self, knowledge: &DrawableKnowledgeLevel, turn_id: u64, player_position: Coord) {
+        self.scroll_position = self.centre_offset(player_position);
+        self.buffers.tiles.update(knowledge, turn_id, self.scroll_position);
+        self.buffers.weather = knowledge.weather();
+    }
+
+    fn draw_game_window(&mut self) {
+        self.draw_internal();
+    }
+
+    fn draw_game_window_with_overlay(&mut self, overlay: &RenderOverlay) {
+        self.draw_game_window();
+        self.draw_overlay_internal(overlay);
+    }
+
+    fn draw_log(&mut self) {
+        self.draw_message_log_internal();
+    }
+
+    fn update_log_buffer(&mut self, messages: &MessageLog, language: &Box<Language>) {
+        for (log_entry, message) in izip!(messages.tail(MESSAGE_LOG_NUM_LINES), &mut self.buffers.message_log) {
+            message.clear();
+            language.translate_repeated(log_entry.message, log_entry.repeated, message);
+        }
+    }
+
+    fn fullscreen_log(&mut self, message_log: &MessageLog, offset: usize, language: &Box<Language>) {
+        let num_rows = self.fullscreen_log_num_rows();
+        let mut terminal = self.terminal.borrow_mut();
+        clear_screen(&mut terminal);
+
+        let mut cursor = Coord::new(0, 0);
+        let mut message = Message::new();
+
+        for log_entry in message_log.tail_with_offset(num_rows, offset) {
+            message.clear();
+            language.translate_repeated(log_entry.message, log_entry.repeated, &mut message);
+            cursor = put_message(&mut terminal, &message, cursor);
+        }
+
+        put_scroll_bar(&mut terminal, message_log.len(), offset, false);
+    }
+
+    fn fullscreen_log_num_rows(&self) -> usize {
+        self.terminal.borrow().rows()
+    }
+
+    fn fullscreen_log_num_cols(&self) -> usize {
+        // leave space for the scroll bar
+        self.terminal.borrow().cols().saturating_sub(1)
+    }
+
+    fn fullscreen_wrapped_translated_message(&mut self, wrapped: &Vec<TextMessage>, offset: usize) {
+        clear_screen(&mut self.terminal.borrow_mut());
+        self.display_wrapped_message_fullscreen_internal(wrapped, offset);
+    }
+
+    fn draw_hud_bottom(&mut self, entity: EntityRef, language: &Box<Language>) {
+        let y = self.terminal.borrow().rows() - 1;
+        self.draw_hud_internal(entity, y, language);
+    }
+
+    fn draw_hud(&mut self, entity: EntityRef, language: &Box<Language>) {
+        let y = self.hud_position.y as usize;
+        self.draw_hud_internal(entity, y, language);
+    }
+
+    fn fullscreen_menu<T>(&mut self, prelude: Option<MessageType>, menu: &SelectMenu<T>, state: &SelectMenuState, language: &Box<Language>) {
+
+        let mut message = Message::new();
+        let mut wrapped = Vec::new();
+
+        clear_screen(&mut self.terminal.borrow_mut());
+
+        let mut cursor = if let Some(message_type) = prelude {
+            language.translate(message_type, &mut message);
+
+            self.fullscreen_wrap(&message, &mut wrapped);
+
+            let mut cursor = self.display_wrapped_message_fullscreen_internal(&wrapped, 0);
+
+            cursor.y += 1;
+
+            cursor
+        } else {
+            Coord::new(0, 0)
+        };
+
+        let num_cols = self.fullscreen_log_num_cols();
+        let mut terminal = self.terminal.borrow_mut();
+
+        for (item_state, item) in state.iter(menu) {
+            message.clear();
+            language.translate(MessageType::Menu(item.message()), &mut message);
+
+            wrapped.clear();
+            wrap_message(&message, num_cols, &mut wrapped);
+
+            let colour = if item_state == SelectMenuItemState::Selected {
+                MENU_SELECTED_COLOUR
+            } else {
+                MENU_DESELECTED_COLOUR
+            };
+
+            for line in &wrapped {
+                cursor = put_text_message(&mut terminal, colour, line, cursor);
+            }
+        }
+    }
+
+    fn publish(&mut self) {
+        self.terminal.borrow_mut().swap_buffers().expect("Failed to draw to terminal");
+    }
+
+    fn log_num_lines(&self) -> usize {
+        MESSAGE_LOG_NUM_LINES
+    }
+
+    fn reset_buffers(&mut self) {
+        self.buffers.reset();
+    }
+}
@@ -0,0 +1,33 @@
+use std::io;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use rustty::Terminal;
+
+use game::*;
+
+use debug;
+
+pub fn launch(args: Arguments) -> ExternalResult<()> {
+
+    // stdout belongs to the terminal, so debugging output goes to stderr
+    let debug_buffer: Box<io::Write> = if args.debug {
+        Box::new(debug::ErrDebug)
+    } else {
+        Box::new(debug::NullDebug)
+    };
+
+    debug::init(debug_buffer);
+
+    let terminal = Terminal::new().map_err(|e| format!("Failed to initialise terminal: {:?}", e))?;
+    let terminal = Rc::new(RefCell::new(terminal));
+
+    let renderer = frontends::ansi::AnsiKnowledgeRenderer::new(terminal.clone(),
+                                                              GAME_WIDTH,
+                                                              GAME_HEIGHT)
+        .map_err(|e| format!("Failed to create renderer: {:?}", e))?;
+
+    let input = frontends::ansi::AnsiInputSource::new(terminal);
+
+    run_game(renderer, input, args)
+}
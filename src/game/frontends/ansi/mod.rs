@@ -0,0 +1,9 @@
+mod knowledge_renderer;
+mod input;
+mod launcher;
+mod tileset;
+
+pub use self::knowledge_renderer::*;
+pub use self::input::*;
+pub use self::launcher::*;
+pub use self::tileset::*;
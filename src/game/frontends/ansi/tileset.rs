@@ -0,0 +1,78 @@
+use rustty::Color;
+
+use game::*;
+use colour::Rgb24;
+
+/// Converts a 24-bit colour into the nearest colour in the 6x6x6 colour cube
+/// of a 256 colour terminal
+pub fn rgb24_to_ansi_colour(rgb24: Rgb24) -> Color {
+    fn component(c: u8) -> u8 {
+        ((c as u16 * 5 + 127) / 255) as u8
+    }
+
+    Color::Byte(16 + 36 * component(rgb24.red) + 6 * component(rgb24.green) + component(rgb24.blue))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AnsiTile {
+    pub ch: char,
+    pub foreground: Rgb24,
+    pub background: Option<Rgb24>,
+}
+
+impl AnsiTile {
+    fn new(ch: char, foreground: Rgb24) -> Self {
+        AnsiTile {
+            ch: ch,
+            foreground: foreground,
+            background: None,
+        }
+    }
+
+    fn with_background(ch: char, foreground: Rgb24, background: Rgb24) -> Self {
+        AnsiTile {
+            ch: ch,
+            foreground: foreground,
+            background: Some(background),
+        }
+    }
+}
+
+pub struct AnsiTileset;
+
+impl AnsiTileset {
+    pub fn new() -> Self {
+        AnsiTileset
+    }
+
+    pub fn resolve(&self, tile_type: TileType) -> AnsiTile {
+        match tile_type {
+            TileType::Van => AnsiTile::new('@', Rgb24::new(255, 255, 255)),
+            TileType::Car => AnsiTile::new('c', Rgb24::new(255, 63, 63)),
+            TileType::Bike => AnsiTile::new('b', Rgb24::new(255, 127, 0)),
+            TileType::Zombie => AnsiTile::new('z', Rgb24::new(127, 255, 63)),
+            TileType::Wreck0 => AnsiTile::new('%', Rgb24::new(191, 191, 191)),
+            TileType::Wreck1 => AnsiTile::new('%', Rgb24::new(127, 127, 127)),
+            TileType::Wreck2 => AnsiTile::new('%', Rgb24::new(95, 95, 95)),
+            TileType::Bullet => AnsiTile::new('*', Rgb24::new(255, 255, 127)),
+            TileType::Road0 => AnsiTile::with_background('.', Rgb24::new(95, 95, 95), Rgb24::new(31, 31, 31)),
+            TileType::Dirt0 => AnsiTile::with_background('.', Rgb24::new(127, 95, 63), Rgb24::new(63, 31, 0)),
+            TileType::Dirt1 => AnsiTile::with_background(',', Rgb24::new(127, 95, 63), Rgb24::new(63, 31, 0)),
+            TileType::Acid0 => AnsiTile::with_background('~', Rgb24::new(127, 255, 0), Rgb24::new(0, 95, 0)),
+            TileType::Acid1 => AnsiTile::with_background('~', Rgb24::new(63, 191, 0), Rgb24::new(0, 95, 0)),
+            TileType::Bloodstain => AnsiTile::new('.', Rgb24::new(191, 0, 0)),
+            TileType::RailgunSlugHorizontal => AnsiTile::new('-', Rgb24::new(0, 255, 255)),
+            TileType::RailgunSlugVertical => AnsiTile::new('|', Rgb24::new(0, 255, 255)),
+            TileType::Letter => AnsiTile::new('&', Rgb24::new(255, 255, 255)),
+            TileType::Barrel => AnsiTile::new('0', Rgb24::new(255, 127, 0)),
+            TileType::Explosion => AnsiTile::with_background('#', Rgb24::new(255, 255, 0), Rgb24::new(191, 63, 0)),
+            TileType::Building => AnsiTile::with_background('#', Rgb24::new(127, 95, 79), Rgb24::new(63, 47, 39)),
+            TileType::FuelCan => AnsiTile::new('f', Rgb24::new(191, 47, 31)),
+            TileType::Mine => AnsiTile::new('^', Rgb24::new(191, 31, 31)),
+            TileType::Flame => AnsiTile::new('"', Rgb24::new(255, 127, 0)),
+            TileType::Harpoon => AnsiTile::new('>', Rgb24::new(191, 191, 191)),
+            TileType::Rocket => AnsiTile::new('=', Rgb24::new(255, 127, 0)),
+            TileType::RoadMarking => AnsiTile::with_background('-', Rgb24::new(191, 191, 159), Rgb24::new(31, 31, 31)),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::io::{self, Write};
+use std::cmp;
+
+use ecs::*;
+use game::*;
+use game::data::*;
+
+use coord::Coord;
+
+const MESSAGE_LOG_NUM_LINES: usize = 4;
+const FULLSCREEN_NUM_ROWS: usize = 24;
+const FULLSCREEN_NUM_COLS: usize = 80;
+
+fn text_message_part_str(part: &TextMessagePart) -> &str {
+    match *part {
+        TextMessagePart::Plain(ref s) => s,
+        TextMessagePart::Colour(_, ref s) => s,
+    }
+}
+
+fn message_to_string(message: &Message) -> String {
+    let mut string = String::new();
+    for part in message {
+        match *part {
+            MessagePart::Text(ref text) => string.push_str(text_message_part_str(text)),
+            MessagePart::Newline => string.push(' '),
+        }
+    }
+
+    string
+}
+
+fn text_message_to_string(message: &TextMessage) -> String {
+    let mut string = String::new();
+    for part in message {
+        string.push_str(text_message_part_str(part));
+    }
+
+    string
+}
+
+/// Renderer which draws nothing. Instead, the text content of each published
+/// frame (hud, message log, menus and fullscreen messages) is written to an
+/// output stream, so automated runs can be inspected after the fact.
+pub struct HeadlessKnowledgeRenderer {
+    buffers: RendererBuffers,
+    width: usize,
+    height: usize,
+    scroll_position: Coord,
+    frame: Vec<String>,
+    num_frames: u64,
+    output: Box<io::Write>,
+}
+
+impl HeadlessKnowledgeRenderer {
+    pub fn new(game_width: usize, game_height: usize, output: Box<io::Write>) -> Self {
+        HeadlessKnowledgeRenderer {
+            buffers: RendererBuffers::new(game_width, game_height, MESSAGE_LOG_NUM_LINES),
+            width: game_width,
+            height: game_height,
+            scroll_position: Coord::new(0, 0),
+            frame: Vec::new(),
+            num_frames: 0,
+            output: output,
+        }
+    }
+
+    pub fn num_frames(&self) -> u64 {
+        self.num_frames
+    }
+
+    fn draw_hud_internal(&mut self, entity: EntityRef, language: &Box<Language>) {
+        let hit_points = entity.hit_points().expect("Entity missing hit_points");
+        let engine = entity.engine_health().expect("Entity missing engine_health");
+        let tyres = entity.tyre_health().expect("Entity missing tyre_health");
+        let armour = entity.armour().expect("Entity missing armour");
+        let speed = entity.current_speed().expect("Entity missing current_speed");
+        let max_speed = entity.player_max_speed().expect("Entity missing max_speed");
+        let fuel_text = entity.fuel().map(|fuel| format!("{}/{}", fuel.current(), fuel.max())).unwrap_or_else(|| "-".to_string());
+        let letters = entity.letter_count().expect("Entity missing letter_count");
+        let bank = entity.bank().expect("Entity missing bank");
+
+        let mut weather = Message::new();
+        language.translate(MessageType::Weather(self.buffers.weather), &mut weather);
+
+        self.frame.push(format!("hp {}/{} engine {}/{} tyres {}/{} armour {} speed {}/{} fuel {} letters {} bank {} weather {}",
+                                hit_points.current(), hit_points.max(),
+                                engine.current(), engine.max(),
+                                tyres.current(), tyres.max(),
+                                armour,
+                                speed, max_speed,
+                                fuel_text,
+                                letters,
+                                bank,
+                                message_to_string(&weather)));
+    }
+
+    fn draw_wrapped_internal(&mut self, wrapped: &Vec<TextMessage>, offset: usize) {
+        let end_idx = cmp::min(wrapped.len(), offset + FULLSCREEN_NUM_ROWS);
+        for line in &wrapped[offset..end_idx] {
+            self.frame.push(text_message_to_string(line));
+        }
+    }
+}
+
+impl KnowledgeRenderer for HeadlessKnowledgeRenderer {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn world_offset(&self) -> Coord {
+        self.scroll_position
+    }
+
+    fn update_game_window_buffer(&mut self, knowledge: &DrawableKnowledgeLevel, turn_id: u64, player_position: Coord) {
+        self.scroll_position = self.centre_offset(player_position);
+        self.buffers.tiles.update(knowledge, turn_id, self.scroll_position);
+        self.buffers.weather = knowledge.weather();
+    }
+
+    fn draw_game_window(&mut self) {}
+
+    fn draw_game_window_with_overlay(&mut self, overlay: &RenderOverlay) {
+        self.frame.push(format!("overlay {:?}", overlay));
+    }
+
+    fn draw_log(&mut self) {
+        for line in &self.buffers.message_log {
+            self.frame.push(message_to_string(line));
+        }
+    }
+
+    fn update_log_buffer(&mut self, messages: &MessageLog, language: &Box<Language>) {
+        for (log_entry, message) in izip!(messages.tail(MESSAGE_LOG_NUM_LINES), &mut self.buffers.message_log) {
+            message.clear();
+            language.translate_repeated(log_entry.message, log_entry.repeated, message);
+        }
+    }
+
+    fn fullscreen_log(&mut self, message_log: &MessageLog, offset: usize, language: &Box<Language>) {
+        let mut message = Message::new();
+
+        for log_entry in message_log.tail_with_offset(FULLSCREEN_NUM_ROWS, offset) {
+            message.clear();
+            language.translate_repeated(log_entry.message, log_entry.repeated, &mut message);
+            self.frame.push(message_to_string(&message));
+        }
+    }
+
+    fn fullscreen_log_num_rows(&self) -> usize {
+        FULLSCREEN_NUM_ROWS
+    }
+
+    fn fullscreen_log_num_cols(&self) -> usize {
+        FULLSCREEN_NUM_COLS
+    }
+
+    fn fullscreen_wrapped_translated_message(&mut self, wrapped: &Vec<TextMessage>, offset: usize) {
+        self.draw_wrapped_internal(wrapped, offset);
+    }
+
+    fn draw_hud_bottom(&mut self, entity: EntityRef, language: &Box<Language>) {
+        self.draw_hud_internal(entity, language);
+    }
+
+    fn draw_hud(&mut self, entity: EntityRef, language: &Box<Language>) {
+        self.draw_hud_internal(entity, language);
+    }
+
+    fn fullscreen_menu<T>(&mut self, prelude: Option<MessageType>, menu: &SelectMenu<T>, state: &SelectMenuState, language: &Box<Language>) {
+        let mut message = Message::new();
+
+        if let Some(message_type) = prelude {
+            let mut wrapped = Vec::new();
+            language.translate(message_type, &mut message);
+            self.fullscreen_wrap(&message, &mut wrapped);
+            self.draw_wrapped_internal(&wrapped, 0);
+        }
+
+        for (item_state, item) in state.iter(menu) {
+            message.clear();
+            language.translate(MessageType::Menu(item.message()), &mut message);
+
+            let marker = if item_state == SelectMenuItemState::Selected {
+                ">"
+            } else {
+                " "
+            };
+
+            self.frame.push(format!("{} {}", marker, message_to_string(&message)));
+        }
+    }
+
+    fn publish(&mut self) {
+        writeln!(self.output, "--- frame {} ---", self.num_frames).expect("Failed to write frame");
+        for line in self.frame.drain(..) {
+            writeln!(self.output, "{}", line).expect("Failed to write frame");
+        }
+        self.num_frames += 1;
+    }
+
+    fn log_num_lines(&self) -> usize {
+        MESSAGE_LOG_NUM_LINES
+    }
+
+    fn reset_buffers(&mut self) {
+        self.buffers.reset();
+        self.frame.clear();
+    }
+}
@@ -0,0 +1,29 @@
+use std::io;
+
+use game::*;
+use game::frontends::headless::HeadlessKnowledgeRenderer;
+
+use debug;
+
+pub fn launch(args: Arguments) -> ExternalResult<()> {
+
+    // stdout carries the frame transcript, so debugging output goes to stderr
+    let debug_buffer: Box<io::Write> = if args.debug {
+        Box::new(debug::ErrDebug)
+    } else {
+        Box::new(debug::NullDebug)
+    };
+
+    debug::init(debug_buffer);
+
+    let inputs = match args.script_path {
+        Some(ref path) => read_input_script(path)?,
+        None if args.replay_path.is_some() => Vec::new(),
+        None => return Err("headless frontend requires an input script".to_string()),
+    };
+
+    let renderer = HeadlessKnowledgeRenderer::new(GAME_WIDTH, GAME_HEIGHT, Box::new(io::stdout()));
+    let input = ScriptedInputSource::new(inputs);
+
+    run_game(renderer, input, args)
+}
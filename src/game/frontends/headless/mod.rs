@@ -0,0 +1,8 @@
+mod knowledge_renderer;
+mod launcher;
+
+pub use self::knowledge_renderer::*;
+pub use self::launcher::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,194 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path;
+
+use game::*;
+use game::frontends::headless::HeadlessKnowledgeRenderer;
+
+const RNG_SEED: usize = 0;
+
+/// Empty user directory unique to a test
+fn user_dir(name: &str) -> path::PathBuf {
+    let path = env::temp_dir().join(format!("apocalypse-post-headless-{}", name));
+    if path.exists() {
+        fs::remove_dir_all(&path).expect("Failed to remove old user directory");
+    }
+    fs::create_dir_all(&path).expect("Failed to create user directory");
+    path
+}
+
+fn run_script(name: &str, script: &str) -> path::PathBuf {
+    let user_path = user_dir(name);
+    run_script_in(&user_path, script);
+    user_path
+}
+
+fn run_script_in(user_path: &path::Path, script: &str) {
+    let mut args = Arguments::default();
+    args.frontend = Frontend::Headless;
+    args.rng_seed = RNG_SEED;
+    args.resource_path = path::Path::new(env!("CARGO_MANIFEST_DIR")).join("resources");
+    args.user_path = user_path.to_path_buf();
+
+    let inputs = parse_input_script(script).expect("Invalid input script");
+    let renderer = HeadlessKnowledgeRenderer::new(GAME_WIDTH, GAME_HEIGHT, Box::new(io::sink()));
+
+    run_game(renderer, ScriptedInputSource::new(inputs), args).expect("Scripted run failed");
+}
+
+/// User directory with a game saved in every slot
+fn full_user_dir(name: &str) -> path::PathBuf {
+    let user_path = run_script(name, "
+# new game, then pause back to the main menu
+Return
+Space
+Escape
+");
+
+    let (_, contents) = save_file::slot_contents(&user_path).pop().expect("Missing save");
+    for slot in 1..save_file::NUM_SAVE_SLOTS {
+        save_file::restore_slot(&user_path, slot, &contents).expect("Failed to copy save");
+    }
+
+    user_path
+}
+
+#[test]
+fn empty_script_quits_from_main_menu() {
+    let user_path = run_script("empty", "");
+
+    assert_eq!(save_file::most_recent_slot(&user_path), None);
+}
+
+#[test]
+fn script_ending_in_level_saves() {
+    let user_path = run_script("in_level", "
+# new game
+Return
+Right
+Right
+Space
+Up
+Space
+");
+
+    assert!(save_file::slot_metadata(&user_path, 0).expect("Unreadable save").is_some());
+}
+
+#[test]
+fn failing_to_save_does_not_end_the_game() {
+    let user_path = user_dir("save_failed");
+
+    // a directory in the way of the temporary save file makes every save fail
+    fs::create_dir(user_path.join("save.tmp")).expect("Failed to block save");
+
+    run_script_in(&user_path, "
+# new game
+Return
+Right
+Right
+Space
+Up
+Space
+");
+
+    assert!(save_file::slot_metadata(&user_path, 0).expect("Unreadable save").is_none());
+}
+
+#[test]
+fn script_ending_on_main_menu_saves() {
+    let user_path = run_script("main_menu", "
+# new game, then pause back to the main menu
+Return
+Space
+Escape
+");
+
+    assert!(save_file::slot_metadata(&user_path, 0).expect("Unreadable save").is_some());
+}
+
+#[test]
+fn scripted_input_repeats_quit_once_exhausted() {
+    let mut input = ScriptedInputSource::new(vec![InputEvent::Return]);
+
+    assert_eq!(input.next_input(), Some(InputEvent::Return));
+    assert_eq!(input.next_input(), Some(InputEvent::Quit));
+    assert_eq!(input.next_input(), Some(InputEvent::Quit));
+    assert_eq!(input.remaining(), 0);
+}
+
+#[test]
+fn new_game_with_full_slots_can_be_cancelled() {
+    let user_path = full_user_dir("full_cancel");
+    let unreadable_slot = 3;
+    save_file::restore_slot(&user_path, unreadable_slot, b"not a save").expect("Failed to write slot");
+
+    // quitting from the main menu saves the loaded game back to its own slot
+    let loaded_slot = save_file::most_recent_slot(&user_path).expect("Missing save");
+    let before = save_file::slot_contents(&user_path);
+
+    run_script_in(&user_path, "
+# new game, then back out of choosing a slot to overwrite
+Down
+Return
+Escape
+");
+
+    let after = save_file::slot_contents(&user_path);
+    assert_eq!(after.len(), save_file::NUM_SAVE_SLOTS);
+    for slot in 0..save_file::NUM_SAVE_SLOTS {
+        if slot != loaded_slot {
+            assert_eq!(before[slot], after[slot]);
+        }
+    }
+}
+
+#[test]
+fn new_game_with_full_slots_overwrites_chosen_slot() {
+    let user_path = full_user_dir("full_overwrite");
+    let unreadable_slot = 3;
+    save_file::restore_slot(&user_path, unreadable_slot, b"not a save").expect("Failed to write slot");
+
+    run_script_in(&user_path, "
+# new game, overwriting the unreadable slot
+Down
+Return
+Down
+Down
+Down
+Return
+");
+
+    assert!(save_file::slot_metadata(&user_path, unreadable_slot).expect("Unreadable save").is_some());
+}
+
+#[test]
+fn failing_to_load_returns_to_saved_games() {
+    let user_path = run_script("load_failed", "
+# new game, then pause back to the main menu
+Return
+Space
+Escape
+");
+
+    // keep the envelope intact so the slot is listed, but damage the game
+    let (slot, mut contents) = save_file::slot_contents(&user_path).pop().expect("Missing save");
+    let len = contents.len();
+    for byte in contents[len / 2..].iter_mut() {
+        *byte = 0xff;
+    }
+    save_file::delete(&user_path, slot);
+    save_file::restore_slot(&user_path, slot, &contents).expect("Failed to write slot");
+    assert!(save_file::slot_metadata(&user_path, slot).expect("Unreadable save").is_some());
+
+    run_script_in(&user_path, "
+# saved games, then load the damaged slot
+Down
+Return
+Return
+Return
+");
+
+    assert!(save_file::load(&user_path, slot).is_err());
+}
@@ -19,6 +19,8 @@ pub struct Hud {
     pub armour: Rect,
     pub letter: Rect,
     pub money: Rect,
+    pub weather: Rect,
+    pub fuel: Rect,
 }
 
 impl Hud {
@@ -52,6 +54,8 @@ impl Hud {
             armour: Self::get_rect(symbol_table, "Armour", symbol_width, symbol_height)?,
             letter: Self::get_rect(symbol_table, "Letter", symbol_width, symbol_height)?,
             money: Self::get_rect(symbol_table, "Money", symbol_width, symbol_height)?,
+            weather: Self::get_rect(symbol_table, "Weather", symbol_width, symbol_height)?,
+            fuel: Self::get_rect(symbol_table, "Fuel", symbol_width, symbol_height)?,
         })
     }
 }
@@ -565,6 +565,49 @@ impl<'a, 'b> SdlKnowledgeRenderer<'a, 'b> {
                     self.renderer.draw_overlay_cell(cell, coord, death_rect, &self.textures);
                 }
             }
+            RenderOverlay::AimLine(ref line) => {
+                let line_rect = self.renderer.tileset.extra.aim_line;
+                let target_rect = self.renderer.tileset.extra.aim_target;
+                let (target, rest) = match line.split_last() {
+                    Some((target, rest)) => (Some(*target), rest),
+                    None => (None, &line[..]),
+                };
+                self.draw_overlay_cells(rest, line_rect);
+                if let Some(target) = target {
+                    self.draw_overlay_cells(&[target], target_rect);
+                }
+            }
+            RenderOverlay::BulletPath(ref path) => {
+                let rect = self.renderer.tileset.extra.aim_line;
+                self.draw_overlay_cells(path, rect);
+            }
+            RenderOverlay::SpreadCone(ref cells) => {
+                let rect = self.renderer.tileset.extra.spread_cone;
+                self.draw_overlay_cells(cells, rect);
+            }
+            RenderOverlay::ExplosionRadius(ref cells) => {
+                let rect = self.renderer.tileset.extra.explosion_radius;
+                self.draw_overlay_cells(cells, rect);
+            }
+            RenderOverlay::ExamineCursor(coord) => {
+                let rect = self.renderer.tileset.extra.examine_cursor;
+                self.draw_overlay_cells(&[coord], rect);
+            }
+            RenderOverlay::Layers(ref overlays) => {
+                for overlay in overlays {
+                    self.draw_overlay_internal(overlay);
+                }
+            }
+        }
+    }
+
+    /// Draws an overlay tile over each of the given cells (in world-space)
+    fn draw_overlay_cells(&mut self, world_coords: &[Coord], overlay: Rect) {
+        for world_coord in world_coords {
+            let coord = self.world_to_screen(*world_coord);
+            if let Some(cell) = self.buffers.tiles.get(coord) {
+                self.renderer.draw_overlay_cell(cell, coord, overlay, &self.textures);
+            }
         }
     }
 
@@ -593,7 +636,7 @@ impl<'a, 'b> SdlKnowledgeRenderer<'a, 'b> {
         x + text_width + self.renderer.hud_height_px()
     }
 
-    fn draw_hud_internal(&mut self, entity: EntityRef, y: usize) {
+    fn draw_hud_internal(&mut self, entity: EntityRef, y: usize, language: &Box<Language>) {
         let mut cursor = LEFT_PADDING_PX;
 
         let hit_points = entity.hit_points().expect("Entity missing hit_points");
@@ -622,6 +665,10 @@ impl<'a, 'b> SdlKnowledgeRenderer<'a, 'b> {
         let speed_symbol = self.renderer.hud.speed;
         cursor = self.draw_hud_component(speed_symbol, speed_text, cursor, y);
 
+        let fuel_text = entity.fuel().map(|fuel| format!("{}/{}", fuel.current(), fuel.max())).unwrap_or_else(|| "-".to_string());
+        let fuel_symbol = self.renderer.hud.fuel;
+        cursor = self.draw_hud_component(fuel_symbol, fuel_text, cursor, y);
+
         let letters = entity.letter_count().expect("Entity missing letter_count");
         let letters_text = format!("{}", letters);
         let letters_symbol = self.renderer.hud.letter;
@@ -630,7 +677,17 @@ impl<'a, 'b> SdlKnowledgeRenderer<'a, 'b> {
         let bank = entity.bank().expect("Entity missing bank");
         let bank_text = format!("{}", bank);
         let bank_symbol = self.renderer.hud.money;
-        self.draw_hud_component(bank_symbol, bank_text, cursor, y);
+        cursor = self.draw_hud_component(bank_symbol, bank_text, cursor, y);
+
+        let weather = self.buffers.weather;
+        let mut weather_message = Message::new();
+        language.translate(MessageType::Weather(weather), &mut weather_message);
+        let weather_text = weather_message.iter()
+            .filter_map(MessagePart::as_text)
+            .map(TextMessagePart::as_str)
+            .collect::<String>();
+        let weather_symbol = self.renderer.hud.weather;
+        self.draw_hud_component(weather_symbol, weather_text, cursor, y);
     }
 
 
@@ -652,6 +709,7 @@ impl<'a, 'b> KnowledgeRenderer for SdlKnowledgeRenderer<'a, 'b> {
     fn update_game_window_buffer(&mut self, knowledge: &DrawableKnowledgeLevel, turn_id: u64, player_position: Coord) {
         self.renderer.scroll_position = self.centre_offset(player_position);
         self.buffers.tiles.update(knowledge, turn_id, self.renderer.scroll_position);
+        self.buffers.weather = knowledge.weather();
     }
 
     fn draw_game_window(&mut self) {
@@ -700,7 +758,7 @@ impl<'a, 'b> KnowledgeRenderer for SdlKnowledgeRenderer<'a, 'b> {
     }
 
     fn fullscreen_log_num_cols(&self) -> usize {
-        self.renderer.display_log_num_cols - 1
+        self.renderer.display_log_num_cols.saturating_sub(1)
     }
 
     fn fullscreen_wrapped_translated_message(&mut self, wrapped: &Vec<TextMessage>, offset: usize) {
@@ -709,15 +767,15 @@ impl<'a, 'b> KnowledgeRenderer for SdlKnowledgeRenderer<'a, 'b> {
     }
 
 
-    fn draw_hud_bottom(&mut self, entity: EntityRef, _language: &Box<Language>) {
+    fn draw_hud_bottom(&mut self, entity: EntityRef, language: &Box<Language>) {
         let y = self.renderer.total_height_px - self.renderer.hud_height_px() - HUD_TOP_PADDING_PX - BOTTOM_PADDING_PX;
-        self.draw_hud_internal(entity, y);
+        self.draw_hud_internal(entity, y, language);
     }
 
-    fn draw_hud(&mut self, entity: EntityRef, _language: &Box<Language>) {
+    fn draw_hud(&mut self, entity: EntityRef, language: &Box<Language>) {
         self.renderer.clear_hud();
         let y = self.renderer.hud_position.y as usize + HUD_TOP_PADDING_PX;
-        self.draw_hud_internal(entity, y);
+        self.draw_hud_internal(entity, y, language);
     }
 
     fn fullscreen_menu<T>(&mut self, prelude: Option<MessageType>, menu: &SelectMenu<T>, state: &SelectMenuState, language: &Box<Language>) {
@@ -754,8 +812,10 @@ impl<'a, 'b> KnowledgeRenderer for SdlKnowledgeRenderer<'a, 'b> {
                 MENU_DESELECTED_COLOUR
             };
 
-            cursor = self.renderer.render_text_message(colour, &wrapped[0], cursor);
-            cursor.y += self.renderer.text_padding_px() as isize;
+            for line in &wrapped {
+                cursor = self.renderer.render_text_message(colour, line, cursor);
+                cursor.y += self.renderer.text_padding_px() as isize;
+            }
         }
     }
 
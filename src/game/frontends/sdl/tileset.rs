@@ -18,6 +18,11 @@ pub type TilesetResult<T> = result::Result<T, TilesetError>;
 pub struct ExtraTiles {
     pub blank: Rect,
     pub death: Rect,
+    pub aim_line: Rect,
+    pub aim_target: Rect,
+    pub spread_cone: Rect,
+    pub explosion_radius: Rect,
+    pub examine_cursor: Rect,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -198,6 +203,11 @@ impl Tileset {
         let extra = ExtraTiles {
             blank: extra_rect(&extra_table, "Blank",  tile_width, tile_height, tile_padding)?,
             death: extra_rect(&extra_table, "Death",  tile_width, tile_height, tile_padding)?,
+            aim_line: extra_rect(&extra_table, "AimLine",  tile_width, tile_height, tile_padding)?,
+            aim_target: extra_rect(&extra_table, "AimTarget",  tile_width, tile_height, tile_padding)?,
+            spread_cone: extra_rect(&extra_table, "SpreadCone",  tile_width, tile_height, tile_padding)?,
+            explosion_radius: extra_rect(&extra_table, "ExplosionRadius",  tile_width, tile_height, tile_padding)?,
+            examine_cursor: extra_rect(&extra_table, "ExamineCursor",  tile_width, tile_height, tile_padding)?,
         };
 
         Ok(Tileset {
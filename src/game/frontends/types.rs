@@ -1,3 +0,0 @@
-pub enum Frontend {
-    Ansi,
-}
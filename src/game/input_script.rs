@@ -0,0 +1,94 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path;
+
+use game::*;
+
+#[derive(Debug)]
+pub enum InputScriptError {
+    InvalidInput {
+        line: usize,
+        input: String,
+    },
+}
+
+const QUIT: &'static str = "Quit";
+
+/// Name of an input as it appears in an input script
+pub fn input_script_line(input: InputEvent) -> String {
+    match input {
+        InputEvent::Quit => QUIT.to_string(),
+        other => String::from(other),
+    }
+}
+
+fn parse_input_script_line(line: &str) -> Option<InputEvent> {
+    if line == QUIT {
+        Some(InputEvent::Quit)
+    } else {
+        InputEvent::from_str(line)
+    }
+}
+
+/// Parses an input script. Each non-empty line names a single input, using
+/// the same names as the controls file, plus "Quit". Lines beginning with
+/// '#' are ignored.
+pub fn parse_input_script(script: &str) -> Result<Vec<InputEvent>, InputScriptError> {
+    let mut inputs = Vec::new();
+
+    for (index, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let input = parse_input_script_line(line).ok_or_else(|| InputScriptError::InvalidInput {
+            line: index + 1,
+            input: line.to_string(),
+        })?;
+
+        inputs.push(input);
+    }
+
+    Ok(inputs)
+}
+
+pub fn read_input_script<P: AsRef<path::Path>>(path: P) -> ExternalResult<Vec<InputEvent>> {
+    let script = game_file::read_string(path.as_ref())
+        .map_err(|e| format!("Couldn't read input script {:?}: {:?}", path.as_ref(), e))?;
+
+    match parse_input_script(&script) {
+        Ok(inputs) => Ok(inputs),
+        Err(InputScriptError::InvalidInput { line, input }) => {
+            Err(format!("Invalid input on line {} of {:?}: {}", line, path.as_ref(), input))
+        }
+    }
+}
+
+/// Input source which replays a fixed sequence of inputs. Clones share their
+/// position in the sequence. Once the sequence is exhausted, `Quit` is
+/// returned for every further request, so the game saves and exits from
+/// wherever the script left it.
+#[derive(Clone)]
+pub struct ScriptedInputSource {
+    inputs: Rc<RefCell<VecDeque<InputEvent>>>,
+}
+
+impl ScriptedInputSource {
+    pub fn new(inputs: Vec<InputEvent>) -> Self {
+        ScriptedInputSource {
+            inputs: Rc::new(RefCell::new(inputs.into_iter().collect())),
+        }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.inputs.borrow().len()
+    }
+}
+
+impl InputSource for ScriptedInputSource {
+    fn next_input(&mut self) -> Option<InputEvent> {
+        Some(self.inputs.borrow_mut().pop_front().unwrap_or(InputEvent::Quit))
+    }
+}
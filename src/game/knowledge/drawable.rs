@@ -7,6 +7,10 @@ use coord::Coord;
 
 pub type DrawableKnowledge = GameKnowledge<DrawableKnowledgeLevel>;
 
+/// Cells with less light than this can't be seen, though their contents
+/// are still remembered from when they were last lit
+pub const MIN_VISIBLE_LIGHT: f64 = 0.1;
+
 #[derive(Serialize, Deserialize)]
 pub struct DrawableKnowledgeCell {
     last_updated: u64,
@@ -108,6 +112,7 @@ pub struct DrawableKnowledgeLevel {
     default: DrawableKnowledgeCell,
     targets: Vec<Coord>,
     last_action_id: u64,
+    weather: Weather,
 }
 
 impl DrawableKnowledgeLevel {
@@ -132,9 +137,47 @@ impl DrawableKnowledgeLevel {
         self.grid.width()
     }
 
+    /// Weather the level had when it was last observed
+    pub fn weather(&self) -> Weather {
+        self.weather
+    }
+
+    pub fn set_weather(&mut self, weather: Weather) {
+        self.weather = weather;
+    }
+
     pub fn height(&self) -> usize {
         self.grid.height()
     }
+
+    /// View of this knowledge which is only updated in cells which the given
+    /// light map makes visible from `eye`, for an entity which can see
+    /// `distance` cells in full light
+    pub fn lit<'a>(&'a mut self, light_map: &'a LightMap, eye: Coord, distance: usize) -> LitDrawableKnowledgeLevel<'a> {
+        LitDrawableKnowledgeLevel {
+            level: self,
+            light_map: light_map,
+            eye: eye,
+            distance: distance,
+        }
+    }
+}
+
+pub struct LitDrawableKnowledgeLevel<'a> {
+    level: &'a mut DrawableKnowledgeLevel,
+    light_map: &'a LightMap,
+    eye: Coord,
+    distance: usize,
+}
+
+impl<'a> LevelKnowledge for LitDrawableKnowledgeLevel<'a> {
+    fn update_cell(&mut self, coord: Coord, world_cell: &SpatialHashCell, accuracy: f64, action_env: ActionEnv) -> bool {
+        if !self.light_map.can_see(self.eye, coord, self.distance) {
+            return false;
+        }
+
+        self.level.update_cell(coord, world_cell, accuracy, action_env)
+    }
 }
 
 impl LevelKnowledge for DrawableKnowledgeLevel {
@@ -172,6 +215,7 @@ impl TwoDimensionalCons for DrawableKnowledgeLevel {
             default: DrawableKnowledgeCell::new(),
             targets: Vec::new(),
             last_action_id: 0,
+            weather: Weather::Clear,
         }
     }
 }
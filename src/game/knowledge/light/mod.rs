@@ -0,0 +1,130 @@
+use game::*;
+use game::data::*;
+use ecs::*;
+use spatial_hash::*;
+use grid::{Grid, StaticGrid, CopyGrid};
+use direction::Direction;
+use coord::Coord;
+
+/// Light level of cells in full daylight
+const FULL_LIGHT: f64 = 1.0;
+
+/// The level of light in each cell of a level, from the ambient light and
+/// from each entity with a light. Where lights overlap, the brightest wins.
+pub struct LightMap {
+    grid: StaticGrid<f64>,
+    ambient: f64,
+}
+
+impl LightMap {
+    pub fn new(width: usize, height: usize, ambient: f64) -> Self {
+        LightMap {
+            grid: StaticGrid::new_copy(width, height, ambient),
+            ambient: ambient,
+        }
+    }
+
+    /// Light map of a level in the given weather, lit by every positioned
+    /// entity with a light
+    pub fn with_lights(weather: Weather,
+                       ecs: &EcsCtx,
+                       world: &SpatialHashTable,
+                       shadowcast: &Shadowcast,
+                       action_env: ActionEnv) -> Self {
+
+        let mut light_map = LightMap::new(world.width(), world.height(), weather.ambient_light());
+        light_map.add_lights(ecs, world, shadowcast, weather.opacity(), action_env);
+
+        light_map
+    }
+
+    /// Light level of a cell between 0 and 1
+    pub fn level(&self, coord: Coord) -> f64 {
+        self.grid.get(coord).cloned().unwrap_or(self.ambient)
+    }
+
+    /// Returns true iff a cell is light enough to be seen from `eye` by an
+    /// entity which can see `distance` cells in full light. Cells lit only
+    /// by the ambient light can be seen out to that fraction of `distance`.
+    pub fn can_see(&self, eye: Coord, coord: Coord, distance: usize) -> bool {
+        let level = self.level(coord);
+
+        if level < MIN_VISIBLE_LIGHT {
+            return false;
+        }
+
+        if level > self.ambient {
+            return true;
+        }
+
+        let ambient_distance = distance as f64 * self.ambient;
+
+        ((coord - eye).length_squared() as f64) < ambient_distance * ambient_distance
+    }
+
+    pub fn add_light(&mut self,
+                     position: Coord,
+                     light: Light,
+                     facing: Option<Direction>,
+                     world: &SpatialHashTable,
+                     shadowcast: &Shadowcast,
+                     ambient_opacity: f64,
+                     action_env: ActionEnv) {
+
+        // lights make no difference in full daylight
+        if self.ambient >= FULL_LIGHT {
+            return;
+        }
+
+        let mut source = LightSource {
+            map: self,
+            position: position,
+            light: light,
+            facing: facing,
+        };
+
+        shadowcast.observe(position, world, light.radius, ambient_opacity, &mut source, action_env);
+    }
+
+    /// Adds the light of every positioned entity with a light component
+    pub fn add_lights(&mut self,
+                      ecs: &EcsCtx,
+                      world: &SpatialHashTable,
+                      shadowcast: &Shadowcast,
+                      ambient_opacity: f64,
+                      action_env: ActionEnv) {
+
+        for (id, light) in ecs.light_iter() {
+            if let Some(position) = ecs.position(id) {
+                self.add_light(position, light, ecs.facing(id), world, shadowcast, ambient_opacity, action_env);
+            }
+        }
+    }
+}
+
+/// Casts a single light onto a light map. Treating the light as an observer
+/// means light is blocked by the same things that block vision.
+struct LightSource<'a> {
+    map: &'a mut LightMap,
+    position: Coord,
+    light: Light,
+    facing: Option<Direction>,
+}
+
+impl<'a> LevelKnowledge for LightSource<'a> {
+    fn update_cell(&mut self, coord: Coord, _world_cell: &SpatialHashCell, accuracy: f64, _action_env: ActionEnv) -> bool {
+        let brightness = self.light.brightness(coord - self.position, self.facing, accuracy);
+
+        if let Some(level) = self.map.grid.get_mut(coord) {
+            if brightness > *level {
+                *level = brightness;
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+use ecs::*;
+use game::*;
+use game::data::*;
+use spatial_hash::*;
+use util::LeakyReserver;
+use coord::Coord;
+use super::*;
+
+const WIDTH: usize = 20;
+const HEIGHT: usize = 10;
+const ACTION_ID: u64 = 1;
+const AMBIENT: f64 = 0.0;
+
+struct Env {
+    sh: SpatialHashTable,
+    ecs: EcsCtx,
+    shadowcast: Shadowcast,
+}
+
+impl Env {
+    /// A level with lights shining in all directions and opaque walls at the
+    /// given coordinates
+    fn new(lights: &[(Coord, usize)], walls: &[Coord]) -> Self {
+        let mut env = Env {
+            sh: SpatialHashTable::new(WIDTH, HEIGHT),
+            ecs: EcsCtx::new(),
+            shadowcast: Shadowcast::new(),
+        };
+
+        let mut ids = LeakyReserver::new();
+        let mut action = EcsAction::new();
+
+        for &(position, radius) in lights {
+            let mut entity = action.entity_mut(ids.reserve());
+            entity.insert_position(position);
+            entity.insert_light(Light::new(radius, LightDirection::All));
+        }
+
+        for wall in walls {
+            let mut entity = action.entity_mut(ids.reserve());
+            entity.insert_position(*wall);
+            entity.insert_solid();
+            entity.insert_opacity(1.0);
+        }
+
+        env.sh.update(&env.ecs, &action, ACTION_ID);
+        env.ecs.commit(&mut action);
+
+        env
+    }
+
+    fn light_map(&self, ambient: f64) -> LightMap {
+        let mut light_map = LightMap::new(WIDTH, HEIGHT, ambient);
+        light_map.add_lights(&self.ecs, &self.sh, &self.shadowcast, 0.0, ActionEnv::new(&self.ecs, ACTION_ID));
+        light_map
+    }
+}
+
+#[test]
+fn unlit_cells_have_ambient_light() {
+    let env = Env::new(&[], &[]);
+    let light_map = env.light_map(0.5);
+
+    assert_eq!(light_map.level(Coord::new(3, 3)), 0.5);
+    assert_eq!(light_map.level(Coord::new(-1, 3)), 0.5);
+}
+
+#[test]
+fn light_fades_towards_its_radius() {
+    let env = Env::new(&[(Coord::new(5, 5), 4)], &[]);
+    let light_map = env.light_map(AMBIENT);
+
+    assert_eq!(light_map.level(Coord::new(5, 5)), 1.0);
+    assert!(light_map.level(Coord::new(6, 5)) > light_map.level(Coord::new(7, 5)));
+    assert!(light_map.level(Coord::new(7, 5)) > light_map.level(Coord::new(8, 5)));
+    assert_eq!(light_map.level(Coord::new(9, 5)), AMBIENT);
+}
+
+#[test]
+fn brightest_overlapping_light_wins() {
+    let env = Env::new(&[(Coord::new(5, 5), 4), (Coord::new(8, 5), 4)], &[]);
+    let light_map = env.light_map(AMBIENT);
+
+    let alone = Env::new(&[(Coord::new(5, 5), 4)], &[]).light_map(AMBIENT);
+
+    // each source is brightest where it stands, rather than the lights adding up
+    assert_eq!(light_map.level(Coord::new(5, 5)), 1.0);
+    assert_eq!(light_map.level(Coord::new(8, 5)), 1.0);
+    assert_eq!(light_map.level(Coord::new(6, 5)), alone.level(Coord::new(6, 5)));
+    assert_eq!(light_map.level(Coord::new(7, 5)), alone.level(Coord::new(6, 5)));
+}
+
+#[test]
+fn light_below_ambient_is_ignored() {
+    let env = Env::new(&[(Coord::new(5, 5), 4)], &[]);
+    let light_map = env.light_map(0.5);
+
+    assert_eq!(light_map.level(Coord::new(5, 5)), 1.0);
+    assert_eq!(light_map.level(Coord::new(8, 5)), 0.5);
+}
+
+#[test]
+fn lights_are_not_cast_in_full_light() {
+    let env = Env::new(&[(Coord::new(5, 5), 4)], &[]);
+    let light_map = env.light_map(FULL_LIGHT);
+
+    assert_eq!(light_map.level(Coord::new(5, 5)), FULL_LIGHT);
+    assert_eq!(light_map.level(Coord::new(6, 5)), FULL_LIGHT);
+}
+
+#[test]
+fn walls_block_light() {
+    let env = Env::new(&[(Coord::new(5, 5), 6)], &[Coord::new(7, 4), Coord::new(7, 5), Coord::new(7, 6)]);
+    let light_map = env.light_map(AMBIENT);
+
+    assert!(light_map.level(Coord::new(7, 5)) > AMBIENT);
+    assert_eq!(light_map.level(Coord::new(8, 5)), AMBIENT);
+}
+
+#[test]
+fn dark_cells_cannot_be_seen() {
+    let light_map = LightMap::new(WIDTH, HEIGHT, 0.0);
+
+    assert!(!light_map.can_see(Coord::new(5, 5), Coord::new(6, 5), 10));
+}
+
+#[test]
+fn dim_ambient_light_shortens_vision() {
+    let light_map = LightMap::new(WIDTH, HEIGHT, 0.5);
+    let eye = Coord::new(0, 5);
+
+    assert!(light_map.can_see(eye, Coord::new(4, 5), 10));
+    assert!(!light_map.can_see(eye, Coord::new(5, 5), 10));
+    assert!(!light_map.can_see(eye, Coord::new(8, 5), 10));
+}
+
+#[test]
+fn lit_cells_can_be_seen_through_dim_light() {
+    let env = Env::new(&[(Coord::new(8, 5), 3)], &[]);
+    let light_map = env.light_map(0.5);
+    let eye = Coord::new(0, 5);
+
+    assert!(light_map.can_see(eye, Coord::new(8, 5), 10));
+    assert!(!light_map.can_see(eye, Coord::new(8, 2), 10));
+}
@@ -3,9 +3,11 @@ mod blind;
 mod shadowcast;
 mod simple_npc;
 mod drawable;
+mod light;
 
 pub use self::knowledge::*;
 pub use self::blind::*;
 pub use self::shadowcast::*;
 pub use self::simple_npc::*;
 pub use self::drawable::*;
+pub use self::light::*;
@@ -279,6 +279,7 @@ struct OctantArgs<'a> {
     eye: Coord,
     distance: usize,
     distance_squared: isize,
+    ambient_opacity: f64,
     initial_min_slope: f64,
     initial_max_slope: f64,
 }
@@ -288,6 +289,7 @@ impl<'a> OctantArgs<'a> {
            world: &'a SpatialHashTable,
            eye: Coord,
            distance: usize,
+           ambient_opacity: f64,
            initial_min_slope: f64,
            initial_max_slope: f64)
            -> Self {
@@ -297,6 +299,7 @@ impl<'a> OctantArgs<'a> {
             eye: eye,
             distance: distance,
             distance_squared: (distance * distance) as isize,
+            ambient_opacity: ambient_opacity,
             initial_min_slope: initial_min_slope,
             initial_max_slope: initial_max_slope,
         }
@@ -364,7 +367,7 @@ impl Shadowcast {
             }
 
             // compute current visibility
-            let current_visibility = (scan.frame.visibility - cell.opacity()).max(0.0);
+            let current_visibility = (scan.frame.visibility - cell.opacity() - args.ambient_opacity).max(0.0);
             let current_opaque = current_visibility == 0.0;
 
             // process changes in visibility
@@ -437,17 +440,22 @@ impl Shadowcast {
     }
 
     // returns true iff the knowledge was changed
+    // ambient_opacity is added to the opacity of every cell other than the eye.
+    // It comes from the level's weather, which is the same in every cell and
+    // never changes, so it's passed in here rather than stored in each cell of
+    // the spatial hash.
     pub fn observe<K: LevelKnowledge>(&self,
                                       eye: Coord,
                                       world: &SpatialHashTable,
                                       distance: usize,
+                                      ambient_opacity: f64,
                                       knowledge: &mut K,
                                       action_env: ActionEnv) -> bool {
 
         let mut changed = knowledge.update_cell(eye, world.get(eye), 1.0, action_env);
 
         for octant in &self.octants {
-            let args = OctantArgs::new(octant, world, eye, distance, 0.0, 1.0);
+            let args = OctantArgs::new(octant, world, eye, distance, ambient_opacity, 0.0, 1.0);
             changed = self.detect_visible_area_octant(&args, knowledge, action_env) || changed;
         }
 
@@ -92,6 +92,22 @@ impl SimpleNpcKnowledgeLevel {
         }
         None
     }
+
+    pub fn targets(&self) -> Vec<Coord> {
+        self.targets.iter().cloned().collect()
+    }
+
+    /// Records a target seen during the action with id `action_id`, unless
+    /// more recent targets are already known
+    pub fn add_target(&mut self, coord: Coord, action_id: u64) {
+        if action_id == self.latest_target {
+            self.targets.insert(coord);
+        } else if action_id > self.latest_target {
+            self.targets.clear();
+            self.targets.insert(coord);
+            self.latest_target = action_id;
+        }
+    }
 }
 
 impl LevelKnowledge for SimpleNpcKnowledgeLevel {
@@ -99,13 +115,7 @@ impl LevelKnowledge for SimpleNpcKnowledgeLevel {
         if let Some(knowledge_cell) = self.grid.get_mut(coord) {
             if knowledge_cell.update(world_cell, accuracy, action_env) {
                 if world_cell.pc() {
-                    if action_env.id == self.latest_target {
-                        self.targets.insert(coord);
-                    } else if action_env.id > self.latest_target {
-                        self.targets.clear();
-                        self.targets.insert(coord);
-                        self.latest_target = action_env.id;
-                    }
+                    self.add_target(coord, action_env.id);
                 }
                 true
             } else {
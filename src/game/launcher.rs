@@ -1,3 +1,8 @@
+use std::env;
+use std::fs;
+use std::path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use game::*;
 
 pub const GAME_WIDTH: usize = 20;
@@ -5,6 +10,12 @@ pub const GAME_HEIGHT: usize = 15;
 
 pub fn launch(args: Arguments) -> ExternalResult<()> {
 
+    if let Some(ref export_save_path) = args.export_save_path {
+        let slot = save_file::most_recent_slot(&args.user_path).unwrap_or(0);
+        return save_file::export_json(&args.user_path, slot, export_save_path)
+            .map_err(|e| format!("Failed to export save: {:?}", e));
+    }
+
     match args.frontend {
         Frontend::Sdl => {
             #[cfg(feature = "sdl2")]
@@ -13,5 +24,72 @@ pub fn launch(args: Arguments) -> ExternalResult<()> {
             #[cfg(not(feature = "sdl2"))]
             return Err("sdl frontend not supported".to_string());
         }
+        Frontend::Ansi => {
+            #[cfg(all(unix, feature = "rustty"))]
+            return frontends::ansi::launch(args);
+
+            #[cfg(not(all(unix, feature = "rustty")))]
+            return Err("ansi frontend not supported".to_string());
+        }
+        Frontend::Headless => frontends::headless::launch(args),
     }
 }
+
+/// Creates an empty user directory for a replay to run in
+fn replay_user_dir() -> ExternalResult<path::PathBuf> {
+    let (secs, nanos) = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|d| (d.as_secs(), d.subsec_nanos())).unwrap_or((0, 0));
+    let path = env::temp_dir().join(format!("apocalypse-post-replay-{}-{}", secs, nanos));
+
+    fs::create_dir_all(&path)
+        .map_err(|e| format!("Couldn't create replay directory {:?}: {}", path, e))?;
+
+    Ok(path)
+}
+
+/// Runs the game with the given renderer and input source, recording or
+/// replaying inputs as requested by the arguments. When replaying, the
+/// frontend's input source is ignored in favour of the recorded inputs.
+pub fn run_game<R, I>(renderer: R, input: I, mut args: Arguments) -> ExternalResult<()>
+    where R: KnowledgeRenderer,
+          I: 'static + InputSource + Clone,
+{
+    let prototypes = PrototypeTable::from_file(args.resource_path.join(PROTOTYPE_FILE))
+        .map_err(|e| format!("Failed to load prototypes: {:?}", e))?;
+
+    let economy = Economy::from_file(args.resource_path.join(ECONOMY_FILE), &prototypes)
+        .map_err(|e| format!("Failed to load economy: {:?}", e))?;
+
+    if let Some(replay_path) = args.replay_path.take() {
+        let recording = InputRecording::load(replay_path)?;
+
+        // replays start from the recorded saves and controls, and mustn't
+        // touch the player's own saves
+        let replay_user_path = replay_user_dir()?;
+        recording.install(&replay_user_path)?;
+        args.user_path = replay_user_path.clone();
+        args.rng_seed = recording.rng_seed;
+
+        let input = ScriptedInputSource::new(recording.inputs);
+
+        let result = {
+            let mut game = GameCtx::new(renderer, input, prototypes, economy, args.rng_seed, GAME_WIDTH, GAME_HEIGHT);
+            game.run(args)
+        };
+
+        fs::remove_dir_all(&replay_user_path)
+            .map_err(|e| format!("Couldn't remove replay directory {:?}: {}", replay_user_path, e))?;
+
+        result?;
+    } else if let Some(record_path) = args.record_path.take() {
+        let input = RecordingInputSource::new(input, args.rng_seed, &args.user_path, record_path)?;
+
+        let mut game = GameCtx::new(renderer, input, prototypes, economy, args.rng_seed, GAME_WIDTH, GAME_HEIGHT);
+        game.run(args)?;
+    } else {
+        let mut game = GameCtx::new(renderer, input, prototypes, economy, args.rng_seed, GAME_WIDTH, GAME_HEIGHT);
+        game.run(args)?;
+    }
+
+    Ok(())
+}
@@ -10,6 +10,7 @@ pub struct Level {
     pub ecs: EcsCtx,
     pub spatial_hash: SpatialHashTable,
     pub turn_schedule: TurnSchedule,
+    pub weather: Weather,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -17,26 +18,29 @@ pub struct SerializableLevel {
     ecs: SerializableEcsCtx,
     spatial_hash: SpatialHashTable,
     turn_schedule: SerializableSchedule<EntityId>,
+    weather: Weather,
 }
 
 impl From<Level> for SerializableLevel {
     fn from(level: Level) -> Self {
-        let Level { ecs, spatial_hash, turn_schedule } = level;
+        let Level { ecs, spatial_hash, turn_schedule, weather } = level;
         SerializableLevel {
             ecs: SerializableEcsCtx::from(ecs),
             spatial_hash: spatial_hash,
             turn_schedule: SerializableSchedule::from(turn_schedule),
+            weather: weather,
         }
     }
 }
 
 impl From<SerializableLevel> for Level {
     fn from(level: SerializableLevel) -> Self {
-        let SerializableLevel { ecs, spatial_hash, turn_schedule } = level;
+        let SerializableLevel { ecs, spatial_hash, turn_schedule, weather } = level;
         Level {
             ecs: EcsCtx::from(ecs),
             spatial_hash: spatial_hash,
             turn_schedule: TurnSchedule::from(turn_schedule),
+            weather: weather,
         }
     }
 }
@@ -47,6 +51,7 @@ impl Level {
                        action: &mut EcsAction,
                        ids: &EntityIdReserver,
                        rng: &GameRng,
+                       prototypes: &PrototypeTable,
                        action_id: ActionId,
                        parent: Option<ParentLevelCtx>,
                        difficulty: usize) -> (Self, LevelConnectionReport) {
@@ -54,14 +59,15 @@ impl Level {
         let mut schedule = TurnSchedule::new();
 
         // generate the level's contents
-        let TerrainMetadata { width, height, start_coord, connection_report } =
-            terrain.generate(ids, rng, &mut schedule, action, parent, difficulty);
+        let TerrainMetadata { width, height, start_coord, connection_report, weather } =
+            terrain.generate(ids, rng, prototypes, &mut schedule, action, parent, difficulty);
 
         // compose a level object
         let mut level = Level {
             ecs: EcsCtx::new(),
             spatial_hash: SpatialHashTable::new(width, height),
             turn_schedule: schedule,
+            weather: weather,
         };
 
         // add the character's starting position to the action that will insert them
@@ -112,19 +118,7 @@ impl Level {
         let mut entity_remove = EcsAction::new();
         let mut entity_insert = EcsAction::new();
 
-        if let Some(weapon_slots) = self.ecs.weapon_slots_borrow(entity_id) {
-            for (_, id) in weapon_slots.iter() {
-                entity_remove.remove_entity_by_id(*id, &self.ecs);
-            }
-        }
-
-        if let Some(inventory) = self.ecs.inventory_borrow(entity_id) {
-            for id in inventory.iter() {
-                entity_remove.remove_entity_by_id(id, &self.ecs);
-            }
-        }
-
-        entity_remove.remove_entity_by_id(entity_id, &self.ecs);
+        remove_entity_and_contents(&mut entity_remove, entity_id, &self.ecs);
         self.commit_into(&mut entity_remove, &mut entity_insert, action_id);
 
         entity_insert
@@ -148,6 +142,30 @@ impl Level {
     }
 }
 
+/// Adds the removal of an entity from `ecs` to `action`, along with the
+/// weapons, inventory and cargo it carries
+pub fn remove_entity_and_contents(action: &mut EcsAction, entity_id: EntityId, ecs: &EcsCtx) {
+    if let Some(weapon_slots) = ecs.weapon_slots_borrow(entity_id) {
+        for (_, id) in weapon_slots.iter() {
+            action.remove_entity_by_id(*id, ecs);
+        }
+    }
+
+    if let Some(inventory) = ecs.inventory_borrow(entity_id) {
+        for id in inventory.iter() {
+            action.remove_entity_by_id(id, ecs);
+        }
+    }
+
+    if let Some(cargo) = ecs.cargo_borrow(entity_id) {
+        for id in cargo.iter() {
+            action.remove_entity_by_id(id, ecs);
+        }
+    }
+
+    action.remove_entity_by_id(entity_id, ecs);
+}
+
 pub struct LevelConnectionReport {
     connections: Vec<LevelConnection>,
 }
@@ -1,4 +1,5 @@
 use std::iter::IntoIterator;
+use std::slice;
 use game::*;
 
 pub type LevelId = usize;
@@ -62,4 +63,8 @@ impl LevelTable {
     pub fn level_mut(&mut self, level_id: LevelId) -> &mut Level {
         self.levels.get_mut(level_id).expect("No such level")
     }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<Level> {
+        self.levels.iter_mut()
+    }
 }
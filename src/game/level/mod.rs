@@ -3,3 +3,6 @@ mod level_table;
 
 pub use self::level::*;
 pub use self::level_table::*;
+
+#[cfg(test)]
+mod tests;
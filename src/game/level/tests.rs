@@ -0,0 +1,43 @@
+use ecs::*;
+use game::*;
+use game::tests::prototypes;
+use coord::Coord;
+
+#[test]
+fn cargo_survives_switching_level() {
+    let ids = EntityIdReserver::new();
+    let rng = GameRng::new(0);
+    let prototypes = prototypes();
+
+    let pc_id = ids.new_id();
+    let cargo_id = ids.new_id();
+
+    let mut action = EcsAction::new();
+    prototypes::pc(action.entity_mut(pc_id), Coord::new(0, 0), &prototypes);
+    prototypes::shotgun(action.entity_mut(cargo_id), &prototypes);
+    let weight = action.weight(cargo_id).expect("Missing component weight");
+    action.cargo_mut(pc_id).expect("Missing component cargo").insert(cargo_id, weight);
+
+    let (mut level, _) = Level::new_with_entity(TerrainType::Road, pc_id, &mut action, &ids, &rng, &prototypes, 0, None, 0);
+
+    // leave the level for the staging area at the end of a delivery
+    let mut staging = EcsCtx::new();
+    let mut entity_insert = level.remove_entity(pc_id, 1);
+    staging.commit(&mut entity_insert);
+
+    assert!(staging.cargo_borrow(pc_id).expect("Missing component cargo").contains(cargo_id));
+    assert!(staging.weight(cargo_id).is_some());
+    assert!(level.ecs.weight(cargo_id).is_none());
+
+    // then leave the staging area for the level of the next delivery
+    let mut entity_remove = EcsAction::new();
+    let mut entity_insert = EcsAction::new();
+    remove_entity_and_contents(&mut entity_remove, pc_id, &staging);
+    staging.commit_into(&mut entity_remove, &mut entity_insert);
+    staging.clear();
+
+    let (level, _) = Level::new_with_entity(TerrainType::Road, pc_id, &mut entity_insert, &ids, &rng, &prototypes, 2, None, 0);
+
+    assert!(level.ecs.cargo_borrow(pc_id).expect("Missing component cargo").contains(cargo_id));
+    assert!(level.ecs.weight(cargo_id).is_some());
+}
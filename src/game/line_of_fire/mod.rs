@@ -0,0 +1,141 @@
+use game::*;
+use game::data::*;
+use ecs::*;
+use spatial_hash::*;
+use coord::{Coord, StraightLine};
+
+/// Cells a projectile passes through travelling along a vector from a
+/// position, stopping at the first visible cell that would stop it
+fn projectile_path(position: Coord, vector: Coord, range: usize, spatial_hash: &SpatialHashTable,
+                   knowledge: &DrawableKnowledgeLevel, action_env: ActionEnv) -> Vec<Coord> {
+
+    let mut path = Vec::new();
+
+    for coord in StraightLine::new(position, position + vector).infinite_iter().skip(1).take(range) {
+        path.push(coord);
+
+        if knowledge.can_see(coord, action_env) {
+            let cell = spatial_hash.get(coord);
+            if cell.solid() || cell.projectile_collider() {
+                break;
+            }
+        }
+    }
+
+    path
+}
+
+fn visibly_solid(coord: Coord, spatial_hash: &SpatialHashTable, knowledge: &DrawableKnowledgeLevel,
+                 action_env: ActionEnv) -> bool {
+    knowledge.can_see(coord, action_env) && spatial_hash.get(coord).solid()
+}
+
+/// Where a rocket travelling along a path explodes. Rockets stop short of
+/// solid cells, but fly into the cell of anything else they hit.
+fn blast_centre(position: Coord, path: &[Coord], spatial_hash: &SpatialHashTable,
+                knowledge: &DrawableKnowledgeLevel, action_env: ActionEnv) -> Coord {
+
+    match path.split_last() {
+        Some((&last, rest)) if visibly_solid(last, spatial_hash, knowledge, action_env) => {
+            rest.last().cloned().unwrap_or(position)
+        }
+        Some((&last, _)) => last,
+        None => position,
+    }
+}
+
+/// Every cell reachable by a projectile aimed along a vector which may
+/// stray up to `spread` cells to either side by the end of its range
+fn spread_cone(position: Coord, vector: Coord, range: usize, spread: usize, spatial_hash: &SpatialHashTable,
+               knowledge: &DrawableKnowledgeLevel, action_env: ActionEnv) -> Vec<Coord> {
+
+    let ideal_vector = actions::scale_to_range(vector, range);
+    let spread = spread as isize;
+
+    let mut cells = Vec::new();
+
+    for y in -spread..spread + 1 {
+        for x in -spread..spread + 1 {
+            let path = projectile_path(position, ideal_vector + Coord::new(x, y), range, spatial_hash, knowledge, action_env);
+            for coord in path {
+                if !cells.contains(&coord) {
+                    cells.push(coord);
+                }
+            }
+        }
+    }
+
+    cells
+}
+
+/// Cells within range of an explosion which aren't shielded from it by a
+/// visible solid cell
+fn explosion_radius(centre: Coord, spatial_hash: &SpatialHashTable, knowledge: &DrawableKnowledgeLevel,
+                    action_env: ActionEnv) -> Vec<Coord> {
+
+    let range = actions::EXPLOSION_RANGE as isize;
+
+    let mut cells = Vec::new();
+
+    for y in -range..range + 1 {
+        for x in -range..range + 1 {
+            let coord = centre + Coord::new(x, y);
+            if coord.real_distance(centre) > range as f64 {
+                continue;
+            }
+
+            let shielded = StraightLine::new(centre, coord).iter().skip(1).any(|cell| {
+                visibly_solid(cell, spatial_hash, knowledge, action_env)
+            });
+
+            if !shielded {
+                cells.push(coord);
+            }
+        }
+    }
+
+    cells
+}
+
+/// Overlay previewing what a gun would hit if fired from a position along
+/// a vector
+pub fn line_of_fire(gun: EntityRef, position: Coord, vector: Coord, spatial_hash: &SpatialHashTable,
+                    knowledge: &DrawableKnowledgeLevel, action_env: ActionEnv) -> RenderOverlay {
+
+    let gun_type = gun.gun_type().expect("Missing component gun_type");
+    let range = gun.gun_range().expect("Missing component gun_range");
+
+    match gun_type {
+        GunType::Pistol |
+            GunType::MachineGun |
+            GunType::Railgun |
+            GunType::Harpoon => {
+            RenderOverlay::BulletPath(projectile_path(position, vector, range, spatial_hash, knowledge, action_env))
+        }
+        GunType::Shotgun => {
+            RenderOverlay::SpreadCone(spread_cone(position, vector, range, actions::SHOTGUN_SPREAD,
+                                                  spatial_hash, knowledge, action_env))
+        }
+        GunType::Flamethrower => {
+            RenderOverlay::SpreadCone(spread_cone(position, vector, range, actions::FLAMETHROWER_SPREAD,
+                                                  spatial_hash, knowledge, action_env))
+        }
+        GunType::MineDropper => {
+            let drop_position = StraightLine::new(position, position + vector).infinite_iter().nth(1)
+                .unwrap_or(position);
+            RenderOverlay::ExplosionRadius(explosion_radius(drop_position, spatial_hash, knowledge, action_env))
+        }
+        GunType::RocketLauncher => {
+            let path = projectile_path(position, vector, range, spatial_hash, knowledge, action_env);
+            let centre = blast_centre(position, &path, spatial_hash, knowledge, action_env);
+            let blast = explosion_radius(centre, spatial_hash, knowledge, action_env);
+            RenderOverlay::Layers(vec![
+                RenderOverlay::ExplosionRadius(blast),
+                RenderOverlay::BulletPath(path),
+            ])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,200 @@
+use ecs::*;
+use game::*;
+use game::tests::prototypes;
+use spatial_hash::*;
+use util::{LeakyReserver, TwoDimensionalCons};
+use coord::Coord;
+use super::*;
+
+const WIDTH: usize = 10;
+const HEIGHT: usize = 10;
+const ACTION_ID: u64 = 1;
+
+struct Env {
+    sh: SpatialHashTable,
+    ecs: EcsCtx,
+    knowledge: DrawableKnowledgeLevel,
+}
+
+impl Env {
+    /// A level with walls at the given coordinates
+    fn new(walls: &[Coord]) -> Self {
+        let mut env = Env {
+            sh: SpatialHashTable::new(WIDTH, HEIGHT),
+            ecs: EcsCtx::new(),
+            knowledge: DrawableKnowledgeLevel::new(WIDTH, HEIGHT),
+        };
+
+        let mut ids = LeakyReserver::new();
+        let mut action = EcsAction::new();
+
+        for wall in walls {
+            let mut entity = action.entity_mut(ids.reserve());
+            entity.insert_position(*wall);
+            entity.insert_solid();
+        }
+
+        env.sh.update(&env.ecs, &action, ACTION_ID);
+        env.ecs.commit(&mut action);
+
+        env
+    }
+
+    /// Lets the player see every cell
+    fn see_everything(&mut self) {
+        let action_env = ActionEnv::new(&self.ecs, ACTION_ID);
+        for y in 0..HEIGHT as isize {
+            for x in 0..WIDTH as isize {
+                let coord = Coord::new(x, y);
+                self.knowledge.update_cell(coord, self.sh.get(coord), 1.0, action_env);
+            }
+        }
+    }
+
+    fn path(&self, position: Coord, vector: Coord, range: usize) -> Vec<Coord> {
+        projectile_path(position, vector, range, &self.sh, &self.knowledge, ActionEnv::new(&self.ecs, ACTION_ID))
+    }
+
+    fn cone(&self, position: Coord, vector: Coord, range: usize, spread: usize) -> Vec<Coord> {
+        spread_cone(position, vector, range, spread, &self.sh, &self.knowledge, ActionEnv::new(&self.ecs, ACTION_ID))
+    }
+
+    fn blast(&self, centre: Coord) -> Vec<Coord> {
+        explosion_radius(centre, &self.sh, &self.knowledge, ActionEnv::new(&self.ecs, ACTION_ID))
+    }
+
+    /// The blast and path previewed for a rocket launcher, in that order
+    fn rocket(&self, position: Coord, vector: Coord) -> (Vec<Coord>, Vec<Coord>) {
+        let mut ids = LeakyReserver::new();
+        let gun_id = ids.reserve();
+        let mut guns = EcsCtx::new();
+        let mut action = EcsAction::new();
+        prototypes().populate("rocket_launcher", &mut action.entity_mut(gun_id));
+        guns.commit(&mut action);
+
+        let overlay = line_of_fire(guns.entity(gun_id), position, vector, &self.sh, &self.knowledge,
+                                   ActionEnv::new(&self.ecs, ACTION_ID));
+
+        if let RenderOverlay::Layers(mut layers) = overlay {
+            if let (Some(RenderOverlay::BulletPath(path)), Some(RenderOverlay::ExplosionRadius(blast))) = (layers.pop(), layers.pop()) {
+                return (blast, path);
+            }
+        }
+
+        panic!("Expected explosion radius and bullet path");
+    }
+}
+
+#[test]
+fn path_reaches_range() {
+    let mut env = Env::new(&[]);
+    env.see_everything();
+
+    assert_eq!(env.path(Coord::new(2, 2), Coord::new(1, 0), 4),
+               vec![Coord::new(3, 2), Coord::new(4, 2), Coord::new(5, 2), Coord::new(6, 2)]);
+}
+
+#[test]
+fn path_continues_past_target() {
+    let mut env = Env::new(&[]);
+    env.see_everything();
+
+    assert_eq!(env.path(Coord::new(2, 2), Coord::new(2, 2), 5),
+               vec![Coord::new(3, 3), Coord::new(4, 4), Coord::new(5, 5), Coord::new(6, 6), Coord::new(7, 7)]);
+}
+
+#[test]
+fn path_stops_at_visible_wall() {
+    let mut env = Env::new(&[Coord::new(5, 2)]);
+    env.see_everything();
+
+    assert_eq!(env.path(Coord::new(2, 2), Coord::new(1, 0), 6),
+               vec![Coord::new(3, 2), Coord::new(4, 2), Coord::new(5, 2)]);
+}
+
+#[test]
+fn path_ignores_unseen_wall() {
+    let env = Env::new(&[Coord::new(5, 2)]);
+
+    assert_eq!(env.path(Coord::new(2, 2), Coord::new(1, 0), 4).len(), 4);
+}
+
+#[test]
+fn cone_without_spread_is_path() {
+    let mut env = Env::new(&[]);
+    env.see_everything();
+
+    assert_eq!(env.cone(Coord::new(2, 5), Coord::new(1, 0), 4, 0), env.path(Coord::new(2, 5), Coord::new(4, 0), 4));
+}
+
+#[test]
+fn cone_covers_spread_at_range() {
+    let mut env = Env::new(&[]);
+    env.see_everything();
+
+    let cone = env.cone(Coord::new(2, 5), Coord::new(1, 0), 4, 1);
+
+    assert!(cone.contains(&Coord::new(6, 4)));
+    assert!(cone.contains(&Coord::new(6, 5)));
+    assert!(cone.contains(&Coord::new(6, 6)));
+
+    for (i, coord) in cone.iter().enumerate() {
+        assert!(!cone[i + 1..].contains(coord));
+    }
+}
+
+#[test]
+fn cone_stops_at_walls() {
+    let mut env = Env::new(&[Coord::new(4, 4), Coord::new(4, 5), Coord::new(4, 6)]);
+    env.see_everything();
+
+    let cone = env.cone(Coord::new(2, 5), Coord::new(1, 0), 4, 1);
+
+    assert!(cone.iter().all(|coord| coord.x <= 4));
+}
+
+#[test]
+fn blast_reaches_range() {
+    let mut env = Env::new(&[]);
+    env.see_everything();
+
+    let blast = env.blast(Coord::new(4, 4));
+
+    assert!(blast.contains(&Coord::new(4, 4)));
+    assert!(blast.contains(&Coord::new(4 + actions::EXPLOSION_RANGE as isize, 4)));
+    assert!(!blast.contains(&Coord::new(5 + actions::EXPLOSION_RANGE as isize, 4)));
+}
+
+#[test]
+fn blast_stops_at_walls() {
+    let walls = (0..HEIGHT as isize).map(|y| Coord::new(6, y)).collect::<Vec<_>>();
+    let mut env = Env::new(&walls);
+    env.see_everything();
+
+    let blast = env.blast(Coord::new(4, 5));
+
+    assert!(blast.contains(&Coord::new(5, 5)));
+    assert!(blast.iter().all(|coord| coord.x < 6));
+}
+
+#[test]
+fn rocket_explodes_short_of_wall() {
+    let mut env = Env::new(&[Coord::new(6, 2)]);
+    env.see_everything();
+
+    let (blast, path) = env.rocket(Coord::new(2, 2), Coord::new(1, 0));
+
+    assert_eq!(path.last(), Some(&Coord::new(6, 2)));
+    assert_eq!(blast, env.blast(Coord::new(5, 2)));
+    assert!(!blast.contains(&Coord::new(6, 2)));
+}
+
+#[test]
+fn rocket_fired_into_adjacent_wall_explodes_at_launcher() {
+    let mut env = Env::new(&[Coord::new(3, 2)]);
+    env.see_everything();
+
+    let (blast, _) = env.rocket(Coord::new(2, 2), Coord::new(1, 0));
+
+    assert_eq!(blast, env.blast(Coord::new(2, 2)));
+}
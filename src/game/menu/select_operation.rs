@@ -1,6 +1,13 @@
 use game::*;
 use ecs::*;
 
+/// How a menu was left
+pub enum SelectMenuResolution<T> {
+    Select(T, SelectMenuState),
+    Escape,
+    Quit,
+}
+
 pub struct SelectMenuOperation<'a, 'b, 'c, 'd, R: 'a + KnowledgeRenderer, I: 'b + InputSource, T> {
     renderer: &'a mut R,
     input: &'b mut I,
@@ -30,34 +37,9 @@ impl<'a, 'b, 'c, 'd, R: 'a + KnowledgeRenderer, I: 'b + InputSource, T> SelectMe
         }
     }
 
-    pub fn run(self) -> (T, SelectMenuState) {
-        let mut state = self.initial_state.unwrap_or_default();
-
-        loop {
-            if let Some(entity) = self.hud_entity {
-                self.renderer.publish_fullscreen_menu_with_hud(self.prelude, &self.menu, &state, self.language, entity);
-            } else {
-                self.renderer.publish_fullscreen_menu(self.prelude, &self.menu, &state, self.language);
-            }
-
-            if let Some(event) = self.input.next_input() {
-                match event {
-                    InputEvent::Down => {
-                        state.select_next(&self.menu);
-                    }
-                    InputEvent::Up => {
-                        state.select_prev(&self.menu);
-                    }
-                    InputEvent::Return => {
-                        return (state.confirm(self.menu), state);
-                    }
-                    _ => {}
-                }
-            }
-        }
-    }
-
-    pub fn run_can_escape(self) -> Option<(T, SelectMenuState)> {
+    /// Runs the menu until an item is chosen, the menu is escaped (if
+    /// `can_escape`) or the input source asks to quit
+    pub fn run_resolution(self, can_escape: bool) -> SelectMenuResolution<T> {
         let mut state = self.initial_state.unwrap_or_default();
 
         loop {
@@ -77,13 +59,20 @@ impl<'a, 'b, 'c, 'd, R: 'a + KnowledgeRenderer, I: 'b + InputSource, T> SelectMe
                     }
                     InputEvent::Return => {
                         if self.menu.is_empty() {
-                            return None;
+                            if can_escape {
+                                return SelectMenuResolution::Escape;
+                            }
                         } else {
-                            return Some((state.confirm(self.menu), state));
+                            return SelectMenuResolution::Select(state.confirm(self.menu), state);
                         }
                     }
                     InputEvent::Escape => {
-                        return None;
+                        if can_escape {
+                            return SelectMenuResolution::Escape;
+                        }
+                    }
+                    InputEvent::Quit => {
+                        return SelectMenuResolution::Quit;
                     }
                     _ => {}
                 }
@@ -91,6 +80,25 @@ impl<'a, 'b, 'c, 'd, R: 'a + KnowledgeRenderer, I: 'b + InputSource, T> SelectMe
         }
     }
 
+    /// Runs a menu which can't be escaped. Returns `None` if the input
+    /// source asks to quit.
+    pub fn run(self) -> Option<(T, SelectMenuState)> {
+        match self.run_resolution(false) {
+            SelectMenuResolution::Select(item, state) => Some((item, state)),
+            SelectMenuResolution::Escape |
+                SelectMenuResolution::Quit => None,
+        }
+    }
+
+    /// Runs a menu which can be escaped. Quitting is treated as escaping.
+    pub fn run_can_escape(self) -> Option<(T, SelectMenuState)> {
+        match self.run_resolution(true) {
+            SelectMenuResolution::Select(item, state) => Some((item, state)),
+            SelectMenuResolution::Escape |
+                SelectMenuResolution::Quit => None,
+        }
+    }
+
     pub fn publish(self) {
         let state = self.initial_state.unwrap_or_default();
         if let Some(entity) = self.hud_entity {
@@ -21,7 +21,57 @@ impl English {
             RelativeDirection::Right => {
                 message.push(MessagePart::plain("Right"));
             }
+            RelativeDirection::FrontLeft => {
+                message.push(MessagePart::plain("Front Left"));
+            }
+            RelativeDirection::FrontRight => {
+                message.push(MessagePart::plain("Front Right"));
+            }
+            RelativeDirection::RearLeft => {
+                message.push(MessagePart::plain("Rear Left"));
+            }
+            RelativeDirection::RearRight => {
+                message.push(MessagePart::plain("Rear Right"));
+            }
+        }
+    }
+    fn translate_upgrade(&self, upgrade: Upgrade, message: &mut Message) {
+        let name = match upgrade.upgrade_type {
+            UpgradeType::Engine => "Engine",
+            UpgradeType::Tyres => "Reinforced Tyres",
+            UpgradeType::WeaponMounts => "Weapon Mounts",
+            UpgradeType::Inventory => "Storage",
+        };
+
+        message.push(MessagePart::Text(TextMessagePart::Plain(format!("{} {}", name, upgrade.tier))));
+    }
+    fn translate_contract(&self, contract: Contract, message: &mut Message) {
+        let cargo = match contract.cargo {
+            CargoType::Mail => "Mail",
+            CargoType::Medicine => "Medicine",
+            CargoType::Glassware => "Glassware",
+            CargoType::Explosives => "Explosives",
+        };
+
+        let destination = match contract.destination {
+            Destination::Farmstead => "the Farmstead",
+            Destination::Refinery => "the Refinery",
+            Destination::Harbour => "the Harbour",
+            Destination::Bunker => "the Bunker",
+            Destination::Monastery => "the Monastery",
+        };
+
+        let mut text = format!("{} to {} - Reward: {}", cargo, destination, contract.reward);
+
+        if let Some(time_limit) = contract.time_limit {
+            text.push_str(&format!(" - Within {} turns", time_limit));
         }
+
+        if let Some(fragility) = contract.fragility {
+            text.push_str(&format!(" - Fragile ({} hits)", fragility));
+        }
+
+        message.push(MessagePart::Text(TextMessagePart::Plain(text)));
     }
     fn translate_name(&self, name: NameMessageType, message: &mut Message) {
         match name {
@@ -61,6 +111,42 @@ impl English {
             NameMessageType::SpareTyre => {
                 message.push(MessagePart::plain("Spare Tyre"));
             }
+            NameMessageType::FuelCan => {
+                message.push(MessagePart::plain("Fuel Can"));
+            }
+            NameMessageType::Bullets => {
+                message.push(MessagePart::plain("Box of Bullets"));
+            }
+            NameMessageType::Shells => {
+                message.push(MessagePart::plain("Box of Shells"));
+            }
+            NameMessageType::RailgunSlugs => {
+                message.push(MessagePart::plain("Box of Railgun Slugs"));
+            }
+            NameMessageType::Flamethrower => {
+                message.push(MessagePart::plain("Flamethrower"));
+            }
+            NameMessageType::MineDropper => {
+                message.push(MessagePart::plain("Mine Dropper"));
+            }
+            NameMessageType::Harpoon => {
+                message.push(MessagePart::plain("Harpoon"));
+            }
+            NameMessageType::RocketLauncher => {
+                message.push(MessagePart::plain("Rocket Launcher"));
+            }
+            NameMessageType::Napalm => {
+                message.push(MessagePart::plain("Napalm Canister"));
+            }
+            NameMessageType::Mines => {
+                message.push(MessagePart::plain("Crate of Mines"));
+            }
+            NameMessageType::Harpoons => {
+                message.push(MessagePart::plain("Bundle of Harpoons"));
+            }
+            NameMessageType::Rockets => {
+                message.push(MessagePart::plain("Crate of Rockets"));
+            }
         }
     }
 
@@ -117,6 +203,27 @@ impl English {
             ActionMessageType::TyreReplaced => {
                 message.push(MessagePart::plain("Tyre replaced."));
             }
+            ActionMessageType::OutOfFuel => {
+                message.push(MessagePart::plain("You're out of fuel."));
+            }
+            ActionMessageType::Refuelled => {
+                message.push(MessagePart::plain("You refuel the van."));
+            }
+            ActionMessageType::FailToAccelerateLoaded => {
+                message.push(MessagePart::plain("The van strains under its load and fails to accelerate."));
+            }
+            ActionMessageType::GunEmpty => {
+                message.push(MessagePart::plain("Click! The gun is empty."));
+            }
+            ActionMessageType::Reloaded => {
+                message.push(MessagePart::plain("You reload the gun."));
+            }
+            ActionMessageType::NoAmmo => {
+                message.push(MessagePart::plain("You have no ammunition for that gun."));
+            }
+            ActionMessageType::AlreadyLoaded => {
+                message.push(MessagePart::plain("That gun is already loaded."));
+            }
         }
     }
 
@@ -134,6 +241,18 @@ impl English {
             DescriptionMessageType::Railgun => {
                 message.push(MessagePart::plain("Good if you want lots of things to die, provided that they're all standing in a line."));
             }
+            DescriptionMessageType::Flamethrower => {
+                message.push(MessagePart::plain("Sets the air alight for a few cells. Short range, but hard to miss with."));
+            }
+            DescriptionMessageType::MineDropper => {
+                message.push(MessagePart::plain("Leaves a mine behind the van. Best mounted at the rear, unless you enjoy driving into your own mines."));
+            }
+            DescriptionMessageType::Harpoon => {
+                message.push(MessagePart::plain("Drags whatever it hits towards the van. Pairs well with anything short ranged."));
+            }
+            DescriptionMessageType::RocketLauncher => {
+                message.push(MessagePart::plain("Explodes on impact. Don't fire it at anything too close."));
+            }
         }
     }
 
@@ -182,6 +301,39 @@ impl English {
             MenuMessageType::Name(name) => {
                 self.translate_name(name, message);
             }
+            MenuMessageType::Upgrades => {
+                message.push(MessagePart::plain("Upgrades"));
+            }
+            MenuMessageType::Upgrade { upgrade, depth, status } => {
+                for _ in 0..depth {
+                    message.push(MessagePart::plain("  "));
+                }
+                self.translate_upgrade(upgrade, message);
+                match status {
+                    UpgradeStatus::Owned => {
+                        message.push(MessagePart::plain(" (owned)"));
+                    }
+                    UpgradeStatus::Available => {
+                        message.push(MessagePart::Text(TextMessagePart::Plain(format!(": {}", upgrade.price))));
+                    }
+                    UpgradeStatus::Locked => {
+                        message.push(MessagePart::Text(TextMessagePart::Plain(format!(": {} (locked)", upgrade.price))));
+                    }
+                }
+            }
+            MenuMessageType::CargoHold => {
+                message.push(MessagePart::plain("Cargo Hold"));
+            }
+            MenuMessageType::Load(name, weight) => {
+                message.push(MessagePart::plain("Load "));
+                self.translate_name(name, message);
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!(" (weight {})", weight))));
+            }
+            MenuMessageType::Unload(name, weight) => {
+                message.push(MessagePart::plain("Unload "));
+                self.translate_name(name, message);
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!(" (weight {})", weight))));
+            }
             MenuMessageType::ShopItem(name, price) => {
                 self.translate_name(name, message);
                 message.push(MessagePart::plain(": "));
@@ -205,6 +357,57 @@ impl English {
             MenuMessageType::Empty => {
                 message.push(MessagePart::plain("(empty)"));
             }
+            MenuMessageType::SavedGames => {
+                message.push(MessagePart::plain("Saved Games"));
+            }
+            MenuMessageType::SaveSlot(slot, metadata) => {
+                let date_time = metadata.utc_date_time();
+                message.push(MessagePart::Text(TextMessagePart::Plain(
+                    format!("Slot {}: Delivery {}, {} letters, balance {} ({}-{:02}-{:02} {:02}:{:02})",
+                            slot + 1, metadata.delivery, metadata.letters, metadata.bank,
+                            date_time.year, date_time.month, date_time.day,
+                            date_time.hour, date_time.minute))));
+            }
+            MenuMessageType::EmptySaveSlot(slot) => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Slot {}: (empty)", slot + 1))));
+            }
+            MenuMessageType::UnreadableSaveSlot(slot) => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Slot {}: (unreadable)", slot + 1))));
+            }
+            MenuMessageType::Load => {
+                message.push(MessagePart::plain("Load"));
+            }
+            MenuMessageType::Save => {
+                message.push(MessagePart::plain("Save"));
+            }
+            MenuMessageType::Delete => {
+                message.push(MessagePart::plain("Delete"));
+            }
+            MenuMessageType::Route(route) => {
+                let terrain = match route.terrain_type {
+                    TerrainType::DemoA => "Proving Ground",
+                    TerrainType::Road => "Highway",
+                    TerrainType::City => "City",
+                };
+
+                let risk = match route.risk {
+                    0 => "Quiet",
+                    1 => "Dangerous",
+                    _ => "Deadly",
+                };
+
+                message.push(MessagePart::Text(TextMessagePart::Plain(
+                    format!("{} - {} - Bonus: {}", terrain, risk, route.bonus))));
+            }
+            MenuMessageType::Contracts => {
+                message.push(MessagePart::plain("Contracts"));
+            }
+            MenuMessageType::Contract(contract) => {
+                self.translate_contract(contract, message);
+            }
+            MenuMessageType::NoContract => {
+                message.push(MessagePart::plain("No Contract"));
+            }
         }
     }
 }
@@ -219,6 +422,9 @@ impl Language for English {
             MessageType::YouDied => {
                 message.push(MessagePart::colour(colours::RED, "YOU DIED"));
             }
+            MessageType::YouRanOutOfFuel => {
+                message.push(MessagePart::colour(colours::RED, "STRANDED WITHOUT FUEL"));
+            }
             MessageType::Action(action) => {
                 self.translate_action(action, message);
             }
@@ -320,6 +526,131 @@ impl Language for English {
             MessageType::MustBeStopped => {
                 message.push(MessagePart::plain("Can't do this while moving."));
             }
+            MessageType::SavedGames => {
+                message.push(MessagePart::plain("Saved Games"));
+            }
+            MessageType::SavedGamesLoadFailed(slot) => {
+                message.push(MessagePart::plain("Saved Games"));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Slot {} could not be loaded!", slot + 1))));
+            }
+            MessageType::SaveSlotTitle(slot) => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Slot {}", slot + 1))));
+            }
+            MessageType::OverwriteSaveSlot => {
+                message.push(MessagePart::plain("New Game"));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::plain("Every slot is in use. Choose a saved game to overwrite."));
+            }
+            MessageType::SaveFailed => {
+                message.push(MessagePart::colour(colours::RED, "The game could not be saved!"));
+            }
+            MessageType::WorldMap => {
+                message.push(MessagePart::plain("Choose a Route"));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::plain("The survivors mark the roads to the next camp on your map. Riskier roads pay more, if you live to collect."));
+            }
+            MessageType::Contracts(maybe_contract) => {
+                message.push(MessagePart::plain("Contracts"));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::Newline);
+                if let Some(contract) = maybe_contract {
+                    message.push(MessagePart::plain("Accepted: "));
+                    self.translate_contract(contract, message);
+                } else {
+                    message.push(MessagePart::plain("You have not accepted a contract."));
+                }
+            }
+            MessageType::SurvivorCampContract(outcome) => {
+                self.translate_repeated(MessageType::SurvivorCamp, 1, message);
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::Newline);
+                match outcome {
+                    ContractOutcome::Completed(contract) => {
+                        message.push(MessagePart::Text(TextMessagePart::Plain(
+                            format!("Contract complete. You are paid a reward of {}.", contract.reward))));
+                    }
+                    ContractOutcome::Late(_) => {
+                        message.push(MessagePart::colour(colours::RED, "The cargo arrived too late. The contract pays nothing."));
+                    }
+                    ContractOutcome::CargoRuined(_) => {
+                        message.push(MessagePart::colour(colours::RED, "The cargo was ruined on the road. The contract pays nothing."));
+                    }
+                }
+            }
+            MessageType::Upgrades(balance) => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Upgrades - Your balance: {}", balance))));
+            }
+            MessageType::UpgradesInsufficientFunds(balance) => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Upgrades - Your balance: {}", balance))));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::plain("You can't afford that!"));
+            }
+            MessageType::UpgradesLocked(balance) => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Upgrades - Your balance: {}", balance))));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::plain("That requires another upgrade first!"));
+            }
+            MessageType::UpgradesOwned(balance) => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Upgrades - Your balance: {}", balance))));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::plain("You already have that!"));
+            }
+            MessageType::ChooseSecondDirection => {
+                message.push(MessagePart::plain("Same direction again, or to the side for a diagonal mount?"));
+            }
+            MessageType::RelativeDirection(direction) => {
+                self.translate_relative_direction(direction, message);
+            }
+            MessageType::ChooseTarget(name) => {
+                message.push(MessagePart::plain("Targeting "));
+                if let Some(name) = name {
+                    self.translate_name(name, message);
+                } else {
+                    message.push(MessagePart::plain("something"));
+                }
+                message.push(MessagePart::plain(". Direction keys to cycle, fire to shoot."));
+            }
+            MessageType::NoTargets => {
+                message.push(MessagePart::plain("Nothing to target!"));
+            }
+            MessageType::NoWeaponFacingTarget => {
+                message.push(MessagePart::plain("No gun can fire that way!"));
+            }
+            MessageType::Examine { name, health, description } => {
+                self.translate_name(name, message);
+                if let Some((current, max)) = health {
+                    message.push(MessagePart::Text(TextMessagePart::Plain(format!(" ({}/{})", current, max))));
+                }
+                if let Some(description) = description {
+                    message.push(MessagePart::plain(" - "));
+                    self.translate_description(description, message);
+                }
+            }
+            MessageType::CargoHold { weight, capacity } => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Cargo Hold: {}/{}", weight, capacity))));
+            }
+            MessageType::CargoHoldFull { weight, capacity } => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Cargo Hold: {}/{}", weight, capacity))));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::plain("That's too heavy to load!"));
+            }
+            MessageType::CargoHoldInventoryFull { weight, capacity } => {
+                message.push(MessagePart::Text(TextMessagePart::Plain(format!("Cargo Hold: {}/{}", weight, capacity))));
+                message.push(MessagePart::Newline);
+                message.push(MessagePart::plain("No space in inventory!"));
+            }
+            MessageType::Weather(weather) => {
+                let string = match weather {
+                    Weather::Clear => "Clear",
+                    Weather::Fog => "Fog",
+                    Weather::Night => "Night",
+                    Weather::DustStorm => "Dust Storm",
+                    Weather::AcidRain => "Acid Rain",
+                };
+                message.push(MessagePart::plain(string));
+            }
         }
 
         if repeated > 1 {
@@ -44,6 +44,13 @@ impl TextMessagePart {
         TextMessagePart::Colour(colour, string.to_string())
     }
 
+    pub fn as_str(&self) -> &str {
+        match *self {
+            TextMessagePart::Plain(ref s) => s.as_str(),
+            TextMessagePart::Colour(_, ref s) => s.as_str(),
+        }
+    }
+
     pub fn len(&self) -> usize {
         match *self {
             TextMessagePart::Plain(ref s) => s.len(),
@@ -7,6 +7,7 @@ pub enum MessageType {
     Title,
     PressAnyKey,
     YouDied,
+    YouRanOutOfFuel,
     Action(ActionMessageType),
     Name(NameMessageType),
     YouRemember(Option<NameMessageType>),
@@ -36,6 +37,41 @@ pub enum MessageType {
     GarageInventoryFull,
     WeaponSlotTitle(RelativeDirection, Option<NameMessageType>),
     MustBeStopped,
+    SavedGames,
+    SavedGamesLoadFailed(usize),
+    SaveSlotTitle(usize),
+    OverwriteSaveSlot,
+    SaveFailed,
+    WorldMap,
+    Contracts(Option<Contract>),
+    SurvivorCampContract(ContractOutcome),
+    Weather(Weather),
+    CargoHold {
+        weight: usize,
+        capacity: usize,
+    },
+    CargoHoldFull {
+        weight: usize,
+        capacity: usize,
+    },
+    CargoHoldInventoryFull {
+        weight: usize,
+        capacity: usize,
+    },
+    Upgrades(usize),
+    UpgradesInsufficientFunds(usize),
+    UpgradesLocked(usize),
+    UpgradesOwned(usize),
+    ChooseSecondDirection,
+    RelativeDirection(RelativeDirection),
+    ChooseTarget(Option<NameMessageType>),
+    NoTargets,
+    NoWeaponFacingTarget,
+    Examine {
+        name: NameMessageType,
+        health: Option<(isize, isize)>,
+        description: Option<DescriptionMessageType>,
+    },
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -44,6 +80,18 @@ pub enum VerbMessageType {
     Claw,
 }
 
+impl VerbMessageType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Ram" => VerbMessageType::Ram,
+            "Claw" => VerbMessageType::Claw,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum NameMessageType {
     Pistol,
@@ -58,6 +106,51 @@ pub enum NameMessageType {
     ArmourUpgrade(usize),
     EngineRepairKit,
     SpareTyre,
+    FuelCan,
+    Bullets,
+    Shells,
+    RailgunSlugs,
+    Flamethrower,
+    MineDropper,
+    Harpoon,
+    RocketLauncher,
+    Napalm,
+    Mines,
+    Harpoons,
+    Rockets,
+}
+
+impl NameMessageType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Pistol" => NameMessageType::Pistol,
+            "Shotgun" => NameMessageType::Shotgun,
+            "MachineGun" => NameMessageType::MachineGun,
+            "Railgun" => NameMessageType::Railgun,
+            "Car" => NameMessageType::Car,
+            "Bike" => NameMessageType::Bike,
+            "Zombie" => NameMessageType::Zombie,
+            "EngineRepair" => NameMessageType::EngineRepair,
+            "TyresRepair" => NameMessageType::TyresRepair,
+            "EngineRepairKit" => NameMessageType::EngineRepairKit,
+            "SpareTyre" => NameMessageType::SpareTyre,
+            "FuelCan" => NameMessageType::FuelCan,
+            "Bullets" => NameMessageType::Bullets,
+            "Shells" => NameMessageType::Shells,
+            "RailgunSlugs" => NameMessageType::RailgunSlugs,
+            "Flamethrower" => NameMessageType::Flamethrower,
+            "MineDropper" => NameMessageType::MineDropper,
+            "Harpoon" => NameMessageType::Harpoon,
+            "RocketLauncher" => NameMessageType::RocketLauncher,
+            "Napalm" => NameMessageType::Napalm,
+            "Mines" => NameMessageType::Mines,
+            "Harpoons" => NameMessageType::Harpoons,
+            "Rockets" => NameMessageType::Rockets,
+            _ => return None,
+        };
+
+        Some(value)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -76,6 +169,13 @@ pub enum ActionMessageType {
     MaxSpeedDecreased,
     EngineRepaired,
     TyreReplaced,
+    OutOfFuel,
+    Refuelled,
+    FailToAccelerateLoaded,
+    GunEmpty,
+    Reloaded,
+    NoAmmo,
+    AlreadyLoaded,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -84,6 +184,28 @@ pub enum DescriptionMessageType {
     Shotgun,
     MachineGun,
     Railgun,
+    Flamethrower,
+    MineDropper,
+    Harpoon,
+    RocketLauncher,
+}
+
+impl DescriptionMessageType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let value = match s {
+            "Pistol" => DescriptionMessageType::Pistol,
+            "Shotgun" => DescriptionMessageType::Shotgun,
+            "MachineGun" => DescriptionMessageType::MachineGun,
+            "Railgun" => DescriptionMessageType::Railgun,
+            "Flamethrower" => DescriptionMessageType::Flamethrower,
+            "MineDropper" => DescriptionMessageType::MineDropper,
+            "Harpoon" => DescriptionMessageType::Harpoon,
+            "RocketLauncher" => DescriptionMessageType::RocketLauncher,
+            _ => return None,
+        };
+
+        Some(value)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -106,4 +228,24 @@ pub enum MenuMessageType {
     Remove,
     WeaponSlot(RelativeDirection, Option<NameMessageType>),
     Empty,
+    SavedGames,
+    SaveSlot(usize, SaveMetadata),
+    EmptySaveSlot(usize),
+    UnreadableSaveSlot(usize),
+    Load,
+    Save,
+    Delete,
+    Route(Route),
+    Contracts,
+    Contract(Contract),
+    NoContract,
+    CargoHold,
+    Load(NameMessageType, usize),
+    Unload(NameMessageType, usize),
+    Upgrades,
+    Upgrade {
+        upgrade: Upgrade,
+        depth: usize,
+        status: UpgradeStatus,
+    },
 }
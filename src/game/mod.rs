@@ -26,6 +26,11 @@ mod config;
 mod renderer_buffers;
 mod control_spec;
 mod entity;
+mod input_script;
+mod recording;
+mod prototype_table;
+mod economy;
+mod line_of_fire;
 
 pub use self::knowledge::*;
 pub use self::behaviour::*;
@@ -54,6 +59,11 @@ pub use self::config::*;
 pub use self::renderer_buffers::*;
 pub use self::control_spec::*;
 pub use self::entity::*;
+pub use self::input_script::*;
+pub use self::recording::*;
+pub use self::prototype_table::*;
+pub use self::economy::*;
+pub use self::line_of_fire::*;
 
 pub mod data;
 pub mod prototypes;
@@ -62,3 +72,6 @@ pub mod save_file;
 pub mod game_file;
 pub mod user_files;
 pub mod control_file;
+
+#[cfg(test)]
+mod tests;
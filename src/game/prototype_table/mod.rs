@@ -0,0 +1,453 @@
+use std::collections::HashMap;
+use std::path;
+use std::result;
+use toml;
+
+use direction::Direction;
+use ecs::*;
+use game::*;
+use game::data::*;
+
+pub const PROTOTYPE_FILE: &'static str = "prototypes.toml";
+
+/// Prototypes which the game instantiates by name, and so must be present
+/// in every prototype file
+const REQUIRED_PROTOTYPES: [&'static str; 17] = [
+    "pc",
+    "zombie",
+    "car",
+    "bike",
+    "wreck",
+    "barrel",
+    "letter",
+    "building",
+    "pistol",
+    "shotgun",
+    "machine_gun",
+    "railgun",
+    "engine_repair",
+    "tyres_repair",
+    "engine_repair_kit",
+    "spare_tyre",
+    "fuel_can",
+];
+
+/// Components in ecs.toml which prototypes can't set. Their values either
+/// only make sense once an entity is placed in a level, or are chosen by
+/// the code which spawns the entity.
+const UNSUPPORTED_COMPONENTS: [&'static str; 22] = [
+    "acid_animation",
+    "bullet",
+    "explosion",
+    "harpoon",
+    "armour_upgrade",
+    "shooter_id",
+    "extra_weapon_mounts",
+    "steering",
+    "squad_id",
+    "squad_role",
+    "realtime_velocity",
+    "realtime_moves_remaining",
+    "position",
+    "control_map",
+    "shadow_entity",
+    "schedule_ticket",
+    "level_switch",
+    "level_switch_auto",
+    "level_switch_group",
+    "probabilistic_animation",
+    "cargo_damage",
+    "burning",
+];
+
+#[derive(Debug)]
+pub enum PrototypeError {
+    FailedToRead(game_file::FileError),
+    InvalidSpec,
+    MissingPrototype(String),
+    UnknownComponent {
+        prototype: String,
+        component: String,
+    },
+    UnsupportedComponent {
+        prototype: String,
+        component: String,
+    },
+    InvalidValue {
+        prototype: String,
+        component: String,
+    },
+}
+
+pub type PrototypeResult<T> = result::Result<T, PrototypeError>;
+
+enum ComponentError {
+    UnknownComponent,
+    UnsupportedComponent,
+    InvalidValue,
+}
+
+type ComponentResult<T> = result::Result<T, ComponentError>;
+
+/// The value of a single component, as named in ecs.toml. Components whose
+/// contents always start empty (e.g. message_log) carry no value here.
+/// Every component in ecs.toml must either have a variant here or be listed
+/// in `UNSUPPORTED_COMPONENTS`, which the tests check.
+#[derive(Clone, Copy, Debug)]
+enum ComponentValue {
+    Solid,
+    Collider,
+    DestroyOnCollision,
+    Pc,
+    Floor,
+    ShouldRender,
+    Enemy,
+    Projectile,
+    ProjectileCollider,
+    BumpAttackable,
+    Acid,
+    CanRunOver,
+    CanBeRunOver,
+    BloodstainOnDeath,
+    DestroyWhenStopped,
+    DestroyWhenOutOfBounds,
+    HealthBar,
+    ComplexDamage,
+    Letter,
+    ExplodeOnCollision,
+    DestroyedByExplosion,
+    TriggersExplosion,
+    BehaviourState,
+    DrawableKnowledge,
+    SimpleNpcKnowledge,
+    PathTraverse,
+    MessageLog,
+    WeaponSlots,
+    Inventory,
+    Cargo,
+    ConsumableType(ConsumableType),
+    RepairType(RepairType),
+    LetterCount(usize),
+    BumpVerb(VerbMessageType),
+    Bank(usize),
+    Price(usize),
+    GunType(GunType),
+    GunRange(usize),
+    Ammo(usize),
+    AmmoCapacity(usize),
+    BulletType(BulletType),
+    CurrentSpeed(usize),
+    MaxSpeed(usize),
+    RedlineSpeed(usize),
+    EngineHealth(usize),
+    TyreHealth(usize),
+    Fuel(usize),
+    Armour(usize),
+    Facing(Direction),
+    BumpAttacker(usize),
+    ProjectileDamage(usize),
+    HitPoints(usize),
+    Tile(TileType),
+    Opacity(f64),
+    VisionDistance(usize),
+    TileDepth(isize),
+    Name(NameMessageType),
+    Description(DescriptionMessageType),
+    BehaviourType(BehaviourType),
+    TurnOffset(u64),
+    TurnTime(u64),
+    InventoryCapacity(usize),
+    CargoCapacity(usize),
+    Weight(usize),
+    Light(Light),
+}
+
+fn direction_from_str(s: &str) -> Option<Direction> {
+    let direction = match s {
+        "North" => Direction::North,
+        "NorthEast" => Direction::NorthEast,
+        "East" => Direction::East,
+        "SouthEast" => Direction::SouthEast,
+        "South" => Direction::South,
+        "SouthWest" => Direction::SouthWest,
+        "West" => Direction::West,
+        "NorthWest" => Direction::NorthWest,
+        _ => return None,
+    };
+
+    Some(direction)
+}
+
+fn flag(value: &toml::Value, component: ComponentValue) -> ComponentResult<Option<ComponentValue>> {
+    // a flag component may be set to false to make its absence explicit
+    let present = value.as_bool().ok_or(ComponentError::InvalidValue)?;
+    Ok(if present { Some(component) } else { None })
+}
+
+fn integer(value: &toml::Value) -> ComponentResult<i64> {
+    value.as_integer().ok_or(ComponentError::InvalidValue)
+}
+
+fn unsigned(value: &toml::Value) -> ComponentResult<u64> {
+    let integer = integer(value)?;
+    if integer < 0 {
+        return Err(ComponentError::InvalidValue);
+    }
+
+    Ok(integer as u64)
+}
+
+fn size(value: &toml::Value) -> ComponentResult<usize> {
+    unsigned(value).map(|u| u as usize)
+}
+
+fn float(value: &toml::Value) -> ComponentResult<f64> {
+    value.as_float()
+        .or_else(|| value.as_integer().map(|i| i as f64))
+        .ok_or(ComponentError::InvalidValue)
+}
+
+fn named<T, F: Fn(&str) -> Option<T>>(value: &toml::Value, from_str: F) -> ComponentResult<T> {
+    value.as_str().and_then(from_str).ok_or(ComponentError::InvalidValue)
+}
+
+fn light(value: &toml::Value) -> ComponentResult<Light> {
+    let table = value.as_table().ok_or(ComponentError::InvalidValue)?;
+    let field = |name: &str| table.get(name).ok_or(ComponentError::InvalidValue);
+
+    Ok(Light::new(size(field("radius")?)?,
+                  named(field("direction")?, LightDirection::from_str)?))
+}
+
+impl ComponentValue {
+    fn from_toml(component: &str, value: &toml::Value) -> ComponentResult<Option<Self>> {
+        let component = match component {
+            "solid" => return flag(value, ComponentValue::Solid),
+            "collider" => return flag(value, ComponentValue::Collider),
+            "destroy_on_collision" => return flag(value, ComponentValue::DestroyOnCollision),
+            "pc" => return flag(value, ComponentValue::Pc),
+            "floor" => return flag(value, ComponentValue::Floor),
+            "should_render" => return flag(value, ComponentValue::ShouldRender),
+            "enemy" => return flag(value, ComponentValue::Enemy),
+            "projectile" => return flag(value, ComponentValue::Projectile),
+            "projectile_collider" => return flag(value, ComponentValue::ProjectileCollider),
+            "bump_attackable" => return flag(value, ComponentValue::BumpAttackable),
+            "acid" => return flag(value, ComponentValue::Acid),
+            "can_run_over" => return flag(value, ComponentValue::CanRunOver),
+            "can_be_run_over" => return flag(value, ComponentValue::CanBeRunOver),
+            "bloodstain_on_death" => return flag(value, ComponentValue::BloodstainOnDeath),
+            "destroy_when_stopped" => return flag(value, ComponentValue::DestroyWhenStopped),
+            "destroy_when_out_of_bounds" => return flag(value, ComponentValue::DestroyWhenOutOfBounds),
+            "health_bar" => return flag(value, ComponentValue::HealthBar),
+            "complex_damage" => return flag(value, ComponentValue::ComplexDamage),
+            "letter" => return flag(value, ComponentValue::Letter),
+            "explode_on_collision" => return flag(value, ComponentValue::ExplodeOnCollision),
+            "destroyed_by_explosion" => return flag(value, ComponentValue::DestroyedByExplosion),
+            "triggers_explosion" => return flag(value, ComponentValue::TriggersExplosion),
+            "behaviour_state" => return flag(value, ComponentValue::BehaviourState),
+            "drawable_knowledge" => return flag(value, ComponentValue::DrawableKnowledge),
+            "simple_npc_knowledge" => return flag(value, ComponentValue::SimpleNpcKnowledge),
+            "path_traverse" => return flag(value, ComponentValue::PathTraverse),
+            "message_log" => return flag(value, ComponentValue::MessageLog),
+            "weapon_slots" => return flag(value, ComponentValue::WeaponSlots),
+            "inventory" => return flag(value, ComponentValue::Inventory),
+            "cargo" => return flag(value, ComponentValue::Cargo),
+            "consumable_type" => ComponentValue::ConsumableType(named(value, ConsumableType::from_str)?),
+            "repair_type" => ComponentValue::RepairType(named(value, RepairType::from_str)?),
+            "letter_count" => ComponentValue::LetterCount(size(value)?),
+            "bump_verb" => ComponentValue::BumpVerb(named(value, VerbMessageType::from_str)?),
+            "bank" => ComponentValue::Bank(size(value)?),
+            "price" => ComponentValue::Price(size(value)?),
+            "gun_type" => ComponentValue::GunType(named(value, GunType::from_str)?),
+            "gun_range" => ComponentValue::GunRange(size(value)?),
+            "ammo" => ComponentValue::Ammo(size(value)?),
+            "ammo_capacity" => ComponentValue::AmmoCapacity(size(value)?),
+            "bullet_type" => ComponentValue::BulletType(named(value, BulletType::from_str)?),
+            "current_speed" => ComponentValue::CurrentSpeed(size(value)?),
+            "max_speed" => ComponentValue::MaxSpeed(size(value)?),
+            "redline_speed" => ComponentValue::RedlineSpeed(size(value)?),
+            "engine_health" => ComponentValue::EngineHealth(size(value)?),
+            "tyre_health" => ComponentValue::TyreHealth(size(value)?),
+            "fuel" => ComponentValue::Fuel(size(value)?),
+            "armour" => ComponentValue::Armour(size(value)?),
+            "facing" => ComponentValue::Facing(named(value, direction_from_str)?),
+            "bump_attacker" => ComponentValue::BumpAttacker(size(value)?),
+            "projectile_damage" => ComponentValue::ProjectileDamage(size(value)?),
+            "hit_points" => ComponentValue::HitPoints(size(value)?),
+            "tile" => ComponentValue::Tile(named(value, TileType::from_str)?),
+            "opacity" => ComponentValue::Opacity(float(value)?),
+            "vision_distance" => ComponentValue::VisionDistance(size(value)?),
+            "tile_depth" => ComponentValue::TileDepth(integer(value)? as isize),
+            "name" => ComponentValue::Name(named(value, NameMessageType::from_str)?),
+            "description" => ComponentValue::Description(named(value, DescriptionMessageType::from_str)?),
+            "behaviour_type" => ComponentValue::BehaviourType(named(value, BehaviourType::from_str)?),
+            "turn_offset" => ComponentValue::TurnOffset(unsigned(value)?),
+            "turn_time" => ComponentValue::TurnTime(unsigned(value)?),
+            "inventory_capacity" => ComponentValue::InventoryCapacity(size(value)?),
+            "cargo_capacity" => ComponentValue::CargoCapacity(size(value)?),
+            "weight" => ComponentValue::Weight(size(value)?),
+            "light" => ComponentValue::Light(light(value)?),
+            other if UNSUPPORTED_COMPONENTS.contains(&other) => return Err(ComponentError::UnsupportedComponent),
+            _ => return Err(ComponentError::UnknownComponent),
+        };
+
+        Ok(Some(component))
+    }
+
+    fn insert<E: EntityPopulate>(self, entity: &mut E) {
+        match self {
+            ComponentValue::Solid => { entity.insert_solid(); }
+            ComponentValue::Collider => { entity.insert_collider(); }
+            ComponentValue::DestroyOnCollision => { entity.insert_destroy_on_collision(); }
+            ComponentValue::Pc => { entity.insert_pc(); }
+            ComponentValue::Floor => { entity.insert_floor(); }
+            ComponentValue::ShouldRender => { entity.insert_should_render(); }
+            ComponentValue::Enemy => { entity.insert_enemy(); }
+            ComponentValue::Projectile => { entity.insert_projectile(); }
+            ComponentValue::ProjectileCollider => { entity.insert_projectile_collider(); }
+            ComponentValue::BumpAttackable => { entity.insert_bump_attackable(); }
+            ComponentValue::Acid => { entity.insert_acid(); }
+            ComponentValue::CanRunOver => { entity.insert_can_run_over(); }
+            ComponentValue::CanBeRunOver => { entity.insert_can_be_run_over(); }
+            ComponentValue::BloodstainOnDeath => { entity.insert_bloodstain_on_death(); }
+            ComponentValue::DestroyWhenStopped => { entity.insert_destroy_when_stopped(); }
+            ComponentValue::DestroyWhenOutOfBounds => { entity.insert_destroy_when_out_of_bounds(); }
+            ComponentValue::HealthBar => { entity.insert_health_bar(); }
+            ComponentValue::ComplexDamage => { entity.insert_complex_damage(); }
+            ComponentValue::Letter => { entity.insert_letter(); }
+            ComponentValue::ExplodeOnCollision => { entity.insert_explode_on_collision(); }
+            ComponentValue::DestroyedByExplosion => { entity.insert_destroyed_by_explosion(); }
+            ComponentValue::TriggersExplosion => { entity.insert_triggers_explosion(); }
+            ComponentValue::BehaviourState => { entity.insert_behaviour_state(BehaviourState::new()); }
+            ComponentValue::DrawableKnowledge => { entity.insert_drawable_knowledge(DrawableKnowledge::new()); }
+            ComponentValue::SimpleNpcKnowledge => { entity.insert_simple_npc_knowledge(SimpleNpcKnowledge::new()); }
+            ComponentValue::PathTraverse => { entity.insert_path_traverse(PathTraverse::new()); }
+            ComponentValue::MessageLog => { entity.insert_message_log(MessageLog::new()); }
+            ComponentValue::WeaponSlots => { entity.insert_weapon_slots(DirectionTable::new()); }
+            ComponentValue::Inventory => { entity.insert_inventory(EntitySet::new()); }
+            ComponentValue::Cargo => { entity.insert_cargo(CargoHold::new()); }
+            ComponentValue::ConsumableType(value) => { entity.insert_consumable_type(value); }
+            ComponentValue::RepairType(value) => { entity.insert_repair_type(value); }
+            ComponentValue::LetterCount(value) => { entity.insert_letter_count(value); }
+            ComponentValue::BumpVerb(value) => { entity.insert_bump_verb(value); }
+            ComponentValue::Bank(value) => { entity.insert_bank(value); }
+            ComponentValue::Price(value) => { entity.insert_price(value); }
+            ComponentValue::GunType(value) => { entity.insert_gun_type(value); }
+            ComponentValue::GunRange(value) => { entity.insert_gun_range(value); }
+            ComponentValue::Ammo(value) => { entity.insert_ammo(value); }
+            ComponentValue::AmmoCapacity(value) => { entity.insert_ammo_capacity(value); }
+            ComponentValue::BulletType(value) => { entity.insert_bullet_type(value); }
+            ComponentValue::CurrentSpeed(value) => { entity.insert_current_speed(value); }
+            ComponentValue::MaxSpeed(value) => { entity.insert_max_speed(value); }
+            ComponentValue::RedlineSpeed(value) => { entity.insert_redline_speed(value); }
+            ComponentValue::EngineHealth(value) => { entity.insert_engine_health(HitPoints::new(value)); }
+            ComponentValue::TyreHealth(value) => { entity.insert_tyre_health(HitPoints::new(value)); }
+            ComponentValue::Fuel(value) => { entity.insert_fuel(HitPoints::new(value)); }
+            ComponentValue::Armour(value) => { entity.insert_armour(value); }
+            ComponentValue::Facing(value) => { entity.insert_facing(value); }
+            ComponentValue::BumpAttacker(value) => { entity.insert_bump_attacker(value); }
+            ComponentValue::ProjectileDamage(value) => { entity.insert_projectile_damage(value); }
+            ComponentValue::HitPoints(value) => { entity.insert_hit_points(HitPoints::new(value)); }
+            ComponentValue::Tile(value) => { entity.insert_tile(value); }
+            ComponentValue::Opacity(value) => { entity.insert_opacity(value); }
+            ComponentValue::VisionDistance(value) => { entity.insert_vision_distance(value); }
+            ComponentValue::TileDepth(value) => { entity.insert_tile_depth(value); }
+            ComponentValue::Name(value) => { entity.insert_name(value); }
+            ComponentValue::Description(value) => { entity.insert_description(value); }
+            ComponentValue::BehaviourType(value) => { entity.insert_behaviour_type(value); }
+            ComponentValue::TurnOffset(value) => { entity.insert_turn_offset(value); }
+            ComponentValue::TurnTime(value) => { entity.insert_turn_time(value); }
+            ComponentValue::InventoryCapacity(value) => { entity.insert_inventory_capacity(value); }
+            ComponentValue::CargoCapacity(value) => { entity.insert_cargo_capacity(value); }
+            ComponentValue::Weight(value) => { entity.insert_weight(value); }
+            ComponentValue::Light(value) => { entity.insert_light(value); }
+        }
+    }
+}
+
+/// The components given to an entity when it is created
+#[derive(Debug)]
+pub struct Prototype {
+    components: Vec<ComponentValue>,
+}
+
+impl Prototype {
+    fn from_toml(name: &str, table: &toml::value::Table) -> PrototypeResult<Self> {
+        let mut components = Vec::new();
+
+        for (component, value) in table.iter() {
+            let maybe_component = ComponentValue::from_toml(component, value).map_err(|e| match e {
+                ComponentError::UnknownComponent => PrototypeError::UnknownComponent {
+                    prototype: name.to_string(),
+                    component: component.clone(),
+                },
+                ComponentError::UnsupportedComponent => PrototypeError::UnsupportedComponent {
+                    prototype: name.to_string(),
+                    component: component.clone(),
+                },
+                ComponentError::InvalidValue => PrototypeError::InvalidValue {
+                    prototype: name.to_string(),
+                    component: component.clone(),
+                },
+            })?;
+
+            if let Some(component) = maybe_component {
+                components.push(component);
+            }
+        }
+
+        Ok(Prototype {
+            components: components,
+        })
+    }
+
+    pub fn populate<E: EntityPopulate>(&self, entity: &mut E) {
+        for component in self.components.iter() {
+            component.insert(entity);
+        }
+    }
+}
+
+/// Entity prototypes loaded from a resource file, keyed by name
+#[derive(Debug)]
+pub struct PrototypeTable {
+    prototypes: HashMap<String, Prototype>,
+}
+
+impl PrototypeTable {
+    pub fn new(table: toml::value::Table) -> PrototypeResult<Self> {
+        let mut prototypes = HashMap::new();
+
+        for (name, prototype_toml) in table.iter() {
+            let prototype_table = prototype_toml.as_table().ok_or(PrototypeError::InvalidSpec)?;
+            prototypes.insert(name.clone(), Prototype::from_toml(name, prototype_table)?);
+        }
+
+        for name in REQUIRED_PROTOTYPES.iter() {
+            if !prototypes.contains_key(*name) {
+                return Err(PrototypeError::MissingPrototype(name.to_string()));
+            }
+        }
+
+        Ok(PrototypeTable {
+            prototypes: prototypes,
+        })
+    }
+
+    pub fn from_file<P: AsRef<path::Path>>(path: P) -> PrototypeResult<Self> {
+        let table = game_file::read_toml(path).map_err(PrototypeError::FailedToRead)?;
+        Self::new(table)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Prototype> {
+        self.prototypes.get(name)
+    }
+
+    /// Adds the components of the named prototype to an entity
+    pub fn populate<E: EntityPopulate>(&self, name: &str, entity: &mut E) {
+        self.get(name).expect(format!("Missing prototype {}", name).as_ref()).populate(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests;
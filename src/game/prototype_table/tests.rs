@@ -0,0 +1,63 @@
+use toml;
+
+use game::tests::resource_path;
+use super::*;
+
+/// Names of the components declared in ecs.toml
+fn ecs_components() -> Vec<String> {
+    let ecs: toml::Value = toml::from_str(include_str!("../../../ecs.toml")).expect("Failed to parse ecs.toml");
+    let components = ecs.get("component").and_then(|c| c.as_table()).expect("Missing component table");
+
+    components.keys().cloned().collect()
+}
+
+/// True if `from_toml` recognises the component, whether or not the value
+/// given is valid for it
+fn is_handled(component: &str) -> bool {
+    match ComponentValue::from_toml(component, &toml::Value::Boolean(true)) {
+        Err(ComponentError::UnknownComponent) | Err(ComponentError::UnsupportedComponent) => false,
+        _ => true,
+    }
+}
+
+#[test]
+fn every_component_is_handled_or_unsupported() {
+    for component in ecs_components() {
+        assert!(is_handled(&component) || UNSUPPORTED_COMPONENTS.contains(&component.as_str()),
+                "Component {} is neither handled by prototypes nor listed as unsupported", component);
+    }
+}
+
+#[test]
+fn unsupported_components_are_not_handled() {
+    for component in UNSUPPORTED_COMPONENTS.iter() {
+        assert!(!is_handled(component), "Component {} is handled but listed as unsupported", component);
+    }
+}
+
+#[test]
+fn unsupported_components_exist() {
+    let components = ecs_components();
+
+    for component in UNSUPPORTED_COMPONENTS.iter() {
+        assert!(components.iter().any(|c| c == component), "Unsupported component {} isn't in ecs.toml", component);
+    }
+}
+
+#[test]
+fn unsupported_component_is_reported() {
+    let prototypes: toml::value::Table = toml::from_str("[thing]\nposition = true\n").unwrap();
+
+    match PrototypeTable::new(prototypes) {
+        Err(PrototypeError::UnsupportedComponent { prototype, component }) => {
+            assert_eq!(prototype, "thing");
+            assert_eq!(component, "position");
+        }
+        other => panic!("Expected UnsupportedComponent, got {:?}", other),
+    }
+}
+
+#[test]
+fn resource_file_is_valid() {
+    assert!(PrototypeTable::from_file(resource_path(PROTOTYPE_FILE)).is_ok());
+}
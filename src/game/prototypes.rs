@@ -1,9 +1,7 @@
 use std::ops::DerefMut;
 use std::cmp;
-use num::PrimInt;
 
 use coord::Coord;
-use direction::Direction;
 
 use ecs::*;
 use game::*;
@@ -15,43 +13,21 @@ pub const PC_TURN_OFFSET: u64 = 2;
 pub const PHYSICS_TURN_OFFSET: u64 = 3;
 pub const ANIMATION_TURN_OFFSET: u64 = 4;
 
-pub fn pc<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
-    entity.insert_position(position);
-
-    entity.insert_tile(TileType::Van);
-
-    entity.insert_tile_depth(2);
-    entity.insert_collider();
-    entity.insert_behaviour_state(BehaviourState::new());
-    entity.insert_behaviour_type(BehaviourType::PlayerInput);
-    entity.insert_turn_offset(PC_TURN_OFFSET);
-    entity.insert_drawable_knowledge(DrawableKnowledge::new());
-    entity.insert_vision_distance(cmp::max(GAME_WIDTH, GAME_HEIGHT));
-    entity.insert_pc();
-    entity.insert_turn_time(TURN_DURATION_BASE);
-    entity.insert_should_render();
-    entity.insert_message_log(MessageLog::new());
-    entity.insert_projectile_collider();
-    entity.insert_bump_attackable();
-    entity.insert_weapon_slots(DirectionTable::new());
-    entity.insert_bank(0);
+const BURNING_WRECK_PROBABILITY: f64 = 0.2;
+const BURNING_WRECK_LIGHT_RADIUS: usize = 4;
+const EXPLOSION_LIGHT_RADIUS: usize = 3;
+const FLAME_LIGHT_RADIUS: usize = 2;
 
-    entity.insert_can_run_over();
+/// Number of physics turns a flame burns for
+pub const FLAME_BURN_TURNS: usize = 6;
 
-    entity.insert_hit_points(HitPoints::new(8));
-    entity.insert_current_speed(1);
-    entity.insert_facing(Direction::East);
-    entity.insert_redline_speed(3);
-    entity.insert_tyre_health(HitPoints::new(4));
-    entity.insert_engine_health(HitPoints::new(8));
-    entity.insert_armour(1);
+pub fn pc<E: EntityPopulate>(mut entity: E, position: Coord, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("pc", &mut entity);
 
-    entity.insert_complex_damage();
+    entity.insert_position(position);
 
-    entity.insert_inventory(EntitySet::new());
-    entity.insert_inventory_capacity(8);
-    entity.insert_letter_count(0);
-    entity.insert_triggers_explosion();
+    // the player can see everything on screen
+    entity.insert_vision_distance(cmp::max(GAME_WIDTH, GAME_HEIGHT));
 
     entity
 }
@@ -63,119 +39,100 @@ pub fn shop<E: EntityPopulate>(mut entity: E, inventory: EntitySet) -> E {
     entity
 }
 
-pub fn zombie<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
-    entity.insert_position(position);
+pub fn zombie<E: EntityPopulate>(mut entity: E, position: Coord, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("zombie", &mut entity);
 
-    entity.insert_tile(TileType::Zombie);
-
-    entity.insert_tile_depth(2);
-    entity.insert_collider();
-    entity.insert_behaviour_state(BehaviourState::new());
-    entity.insert_behaviour_type(BehaviourType::Zombie);
-    entity.insert_turn_offset(NPC_TURN_OFFSET);
-    entity.insert_vision_distance(8);
-    entity.insert_simple_npc_knowledge(SimpleNpcKnowledge::new());
-    entity.insert_path_traverse(PathTraverse::new());
-    entity.insert_turn_time(TURN_DURATION_BASE);
-    entity.insert_enemy();
-    entity.insert_projectile_collider();
-    entity.insert_hit_points(HitPoints::new(2));
-    entity.insert_health_bar();
-    entity.insert_bump_attacker(1);
-
-    entity.insert_can_be_run_over();
-    entity.insert_bloodstain_on_death();
-
-    entity.insert_name(NameMessageType::Zombie);
-    entity.insert_bump_verb(VerbMessageType::Claw);
+    entity.insert_position(position);
 
     entity
 }
 
-pub fn car<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
+pub fn car<E: EntityPopulate>(mut entity: E, position: Coord, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("car", &mut entity);
+
     entity.insert_position(position);
 
-    entity.insert_tile(TileType::Car);
+    entity
+}
 
-    entity.insert_tile_depth(2);
-    entity.insert_collider();
-    entity.insert_behaviour_state(BehaviourState::new());
-    entity.insert_behaviour_type(BehaviourType::Car);
-    entity.insert_turn_offset(NPC_TURN_OFFSET);
-    entity.insert_vision_distance(20);
-    entity.insert_simple_npc_knowledge(SimpleNpcKnowledge::new());
-    entity.insert_turn_time(TURN_DURATION_BASE);
-    entity.insert_enemy();
-    entity.insert_projectile_collider();
-    entity.insert_hit_points(HitPoints::new(5));
-    entity.insert_health_bar();
-    entity.insert_bump_attacker(1);
+pub fn bike<E: EntityPopulate>(mut entity: E, position: Coord, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("bike", &mut entity);
 
-    entity.insert_current_speed(1);
-    entity.insert_max_speed(4);
-    entity.insert_facing(Direction::East);
+    entity.insert_position(position);
 
-    entity.insert_destroy_when_out_of_bounds();
+    entity
+}
 
-    entity.insert_weapon_slots(DirectionTable::new());
-    entity.insert_can_run_over();
 
-    entity.insert_name(NameMessageType::Car);
-    entity.insert_bump_verb(VerbMessageType::Ram);
 
-    entity
-}
+pub fn bullet<E: EntityPopulate>(mut entity: E, position: Coord, velocity: RealtimeVelocity, range: usize) -> E {
 
-pub fn bike<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
     entity.insert_position(position);
+    entity.insert_realtime_velocity(velocity);
+    entity.insert_realtime_moves_remaining(range);
+    entity.insert_destroy_on_collision();
+    entity.insert_projectile();
+    entity.insert_collider();
+    entity.insert_projectile_damage(1);
+    entity.insert_destroy_when_stopped();
+    entity.insert_bullet();
 
-    entity.insert_tile(TileType::Bike);
+    entity.insert_tile(TileType::Bullet);
 
-    entity.insert_tile_depth(2);
-    entity.insert_collider();
-    entity.insert_behaviour_state(BehaviourState::new());
-    entity.insert_behaviour_type(BehaviourType::Bike);
-    entity.insert_turn_offset(NPC_TURN_OFFSET);
-    entity.insert_vision_distance(20);
-    entity.insert_simple_npc_knowledge(SimpleNpcKnowledge::new());
-    entity.insert_turn_time(TURN_DURATION_BASE);
-    entity.insert_enemy();
-    entity.insert_projectile_collider();
-    entity.insert_hit_points(HitPoints::new(3));
-    entity.insert_health_bar();
-    entity.insert_bump_attacker(1);
+    entity.insert_tile_depth(1);
 
-    entity.insert_current_speed(1);
-    entity.insert_max_speed(4);
-    entity.insert_facing(Direction::East);
+    entity
+}
 
-    entity.insert_destroy_when_out_of_bounds();
+pub fn harpoon<E: EntityPopulate>(entity: E, position: Coord, velocity: RealtimeVelocity, range: usize) -> E {
+    let mut entity = bullet(entity, position, velocity, range);
 
-    entity.insert_weapon_slots(DirectionTable::new());
-    entity.insert_can_run_over();
-    entity.insert_name(NameMessageType::Bike);
-    entity.insert_bump_verb(VerbMessageType::Ram);
+    entity.insert_harpoon();
+    entity.insert_tile(TileType::Harpoon);
 
     entity
 }
 
+pub fn rocket<E: EntityPopulate>(mut entity: E, position: Coord, velocity: RealtimeVelocity, range: usize) -> E {
+    entity.insert_position(position);
+    entity.insert_realtime_velocity(velocity);
+    entity.insert_realtime_moves_remaining(range);
+    entity.insert_explode_on_collision();
+    entity.insert_projectile();
+    entity.insert_collider();
+    entity.insert_destroy_when_stopped();
 
+    entity.insert_tile(TileType::Rocket);
+    entity.insert_tile_depth(1);
 
-pub fn bullet<E: EntityPopulate>(mut entity: E, position: Coord, velocity: RealtimeVelocity, range: usize) -> E {
+    entity
+}
 
+pub fn flame<E: EntityPopulate>(mut entity: E, position: Coord, velocity: RealtimeVelocity, range: usize) -> E {
     entity.insert_position(position);
+    entity.insert_tile(TileType::Flame);
+    entity.insert_tile_depth(1);
     entity.insert_realtime_velocity(velocity);
     entity.insert_realtime_moves_remaining(range);
     entity.insert_destroy_on_collision();
-    entity.insert_projectile();
-    entity.insert_collider();
     entity.insert_projectile_damage(1);
-    entity.insert_destroy_when_stopped();
-    entity.insert_bullet();
+    entity.insert_projectile();
+    entity.insert_light(Light::new(FLAME_LIGHT_RADIUS, LightDirection::All));
 
-    entity.insert_tile(TileType::Bullet);
+    // once stopped, the flame stays where it landed until it burns out
+    entity.insert_burning(FLAME_BURN_TURNS);
 
+    entity
+}
+
+pub fn mine<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
+    entity.insert_position(position);
+    entity.insert_tile(TileType::Mine);
     entity.insert_tile_depth(1);
+    entity.insert_explode_on_collision();
+    entity.insert_projectile_collider();
+    entity.insert_collider();
+    entity.insert_solid();
 
     entity
 }
@@ -194,6 +151,16 @@ pub fn road<E: EntityPopulate>(mut entity: E, position: Coord, rng: &GameRng) ->
     entity
 }
 
+/// Road painted with the line between two lanes along its top edge
+pub fn road_marking<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
+    entity.insert_position(position);
+    entity.insert_tile(TileType::RoadMarking);
+    entity.insert_tile_depth(0);
+    entity.insert_floor();
+
+    entity
+}
+
 pub fn dirt<E: EntityPopulate>(mut entity: E, position: Coord, rng: &GameRng) -> E {
     entity.insert_position(position);
 
@@ -225,7 +192,9 @@ pub fn acid<E: EntityPopulate>(mut entity: E, position: Coord, rng: &GameRng) ->
     entity
 }
 
-pub fn wreck<E: EntityPopulate>(mut entity: E, position: Coord, rng: &GameRng) -> E {
+pub fn wreck<E: EntityPopulate>(mut entity: E, position: Coord, rng: &GameRng, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("wreck", &mut entity);
+
     entity.insert_position(position);
 
     let tiles = [
@@ -237,8 +206,10 @@ pub fn wreck<E: EntityPopulate>(mut entity: E, position: Coord, rng: &GameRng) -
     let tile = *rng.select_uniform(&tiles);
 
     entity.insert_tile(tile);
-    entity.insert_tile_depth(2);
-    entity.insert_solid();
+
+    if rng.gen_f64() < BURNING_WRECK_PROBABILITY {
+        entity.insert_light(Light::new(BURNING_WRECK_LIGHT_RADIUS, LightDirection::All));
+    }
 
     entity
 }
@@ -269,46 +240,26 @@ pub fn bloodstain<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
     entity
 }
 
-pub fn pistol<E: EntityPopulate>(mut entity: E) -> E {
-
-    entity.insert_gun_type(GunType::Pistol);
-    entity.insert_name(NameMessageType::Pistol);
-    entity.insert_description(DescriptionMessageType::Pistol);
-    entity.insert_gun_range(6);
-    entity.insert_price(10);
+pub fn pistol<E: EntityPopulate>(mut entity: E, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("pistol", &mut entity);
 
     entity
 }
 
-pub fn shotgun<E: EntityPopulate>(mut entity: E) -> E {
-
-    entity.insert_gun_type(GunType::Shotgun);
-    entity.insert_name(NameMessageType::Shotgun);
-    entity.insert_description(DescriptionMessageType::Shotgun);
-    entity.insert_gun_range(3);
-    entity.insert_price(20);
+pub fn shotgun<E: EntityPopulate>(mut entity: E, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("shotgun", &mut entity);
 
     entity
 }
 
-pub fn machine_gun<E: EntityPopulate>(mut entity: E) -> E {
-
-    entity.insert_gun_type(GunType::MachineGun);
-    entity.insert_name(NameMessageType::MachineGun);
-    entity.insert_description(DescriptionMessageType::MachineGun);
-    entity.insert_gun_range(5);
-    entity.insert_price(30);
+pub fn machine_gun<E: EntityPopulate>(mut entity: E, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("machine_gun", &mut entity);
 
     entity
 }
 
-pub fn railgun<E: EntityPopulate>(mut entity: E) -> E {
-
-    entity.insert_gun_type(GunType::Railgun);
-    entity.insert_name(NameMessageType::Railgun);
-    entity.insert_description(DescriptionMessageType::Railgun);
-    entity.insert_gun_range(10);
-    entity.insert_price(100);
+pub fn railgun<E: EntityPopulate>(mut entity: E, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("railgun", &mut entity);
 
     entity
 }
@@ -321,24 +272,26 @@ pub fn goal<E: EntityPopulate>(mut entity: E, position: Coord, level_switch: Lev
     entity
 }
 
-pub fn letter<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
+pub fn letter<E: EntityPopulate>(mut entity: E, position: Coord, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("letter", &mut entity);
+
     entity.insert_position(position);
-    entity.insert_tile(TileType::Letter);
-    entity.insert_tile_depth(1);
-    entity.insert_letter();
-    entity.insert_destroyed_by_explosion();
 
     entity
 }
 
-pub fn barrel<E: EntityPopulate>(mut entity: E, position: Coord) -> E {
+pub fn barrel<E: EntityPopulate>(mut entity: E, position: Coord, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("barrel", &mut entity);
+
+    entity.insert_position(position);
+
+    entity
+}
+
+pub fn building<E: EntityPopulate>(mut entity: E, position: Coord, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("building", &mut entity);
+
     entity.insert_position(position);
-    entity.insert_tile(TileType::Barrel);
-    entity.insert_tile_depth(1);
-    entity.insert_explode_on_collision();
-    entity.insert_projectile_collider();
-    entity.insert_collider();
-    entity.insert_solid();
 
     entity
 }
@@ -354,24 +307,19 @@ pub fn explosion<E: EntityPopulate>(mut entity: E, position: Coord, velocity: Re
     entity.insert_projectile_damage(1);
     entity.insert_projectile();
     entity.insert_explosion();
+    entity.insert_light(Light::new(EXPLOSION_LIGHT_RADIUS, LightDirection::All));
 
     entity
 }
 
-pub fn engine_repair<E: EntityPopulate>(mut entity: E) -> E {
-
-    entity.insert_repair_type(RepairType::Engine);
-    entity.insert_name(NameMessageType::EngineRepair);
-    entity.insert_price(10);
+pub fn engine_repair<E: EntityPopulate>(mut entity: E, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("engine_repair", &mut entity);
 
     entity
 }
 
-pub fn tyres_repair<E: EntityPopulate>(mut entity: E) -> E {
-
-    entity.insert_repair_type(RepairType::Tyres);
-    entity.insert_name(NameMessageType::TyresRepair);
-    entity.insert_price(10);
+pub fn tyres_repair<E: EntityPopulate>(mut entity: E, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("tyres_repair", &mut entity);
 
     entity
 }
@@ -380,25 +328,26 @@ pub fn armour_upgrade<E: EntityPopulate>(mut entity: E, amount: usize) -> E {
 
     entity.insert_name(NameMessageType::ArmourUpgrade(amount));
     entity.insert_armour_upgrade(amount);
-    entity.insert_price(10 * 2.pow(amount as u32 - 1));
 
     entity
 }
 
-pub fn engine_repair_kit<E: EntityPopulate>(mut entity: E) -> E {
+pub fn engine_repair_kit<E: EntityPopulate>(mut entity: E, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("engine_repair_kit", &mut entity);
+
+    entity
+}
 
-    entity.insert_name(NameMessageType::EngineRepairKit);
-    entity.insert_consumable_type(ConsumableType::EngineRepairKit);
-    entity.insert_price(10);
+pub fn spare_tyre<E: EntityPopulate>(mut entity: E, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("spare_tyre", &mut entity);
 
     entity
 }
 
-pub fn spare_tyre<E: EntityPopulate>(mut entity: E) -> E {
+pub fn fuel_can<E: EntityPopulate>(mut entity: E, position: Coord, prototypes: &PrototypeTable) -> E {
+    prototypes.populate("fuel_can", &mut entity);
 
-    entity.insert_name(NameMessageType::SpareTyre);
-    entity.insert_consumable_type(ConsumableType::SpareTyre);
-    entity.insert_price(10);
+    entity.insert_position(position);
 
     entity
 }
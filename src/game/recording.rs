@@ -0,0 +1,179 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Write;
+use std::path;
+use std::str;
+
+use serde_json;
+
+use game::*;
+
+const SEED_PREFIX: &'static str = "seed ";
+const CONTROLS_PREFIX: &'static str = "controls ";
+const SAVE_PREFIX: &'static str = "save ";
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+
+    hex.as_bytes().chunks(2).map(|pair| {
+        str::from_utf8(pair).ok().and_then(|pair| u8::from_str_radix(pair, 16).ok())
+    }).collect()
+}
+
+/// Input source which passes through inputs from another input source,
+/// appending each one to a recording file. The file is flushed after every
+/// input so a recording survives the game crashing.
+///
+/// The recording begins with everything else a run depends on: the rng
+/// seed, the control map and the contents of every save slot.
+pub struct RecordingInputSource<I: InputSource> {
+    input_source: I,
+    file: Rc<RefCell<File>>,
+}
+
+impl<I: InputSource + Clone> Clone for RecordingInputSource<I> {
+    fn clone(&self) -> Self {
+        RecordingInputSource {
+            input_source: self.input_source.clone(),
+            file: self.file.clone(),
+        }
+    }
+}
+
+impl<I: InputSource> RecordingInputSource<I> {
+    pub fn new<P, Q>(input_source: I, rng_seed: usize, user_path: P, path: Q) -> ExternalResult<Self>
+        where P: AsRef<path::Path>,
+              Q: AsRef<path::Path>,
+    {
+        let mut file = File::create(path.as_ref())
+            .map_err(|e| format!("Couldn't create recording {:?}: {}", path.as_ref(), e))?;
+
+        let control_map = control_file::from_file(user_path.as_ref().join(user_files::CONTROL)).unwrap_or_default();
+        let controls = serde_json::to_string(&StringControlSpec::from(&control_map))
+            .map_err(|e| format!("Couldn't serialize controls: {}", e))?;
+
+        let mut header = format!("{}{}\n{}{}\n", SEED_PREFIX, rng_seed, CONTROLS_PREFIX, controls);
+        for (slot, contents) in save_file::slot_contents(user_path) {
+            header.push_str(&format!("{}{} {}\n", SAVE_PREFIX, slot, to_hex(&contents)));
+        }
+
+        file.write_all(header.as_bytes())
+            .map_err(|e| format!("Couldn't write recording {:?}: {}", path.as_ref(), e))?;
+
+        Ok(RecordingInputSource {
+            input_source: input_source,
+            file: Rc::new(RefCell::new(file)),
+        })
+    }
+}
+
+impl<I: InputSource> InputSource for RecordingInputSource<I> {
+    fn next_input(&mut self) -> Option<InputEvent> {
+        let maybe_input = self.input_source.next_input();
+
+        if let Some(input) = maybe_input {
+            let mut file = self.file.borrow_mut();
+            writeln!(file, "{}", input_script_line(input)).expect("Failed to write recording");
+            file.flush().expect("Failed to write recording");
+        }
+
+        maybe_input
+    }
+}
+
+pub struct InputRecording {
+    pub rng_seed: usize,
+    pub control_map: ControlMap,
+    /// Contents of each save slot when recording began
+    pub saves: Vec<(usize, Vec<u8>)>,
+    pub inputs: Vec<InputEvent>,
+}
+
+impl InputRecording {
+    /// Reads a recording file. The file starts with a header of lines
+    /// containing the rng seed, the control map and the save slots, and the
+    /// remainder is an input script. Recordings without a control map use
+    /// the default controls, and start with no saved games.
+    pub fn load<P: AsRef<path::Path>>(path: P) -> ExternalResult<Self> {
+        let contents = game_file::read_string(path.as_ref())
+            .map_err(|e| format!("Couldn't read recording {:?}: {:?}", path.as_ref(), e))?;
+
+        let mut lines = contents.split('\n');
+        let mut header_len = 0;
+
+        let rng_seed = lines.next()
+            .and_then(|line| {
+                let line = line.trim();
+                if line.starts_with(SEED_PREFIX) {
+                    line[SEED_PREFIX.len()..].parse::<usize>().ok()
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| format!("Recording {:?} is missing its rng seed", path.as_ref()))?;
+        header_len += 1;
+
+        let mut control_map = ControlMap::default();
+        let mut saves = Vec::new();
+        let mut script = String::new();
+
+        for line in lines {
+            if !script.is_empty() || !(line.starts_with(CONTROLS_PREFIX) || line.starts_with(SAVE_PREFIX)) {
+                script.push_str(line);
+                script.push('\n');
+                continue;
+            }
+
+            header_len += 1;
+
+            if line.starts_with(CONTROLS_PREFIX) {
+                let spec: StringControlSpec = serde_json::from_str(&line[CONTROLS_PREFIX.len()..])
+                    .map_err(|e| format!("Invalid controls in recording {:?}: {}", path.as_ref(), e))?;
+                control_map = ControlMap::from(&spec);
+            } else {
+                let mut parts = line[SAVE_PREFIX.len()..].trim().splitn(2, ' ');
+                let slot = parts.next().and_then(|slot| slot.parse::<usize>().ok());
+                let save = parts.next().and_then(from_hex);
+                match (slot, save) {
+                    (Some(slot), Some(save)) => saves.push((slot, save)),
+                    _ => return Err(format!("Invalid save on line {} of {:?}", header_len, path.as_ref())),
+                }
+            }
+        }
+
+        let inputs = match parse_input_script(&script) {
+            Ok(inputs) => inputs,
+            Err(InputScriptError::InvalidInput { line, input }) => {
+                // the header occupies the start of the file
+                return Err(format!("Invalid input on line {} of {:?}: {}", line + header_len, path.as_ref(), input));
+            }
+        };
+
+        Ok(InputRecording {
+            rng_seed: rng_seed,
+            control_map: control_map,
+            saves: saves,
+            inputs: inputs,
+        })
+    }
+
+    /// Fills an empty user directory with the controls and saves the
+    /// recording started with
+    pub fn install<P: AsRef<path::Path>>(&self, user_path: P) -> ExternalResult<()> {
+        control_file::to_file(user_path.as_ref().join(user_files::CONTROL), &self.control_map);
+
+        for &(slot, ref contents) in self.saves.iter() {
+            save_file::restore_slot(user_path.as_ref(), slot, contents)
+                .map_err(|e| format!("Couldn't restore save slot {}: {}", slot, e))?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,19 @@
+use coord::Coord;
+
+#[derive(Debug)]
 pub enum RenderOverlay {
     Death,
+    /// Line of fire from the shooter to a target, in world-space. The
+    /// last coordinate is the target.
+    AimLine(Vec<Coord>),
+    /// Cells a single projectile would pass through, in world-space
+    BulletPath(Vec<Coord>),
+    /// Cells which could be hit by a spread of projectiles
+    SpreadCone(Vec<Coord>),
+    /// Cells caught in an explosion
+    ExplosionRadius(Vec<Coord>),
+    /// Cell being examined, in world-space
+    ExamineCursor(Coord),
+    /// Several overlays drawn in order, so later ones appear on top
+    Layers(Vec<RenderOverlay>),
 }
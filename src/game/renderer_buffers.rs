@@ -1,8 +1,10 @@
 use game::*;
+use game::data::*;
 
 pub struct RendererBuffers {
     pub tiles: TileBuffer,
     pub message_log: Vec<Message>,
+    pub weather: Weather,
 }
 
 impl RendererBuffers {
@@ -15,6 +17,7 @@ impl RendererBuffers {
         RendererBuffers {
             tiles: TileBuffer::new(width, height),
             message_log: message_log,
+            weather: Weather::Clear,
         }
     }
 
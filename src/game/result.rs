@@ -1,5 +1,6 @@
 use std::result;
 use behaviour;
+use game::save_file;
 
 pub type GameResult<T> = result::Result<T, GameError>;
 
@@ -7,6 +8,7 @@ pub type GameResult<T> = result::Result<T, GameError>;
 pub enum GameError {
     ScheduleEmpty,
     BehaviourError(behaviour::Error),
+    SaveError(save_file::SaveError),
 }
 
 impl From<behaviour::Error> for GameError {
@@ -15,6 +17,12 @@ impl From<behaviour::Error> for GameError {
     }
 }
 
+impl From<save_file::SaveError> for GameError {
+    fn from(e: save_file::SaveError) -> Self {
+        GameError::SaveError(e)
+    }
+}
+
 pub type ExternalResult<T> = result::Result<T, String>;
 
 impl From<GameError> for String {
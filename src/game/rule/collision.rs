@@ -13,6 +13,10 @@ pub fn collision(env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>
                 if env.ecs.contains_explode_on_collision(solid_id) && entity.contains_triggers_explosion() {
                     reactions.push(Reaction::new(ActionArgs::Explode(solid_id), 0));
                 }
+                if entity.contains_explode_on_collision() {
+                    // rockets explode where they hit a wall
+                    reactions.push(Reaction::new(ActionArgs::Explode(entity_id), 0));
+                }
                 if entity.current_realtime_velocity().is_some() {
                     reactions.push(Reaction::new(ActionArgs::RealtimeVelocityStop(entity_id), 0));
                 }
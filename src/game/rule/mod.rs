@@ -1,5 +1,7 @@
 mod types;
+mod registry;
 pub use self::types::*;
+pub use self::registry::*;
 
 mod collision;
 mod realtime_velocity;
@@ -15,8 +17,12 @@ mod bounds;
 mod then;
 mod acid;
 mod letter;
+mod pickup;
 mod explosion;
 
+#[cfg(test)]
+mod tests;
+
 pub mod rules {
     pub use super::collision::*;
     pub use super::realtime_velocity::*;
@@ -32,5 +38,6 @@ pub mod rules {
     pub use super::then::*;
     pub use super::acid::*;
     pub use super::letter::*;
+    pub use super::pickup::*;
     pub use super::explosion::*;
 }
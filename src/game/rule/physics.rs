@@ -6,6 +6,22 @@ use direction::Direction;
 
 const SPEED_CELLS_PER_SEC: f64 = 20.0;
 
+/// Damage dealt each physics turn to whatever is in a burning cell
+pub const FIRE_DAMAGE: usize = 1;
+
+/// Returns true iff an entity has a fuel can in its inventory
+fn carries_fuel(ecs: &EcsCtx, entity_id: EntityId) -> bool {
+    ecs.inventory_borrow(entity_id).map(|inventory| {
+        inventory.iter().any(|item_id| {
+            if let Some(ConsumableType::FuelCan) = ecs.consumable_type(item_id) {
+                true
+            } else {
+                false
+            }
+        })
+    }).unwrap_or(false)
+}
+
 pub fn physics(env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
 
     if !action.contains_physics() {
@@ -25,11 +41,43 @@ pub fn physics(env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>)
     }
 
     for (entity_id, speed) in env.ecs.current_speed_iter() {
+        let speed = if speed != 0 && env.ecs.entity(entity_id).out_of_fuel() {
+            // without fuel, vehicles slow to a stop
+            reactions.push(Reaction::new(ActionArgs::ChangeSpeed(entity_id, ChangeSpeed::Decelerate), 0));
+            speed - 1
+        } else {
+            speed
+        };
+
         if speed != 0 {
             if let Some(facing) = env.ecs.facing(entity_id) {
                 let velocity = RealtimeVelocity::new(facing.vector(), SPEED_CELLS_PER_SEC);
                 reactions.push(Reaction::new(ActionArgs::RealtimeVelocityStart(entity_id, velocity, speed), 0));
             }
+        } else if env.ecs.contains_pc(entity_id) && env.ecs.entity(entity_id).out_of_fuel() && !carries_fuel(env.ecs, entity_id) {
+            // the player can't go anywhere without fuel
+            reactions.push(Reaction::new(ActionArgs::Strand, 0));
+        }
+    }
+
+    // fires burn down, hurting whatever shares their cell
+    let mut burning_cells = Vec::new();
+    for (entity_id, _) in env.ecs.burning_iter() {
+        reactions.push(Reaction::new(ActionArgs::Burn(entity_id), 0));
+        if let Some(position) = env.ecs.position(entity_id) {
+            if !burning_cells.contains(&position) {
+                burning_cells.push(position);
+            }
+        }
+    }
+
+    for position in burning_cells {
+        for entity_id in env.spatial_hash.get(position).entity_id_iter() {
+            if env.ecs.contains_complex_damage(entity_id) {
+                reactions.push(Reaction::new(ActionArgs::ComplexDamage(entity_id, FIRE_DAMAGE), 0));
+            } else if env.ecs.contains_hit_points(entity_id) {
+                reactions.push(Reaction::new(ActionArgs::Damage(entity_id, FIRE_DAMAGE), 0));
+            }
         }
     }
 
@@ -0,0 +1,18 @@
+use game::*;
+use ecs::*;
+
+pub fn pickup(env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
+
+    for (entity_id, position) in action.position_profile().insertion_copy_iter() {
+
+        if let Some(item_id) = env.spatial_hash.get(position).any_consumable_type() {
+
+            // items on the ground are used immediately, if they would have any effect
+            if env.ecs.contains_inventory(entity_id) {
+                reactions.push(Reaction::new(ActionArgs::Consume(entity_id, item_id), 0));
+            }
+        }
+    }
+
+    RULE_ACCEPT
+}
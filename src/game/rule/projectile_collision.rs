@@ -2,6 +2,9 @@ use game::*;
 use game::data::*;
 use ecs::*;
 
+/// Number of cells a harpoon drags whatever it hits
+const HARPOON_PULL_DISTANCE: usize = 4;
+
 pub fn projectile_collision_trigger(env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
 
     for (projectile_id, position) in action.position_profile().insertion_copy_iter() {
@@ -12,8 +15,10 @@ pub fn projectile_collision_trigger(env: RuleEnv, action: &EcsAction, reactions:
 
             if projectile.contains_projectile() {
 
-                if projectile.contains_destroy_on_collision() {
-                    // must happen after processing the collision
+                // must happen after processing the collision
+                if projectile.contains_explode_on_collision() {
+                    reactions.push(Reaction::new(ActionArgs::Explode(projectile_id), 1));
+                } else if projectile.contains_destroy_on_collision() {
                     reactions.push(Reaction::new(ActionArgs::Destroy(projectile_id), 1));
                 }
 
@@ -42,6 +47,16 @@ pub fn projectile_collision(env: RuleEnv, action: &EcsAction, reactions: &mut Ve
                 reactions.push(Reaction::new(ActionArgs::Explode(collider_id), 0));
             }
         }
+
+        if projectile.contains_harpoon() && env.ecs.contains_enemy(collider_id) {
+            if let Some(shooter_id) = projectile.shooter_id() {
+                reactions.push(Reaction::new(ActionArgs::Pull {
+                    entity_id: collider_id,
+                    towards_id: shooter_id,
+                    remaining: HARPOON_PULL_DISTANCE,
+                }, 0));
+            }
+        }
     }
 
     RULE_ACCEPT
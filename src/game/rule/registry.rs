@@ -0,0 +1,205 @@
+use std::slice;
+
+use game::*;
+use ecs::*;
+
+pub trait Rule {
+    fn check(&self, env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult;
+}
+
+impl<F> Rule for F
+    where F: Fn(RuleEnv, &EcsAction, &mut Vec<Reaction>) -> RuleResult,
+{
+    fn check(&self, env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
+        self(env, action, reactions)
+    }
+}
+
+pub struct NamedRule {
+    name: &'static str,
+    rule: Box<Rule>,
+}
+
+impl NamedRule {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn rule(&self) -> &Rule {
+        self.rule.as_ref()
+    }
+}
+
+/// Rules checked in order against an action. The first rule to reject,
+/// force or consume the action decides its fate, and later rules are
+/// not checked.
+pub struct RuleList {
+    rules: Vec<NamedRule>,
+}
+
+impl RuleList {
+    pub fn new() -> Self {
+        RuleList {
+            rules: Vec::new(),
+        }
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.rules.iter().position(|r| r.name == name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    pub fn push<R: 'static + Rule>(&mut self, name: &'static str, rule: R) {
+        self.rules.push(NamedRule {
+            name: name,
+            rule: Box::new(rule),
+        });
+    }
+
+    /// Inserts a rule to be checked just before the named rule. Returns
+    /// false, and doesn't insert the rule, if the named rule isn't present.
+    pub fn insert_before<R: 'static + Rule>(&mut self, before: &str, name: &'static str, rule: R) -> bool {
+        if let Some(index) = self.position(before) {
+            self.rules.insert(index, NamedRule {
+                name: name,
+                rule: Box::new(rule),
+            });
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Inserts a rule to be checked just after the named rule. Returns
+    /// false, and doesn't insert the rule, if the named rule isn't present.
+    pub fn insert_after<R: 'static + Rule>(&mut self, after: &str, name: &'static str, rule: R) -> bool {
+        if let Some(index) = self.position(after) {
+            self.rules.insert(index + 1, NamedRule {
+                name: name,
+                rule: Box::new(rule),
+            });
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<NamedRule> {
+        self.position(name).map(|index| self.rules.remove(index))
+    }
+
+    pub fn iter(&self) -> slice::Iter<NamedRule> {
+        self.rules.iter()
+    }
+
+    pub fn check(&self, env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
+        for named_rule in self.rules.iter() {
+            named_rule.rule.check(env, action, reactions)?;
+        }
+
+        RULE_ACCEPT
+    }
+
+    /// Like `check`, but logs the resolution of each rule, and the
+    /// reactions it added, to the debug writer
+    pub fn check_traced(&self, env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
+        for named_rule in self.rules.iter() {
+            let num_reactions = reactions.len();
+            let result = named_rule.rule.check(env, action, reactions);
+
+            match result {
+                Ok(()) => debug_println!("  rule {}: continue", named_rule.name),
+                Err(ref resolution) => debug_println!("  rule {}: {:?}", named_rule.name, resolution),
+            }
+
+            for reaction in &reactions[num_reactions..] {
+                debug_println!("    reaction {:?} (delay {})", reaction.action, reaction.delay);
+            }
+
+            result?;
+        }
+
+        RULE_ACCEPT
+    }
+}
+
+/// The rules governing which actions may be committed. Actions marked
+/// no_commit (e.g. projectiles probing for collisions) are checked against
+/// a separate list from all other actions.
+pub struct RuleRegistry {
+    commit: RuleList,
+    no_commit: RuleList,
+}
+
+impl RuleRegistry {
+    pub fn new() -> Self {
+        RuleRegistry {
+            commit: RuleList::new(),
+            no_commit: RuleList::new(),
+        }
+    }
+
+    /// The rules of the base game
+    pub fn standard() -> Self {
+        let mut registry = Self::new();
+
+        registry.no_commit.push("projectile_collision", rules::projectile_collision);
+
+        registry.commit.push("then", rules::then);
+        registry.commit.push("bounds", rules::bounds);
+        registry.commit.push("letter", rules::letter);
+        registry.commit.push("pickup", rules::pickup);
+        registry.commit.push("acid", rules::acid);
+        registry.commit.push("run_over", rules::run_over);
+        registry.commit.push("bump_attack", rules::bump_attack);
+        registry.commit.push("projectile_collision_trigger", rules::projectile_collision_trigger);
+        registry.commit.push("collision", rules::collision);
+        registry.commit.push("death", rules::death);
+        registry.commit.push("enemy_collision", rules::enemy_collision);
+        registry.commit.push("pc_collision", rules::pc_collision);
+        registry.commit.push("level_switch", rules::level_switch);
+        registry.commit.push("level_switch_auto", rules::level_switch_auto);
+        registry.commit.push("physics", rules::physics);
+        registry.commit.push("driving", rules::driving);
+        registry.commit.push("realtime_velocity_start", rules::realtime_velocity_start);
+        registry.commit.push("realtime_velocity", rules::realtime_velocity);
+        registry.commit.push("explosion_destroy", rules::explosion_destroy);
+
+        registry
+    }
+
+    pub fn commit_rules(&self) -> &RuleList {
+        &self.commit
+    }
+
+    pub fn commit_rules_mut(&mut self) -> &mut RuleList {
+        &mut self.commit
+    }
+
+    pub fn no_commit_rules(&self) -> &RuleList {
+        &self.no_commit
+    }
+
+    pub fn no_commit_rules_mut(&mut self) -> &mut RuleList {
+        &mut self.no_commit
+    }
+
+    pub fn rules_for(&self, action: &EcsAction) -> &RuleList {
+        if action.contains_no_commit() {
+            &self.no_commit
+        } else {
+            &self.commit
+        }
+    }
+
+    pub fn check(&self, env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
+        self.rules_for(action).check(env, action, reactions)
+    }
+
+    pub fn check_traced(&self, env: RuleEnv, action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
+        self.rules_for(action).check_traced(env, action, reactions)
+    }
+}
@@ -0,0 +1,282 @@
+use ecs::*;
+use game::*;
+use game::data::*;
+use spatial_hash::*;
+use util::LeakyReserver;
+use coord::Coord;
+
+const WIDTH: usize = 10;
+const HEIGHT: usize = 10;
+const ACTION_ID: u64 = 1;
+
+struct Env {
+    sh: SpatialHashTable,
+    ecs: EcsCtx,
+    ids: LeakyReserver<EntityId>,
+}
+
+impl Env {
+    fn new() -> Self {
+        Env {
+            sh: SpatialHashTable::new(WIDTH, HEIGHT),
+            ecs: EcsCtx::new(),
+            ids: LeakyReserver::new(),
+        }
+    }
+
+    fn commit(&mut self, action: &mut EcsAction) {
+        self.sh.update(&self.ecs, action, ACTION_ID);
+        self.ecs.commit(action);
+    }
+
+    fn add_fire(&mut self, position: Coord, turns: usize) -> EntityId {
+        let id = self.ids.reserve();
+        let mut action = EcsAction::new();
+        action.insert_position(id, position);
+        action.insert_burning(id, turns);
+        self.commit(&mut action);
+        id
+    }
+
+    fn add_victim(&mut self, position: Coord) -> EntityId {
+        let id = self.ids.reserve();
+        let mut action = EcsAction::new();
+        action.insert_position(id, position);
+        action.insert_hit_points(id, HitPoints::new(3));
+        self.commit(&mut action);
+        id
+    }
+
+    fn physics(&self) -> Vec<Reaction> {
+        let mut action = EcsAction::new();
+        actions::physics(&mut action);
+
+        let env = RuleEnv {
+            ecs: &self.ecs,
+            spatial_hash: &self.sh,
+        };
+
+        let mut reactions = Vec::new();
+        assert!(rules::physics(env, &action, &mut reactions).is_ok());
+        reactions
+    }
+}
+
+fn damaged(reactions: &[Reaction]) -> Vec<EntityId> {
+    reactions.iter().filter_map(|reaction| match reaction.action {
+        ActionArgs::Damage(entity_id, damage) => {
+            assert_eq!(damage, rules::FIRE_DAMAGE);
+            Some(entity_id)
+        }
+        _ => None,
+    }).collect()
+}
+
+fn burnt(reactions: &[Reaction]) -> Vec<EntityId> {
+    reactions.iter().filter_map(|reaction| match reaction.action {
+        ActionArgs::Burn(entity_id) => Some(entity_id),
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn fire_damages_whatever_stands_in_it() {
+    let mut env = Env::new();
+    env.add_fire(Coord::new(3, 3), 4);
+    let victim_id = env.add_victim(Coord::new(3, 3));
+    env.add_victim(Coord::new(4, 3));
+
+    assert_eq!(damaged(&env.physics()), vec![victim_id]);
+}
+
+#[test]
+fn overlapping_fires_damage_once() {
+    let mut env = Env::new();
+    env.add_fire(Coord::new(3, 3), 4);
+    env.add_fire(Coord::new(3, 3), 2);
+    let victim_id = env.add_victim(Coord::new(3, 3));
+
+    let reactions = env.physics();
+
+    assert_eq!(damaged(&reactions), vec![victim_id]);
+    assert_eq!(burnt(&reactions).len(), 2);
+}
+
+#[test]
+fn every_fire_burns_down() {
+    let mut env = Env::new();
+    let fire_id = env.add_fire(Coord::new(3, 3), 4);
+
+    assert_eq!(burnt(&env.physics()), vec![fire_id]);
+}
+
+/// How a test rule resolves
+#[derive(Clone, Copy)]
+enum Outcome {
+    Continue,
+    Reject,
+    Force,
+    Consume,
+}
+
+/// Rule which records that it was checked by adding a reaction delayed by
+/// `mark`, then resolves with `outcome`
+struct Marker {
+    mark: u64,
+    outcome: Outcome,
+}
+
+impl Rule for Marker {
+    fn check(&self, _env: RuleEnv, _action: &EcsAction, reactions: &mut Vec<Reaction>) -> RuleResult {
+        reactions.push(Reaction::new(ActionArgs::Null, self.mark));
+        match self.outcome {
+            Outcome::Continue => RULE_ACCEPT,
+            Outcome::Reject => RULE_REJECT,
+            Outcome::Force => RULE_FORCE,
+            Outcome::Consume => rule_consume(ActionArgs::Physics),
+        }
+    }
+}
+
+fn marker(mark: u64, outcome: Outcome) -> Marker {
+    Marker {
+        mark: mark,
+        outcome: outcome,
+    }
+}
+
+/// Checks an action against a list of rules, returning the result along
+/// with the marks of the rules which were checked
+fn check(rules: &RuleList, action: &EcsAction) -> (RuleResult, Vec<u64>) {
+    let env = Env::new();
+    let rule_env = RuleEnv {
+        ecs: &env.ecs,
+        spatial_hash: &env.sh,
+    };
+
+    let mut reactions = Vec::new();
+    let result = rules.check(rule_env, action, &mut reactions);
+    (result, reactions.iter().map(|reaction| reaction.delay).collect())
+}
+
+fn names(rules: &RuleList) -> Vec<&'static str> {
+    rules.iter().map(NamedRule::name).collect()
+}
+
+#[test]
+fn rules_are_checked_in_order() {
+    let mut rules = RuleList::new();
+    rules.push("a", marker(1, Outcome::Continue));
+    rules.push("b", marker(2, Outcome::Continue));
+    rules.push("c", marker(3, Outcome::Continue));
+
+    let (result, marks) = check(&rules, &EcsAction::new());
+
+    assert!(result.is_ok());
+    assert_eq!(marks, vec![1, 2, 3]);
+}
+
+#[test]
+fn rules_are_inserted_beside_named_rules() {
+    let mut rules = RuleList::new();
+    rules.push("a", marker(1, Outcome::Continue));
+    rules.push("c", marker(3, Outcome::Continue));
+
+    assert!(rules.insert_before("c", "b", marker(2, Outcome::Continue)));
+    assert!(rules.insert_after("c", "d", marker(4, Outcome::Continue)));
+
+    assert_eq!(names(&rules), vec!["a", "b", "c", "d"]);
+    assert_eq!(check(&rules, &EcsAction::new()).1, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn rules_are_not_inserted_beside_missing_rules() {
+    let mut rules = RuleList::new();
+    rules.push("a", marker(1, Outcome::Continue));
+
+    assert!(!rules.insert_before("missing", "b", marker(2, Outcome::Continue)));
+    assert!(!rules.insert_after("missing", "c", marker(3, Outcome::Continue)));
+
+    assert_eq!(names(&rules), vec!["a"]);
+}
+
+#[test]
+fn removed_rules_are_not_checked() {
+    let mut rules = RuleList::new();
+    rules.push("a", marker(1, Outcome::Continue));
+    rules.push("b", marker(2, Outcome::Continue));
+
+    assert_eq!(rules.remove("a").map(|rule| rule.name()), Some("a"));
+    assert!(rules.remove("a").is_none());
+    assert!(!rules.contains("a"));
+    assert_eq!(check(&rules, &EcsAction::new()).1, vec![2]);
+}
+
+#[test]
+fn rejecting_rule_stops_later_rules() {
+    let mut rules = RuleList::new();
+    rules.push("a", marker(1, Outcome::Continue));
+    rules.push("b", marker(2, Outcome::Reject));
+    rules.push("c", marker(3, Outcome::Continue));
+
+    let (result, marks) = check(&rules, &EcsAction::new());
+
+    match result {
+        Err(RuleResolution::Reject) => {}
+        other => panic!("Unexpected result: {:?}", other),
+    }
+    assert_eq!(marks, vec![1, 2]);
+}
+
+#[test]
+fn forcing_rule_stops_later_rules() {
+    let mut rules = RuleList::new();
+    rules.push("a", marker(1, Outcome::Force));
+    rules.push("b", marker(2, Outcome::Reject));
+
+    let (result, marks) = check(&rules, &EcsAction::new());
+
+    match result {
+        Err(RuleResolution::Accept) => {}
+        other => panic!("Unexpected result: {:?}", other),
+    }
+    assert_eq!(marks, vec![1]);
+}
+
+#[test]
+fn consuming_rule_stops_later_rules() {
+    let mut rules = RuleList::new();
+    rules.push("a", marker(1, Outcome::Continue));
+    rules.push("b", marker(2, Outcome::Consume));
+    rules.push("c", marker(3, Outcome::Reject));
+
+    let (result, marks) = check(&rules, &EcsAction::new());
+
+    match result {
+        Err(RuleResolution::Consume(ActionArgs::Physics)) => {}
+        other => panic!("Unexpected result: {:?}", other),
+    }
+    assert_eq!(marks, vec![1, 2]);
+}
+
+#[test]
+fn no_commit_actions_are_checked_against_their_own_rules() {
+    let mut registry = RuleRegistry::new();
+    registry.commit_rules_mut().push("commit", marker(1, Outcome::Continue));
+    registry.no_commit_rules_mut().push("no_commit", marker(2, Outcome::Continue));
+
+    let mut probe = EcsAction::new();
+    probe.set_no_commit();
+
+    assert_eq!(check(registry.rules_for(&EcsAction::new()), &EcsAction::new()).1, vec![1]);
+    assert_eq!(check(registry.rules_for(&probe), &probe).1, vec![2]);
+}
+
+#[test]
+fn standard_rules_resolve_then_first() {
+    let registry = RuleRegistry::standard();
+
+    assert_eq!(names(registry.commit_rules()).first(), Some(&"then"));
+    assert!(registry.commit_rules().contains("physics"));
+    assert_eq!(names(registry.no_commit_rules()), vec!["projectile_collision"]);
+}
@@ -6,6 +6,7 @@ use spatial_hash::*;
 
 pub type RuleResult = result::Result<(), RuleResolution>;
 
+#[derive(Debug)]
 pub enum RuleResolution {
     Accept,
     Reject,
@@ -20,7 +21,7 @@ pub fn rule_consume(action_args: ActionArgs) -> RuleResult {
     Err(RuleResolution::Consume(action_args))
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Reaction {
     pub action: ActionArgs,
     pub delay: u64,
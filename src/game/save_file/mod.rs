@@ -0,0 +1,375 @@
+use std::path::{Path, PathBuf};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::result;
+use std::time::{SystemTime, UNIX_EPOCH};
+use bincode::{self, SizeLimit};
+use serde::Deserialize;
+use serde_json;
+use game::*;
+use game::data::*;
+
+const SAVE_FILE: &'static str = "save";
+const TEMP_EXTENSION: &'static str = "tmp";
+const BACKUP_EXTENSION: &'static str = "bak";
+
+pub const NUM_SAVE_SLOTS: usize = 5;
+
+/// First bytes of every save file
+const MAGIC: [u8; 4] = [b'A', b'P', b'S', b'V'];
+
+/// Version of the layout of save files. This is bumped once per release
+/// in which a change to ecs.toml, sh.toml or `SerializableGameState`
+/// changes how a game is encoded, not once per commit, with a migration
+/// from the previously released version added to `MIGRATIONS`. Until a
+/// version is released, its encoding may still change without a bump.
+/// The `format_version_matches_schema` test fails until the version is
+/// reconsidered.
+///
+/// Changes to the behaviour graph don't affect saves, as behaviour states
+/// are reset when a game is loaded.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Saves older than this were written with a different set of components
+/// in ecs.toml. This changes the encoding of every level, so they can't be
+/// migrated.
+const OLDEST_COMPATIBLE_FORMAT_VERSION: u32 = 1;
+
+/// Saves written before the envelope existed are raw bincode with no header
+const LEGACY_FORMAT_VERSION: u32 = 0;
+
+const GAME_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+type Migration = fn(Vec<u8>) -> SaveResult<Vec<u8>>;
+
+/// `MIGRATIONS[i]` converts the body of a version `i` save into version `i + 1`
+const MIGRATIONS: [Migration; FORMAT_VERSION as usize] = [
+    // legacy saves predate the components added alongside the envelope
+    migrate_incompatible,
+];
+
+#[derive(Debug)]
+pub enum SaveError {
+    FailedToRead,
+    FailedToWrite,
+    FailedToExport,
+    UnrecognisedFile,
+    NewerFormat {
+        format_version: u32,
+        game_version: String,
+    },
+    CorruptSave {
+        format_version: u32,
+    },
+    IncompatibleFormat,
+}
+
+pub type SaveResult<T> = result::Result<T, SaveError>;
+
+#[derive(Serialize, Deserialize)]
+struct SaveHeader {
+    magic: [u8; 4],
+    format_version: u32,
+    game_version: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveEnvelope {
+    header: SaveHeader,
+    metadata: SaveMetadata,
+    body: Vec<u8>,
+}
+
+struct OpenedSave {
+    format_version: u32,
+    metadata: Option<SaveMetadata>,
+    body: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct SaveExport<'a> {
+    format_version: u32,
+    game_version: &'a str,
+    state: &'a SerializableGameState,
+}
+
+fn migrate_incompatible(_body: Vec<u8>) -> SaveResult<Vec<u8>> {
+    Err(SaveError::IncompatibleFormat)
+}
+
+fn migrate(mut format_version: u32, mut body: Vec<u8>) -> SaveResult<Vec<u8>> {
+    while format_version < FORMAT_VERSION {
+        body = MIGRATIONS[format_version as usize](body)?;
+        format_version += 1;
+    }
+
+    Ok(body)
+}
+
+/// Deserializes without allowing a corrupt length prefix to request more
+/// memory than the encoded data could possibly describe
+fn decode_bounded<T: Deserialize>(encoded: &[u8]) -> Option<T> {
+    let mut reader = encoded;
+    bincode::deserialize_from(&mut reader, SizeLimit::Bounded(encoded.len() as u64)).ok()
+}
+
+fn slot_path<P: AsRef<Path>>(user_path: P, slot: usize) -> PathBuf {
+    // the first slot keeps the name used before slots existed
+    if slot == 0 {
+        user_path.as_ref().join(SAVE_FILE)
+    } else {
+        user_path.as_ref().join(format!("{}{}", SAVE_FILE, slot))
+    }
+}
+
+/// The previous save in a slot, kept until the next save is complete
+fn backup_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    path.as_ref().with_extension(BACKUP_EXTENSION)
+}
+
+/// Replaces the file at `path` such that a crash at any point leaves either
+/// the new contents at `path`, or the old contents at its backup path
+fn write_atomic<P: AsRef<Path>>(path: P, encoded: &[u8]) -> io::Result<()> {
+    let path = path.as_ref();
+    let temp_path = path.with_extension(TEMP_EXTENSION);
+
+    {
+        let mut f = File::create(&temp_path)?;
+        f.write_all(encoded)?;
+        f.sync_all()?;
+    }
+
+    if path.exists() {
+        fs::rename(path, backup_path(path))?;
+    }
+
+    fs::rename(&temp_path, path)?;
+
+    sync_parent(path)
+}
+
+/// Renames aren't durable until the directory containing them is synced
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if parent != Path::new("") => File::open(parent)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+/// Directories can't be opened as files on windows, where renames are
+/// flushed with the file system's metadata
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+fn timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn encode(game_state: &SerializableGameState, metadata: SaveMetadata) -> Vec<u8> {
+    let body = bincode::serialize(game_state, SizeLimit::Infinite).expect("Failed to serialize game state");
+
+    let envelope = SaveEnvelope {
+        header: SaveHeader {
+            magic: MAGIC,
+            format_version: FORMAT_VERSION,
+            game_version: GAME_VERSION.to_string(),
+        },
+        metadata: metadata,
+        body: body,
+    };
+
+    bincode::serialize(&envelope, SizeLimit::Infinite).expect("Failed to serialize save envelope")
+}
+
+fn open(encoded: Vec<u8>) -> SaveResult<OpenedSave> {
+    let format_version = match decode_bounded::<SaveHeader>(&encoded) {
+        Some(header) => {
+            if header.magic != MAGIC {
+                LEGACY_FORMAT_VERSION
+            } else if header.format_version > FORMAT_VERSION {
+                return Err(SaveError::NewerFormat {
+                    format_version: header.format_version,
+                    game_version: header.game_version,
+                });
+            } else {
+                header.format_version
+            }
+        }
+        None => LEGACY_FORMAT_VERSION,
+    };
+
+    let opened = match format_version {
+        LEGACY_FORMAT_VERSION => Some(OpenedSave {
+            format_version: format_version,
+            metadata: None,
+            body: encoded,
+        }),
+        _ => decode_bounded::<SaveEnvelope>(&encoded).map(|envelope| OpenedSave {
+            format_version: format_version,
+            metadata: Some(envelope.metadata),
+            body: envelope.body,
+        }),
+    };
+
+    opened.ok_or(SaveError::CorruptSave { format_version: format_version })
+}
+
+fn decode(opened: OpenedSave) -> SaveResult<SerializableGameState> {
+    let OpenedSave { format_version, body, .. } = opened;
+
+    let body = migrate(format_version, body)?;
+
+    decode_bounded(&body).ok_or_else(|| {
+        if format_version == LEGACY_FORMAT_VERSION {
+            SaveError::UnrecognisedFile
+        } else {
+            SaveError::CorruptSave { format_version: format_version }
+        }
+    })
+}
+
+fn read_opened<P: AsRef<Path>>(path: P) -> SaveResult<Option<OpenedSave>> {
+    if let Ok(mut f) = File::open(path) {
+        let mut encoded = Vec::new();
+        f.read_to_end(&mut encoded).map_err(|_| SaveError::FailedToRead)?;
+        open(encoded).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Opens the save in a slot, falling back to its backup if the save is
+/// missing or damaged (e.g. by a crash during saving)
+fn open_slot<P: AsRef<Path>>(user_path: P, slot: usize) -> SaveResult<Option<(PathBuf, OpenedSave)>> {
+    let path = slot_path(user_path, slot);
+
+    let result = match read_opened(&path) {
+        Ok(Some(opened)) => return Ok(Some((path, opened))),
+        other => other,
+    };
+
+    let backup = backup_path(&path);
+    if let Ok(Some(opened)) = read_opened(&backup) {
+        return Ok(Some((backup, opened)));
+    }
+
+    result.map(|_| None)
+}
+
+fn read<P: AsRef<Path>>(user_path: P, slot: usize) -> SaveResult<Option<SerializableGameState>> {
+    match open_slot(user_path, slot)? {
+        Some((_, opened)) => decode(opened).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Saves a game, handing it back along with whether it was saved. A failure
+/// to save is also reported in the player's message log.
+pub fn save<P: AsRef<Path>>(user_path: P, slot: usize, game_state: GameState) -> (GameState, SaveResult<()>) {
+    let metadata = game_state.metadata(timestamp(SystemTime::now())).expect("Uninitialised game state");
+    let serializable = SerializableGameState::from(game_state);
+
+    let encoded = encode(&serializable, metadata);
+
+    let result = write_atomic(slot_path(user_path, slot), &encoded).map_err(|_| SaveError::FailedToWrite);
+
+    let game_state = GameState::from(serializable);
+    if result.is_err() {
+        game_state.add_message(MessageType::SaveFailed);
+    }
+
+    (game_state, result)
+}
+
+/// Saves a game which is still being played
+pub fn autosave<P: AsRef<Path>>(user_path: P, slot: usize, game_state: &mut GameState) {
+    let to_save = mem::replace(game_state, GameState::new());
+    let (saved, _) = save(user_path, slot, to_save);
+    *game_state = saved;
+}
+
+pub fn load<P: AsRef<Path>>(user_path: P, slot: usize) -> SaveResult<Option<GameState>> {
+    read(user_path, slot).map(|maybe_state| maybe_state.map(|state| {
+        let mut game_state = GameState::from(state);
+        game_state.reset_behaviour();
+        game_state
+    }))
+}
+
+/// Summary of the game saved in a slot, or `None` if the slot is empty
+pub fn slot_metadata<P: AsRef<Path>>(user_path: P, slot: usize) -> SaveResult<Option<SaveMetadata>> {
+    let opened = match open_slot(user_path, slot)? {
+        Some((_, opened)) => opened,
+        None => return Ok(None),
+    };
+
+    if opened.format_version < OLDEST_COMPATIBLE_FORMAT_VERSION {
+        return Err(SaveError::IncompatibleFormat);
+    }
+
+    // every envelope which can be read has metadata
+    let format_version = opened.format_version;
+    opened.metadata.map(Some).ok_or(SaveError::CorruptSave { format_version: format_version })
+}
+
+/// The slot containing the most recently saved game which can be read
+pub fn most_recent_slot<P: AsRef<Path>>(user_path: P) -> Option<usize> {
+    (0..NUM_SAVE_SLOTS).filter_map(|slot| {
+        slot_metadata(user_path.as_ref(), slot).ok()
+            .and_then(|m| m)
+            .map(|metadata| (slot, metadata.timestamp))
+    }).max_by_key(|&(_, timestamp)| timestamp).map(|(slot, _)| slot)
+}
+
+pub fn first_empty_slot<P: AsRef<Path>>(user_path: P) -> Option<usize> {
+    (0..NUM_SAVE_SLOTS).find(|&slot| {
+        let path = slot_path(user_path.as_ref(), slot);
+        !path.exists() && !backup_path(&path).exists()
+    })
+}
+
+/// Writes the game saved in a slot as json, for inspecting or diffing by hand
+pub fn export_json<P: AsRef<Path>, Q: AsRef<Path>>(user_path: P, slot: usize, output_path: Q) -> SaveResult<()> {
+    let state = read(user_path, slot)?.ok_or(SaveError::FailedToRead)?;
+
+    let export = SaveExport {
+        format_version: FORMAT_VERSION,
+        game_version: GAME_VERSION,
+        state: &state,
+    };
+
+    let json = serde_json::to_string_pretty(&export).map_err(|_| SaveError::FailedToExport)?;
+
+    game_file::write_string(output_path, json).map_err(|_| SaveError::FailedToWrite)
+}
+
+/// Raw contents of every save slot, including saves which can't be read,
+/// so they can be copied to another user directory with `restore_slot`
+pub fn slot_contents<P: AsRef<Path>>(user_path: P) -> Vec<(usize, Vec<u8>)> {
+    (0..NUM_SAVE_SLOTS).filter_map(|slot| {
+        let path = slot_path(user_path.as_ref(), slot);
+        let backup = backup_path(&path);
+
+        [path, backup].iter().filter_map(|path| {
+            let mut contents = Vec::new();
+            File::open(path).and_then(|mut f| f.read_to_end(&mut contents)).ok().map(|_| contents)
+        }).next().map(|contents| (slot, contents))
+    }).collect()
+}
+
+pub fn restore_slot<P: AsRef<Path>>(user_path: P, slot: usize, contents: &[u8]) -> io::Result<()> {
+    write_atomic(slot_path(user_path, slot), contents)
+}
+
+pub fn delete<P: AsRef<Path>>(user_path: P, slot: usize) -> bool {
+    let path = slot_path(user_path, slot);
+    let deleted_backup = fs::remove_file(backup_path(&path)).is_ok();
+    fs::remove_file(path).is_ok() || deleted_backup
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,131 @@
+use std::fs::File;
+use std::io::Write;
+
+use bincode::{self, SizeLimit};
+
+use game::*;
+use game::data::*;
+use game::tests::temp_dir;
+use super::*;
+
+/// Fingerprint of the files which determine how levels are encoded
+const SCHEMA_FINGERPRINT: u64 = 0xd7cc7efccdfe1b4c;
+
+/// 64-bit FNV-1a hash
+fn fingerprint(data: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for s in data {
+        for byte in s.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+fn metadata(delivery: usize) -> SaveMetadata {
+    SaveMetadata {
+        delivery: delivery,
+        bank: 100,
+        letters: 3,
+        timestamp: 1500000000,
+    }
+}
+
+fn encoded_game(delivery: usize) -> Vec<u8> {
+    encode(&SerializableGameState::from(GameState::new()), metadata(delivery))
+}
+
+fn encoded_envelope(format_version: u32, body: Vec<u8>) -> Vec<u8> {
+    let envelope = SaveEnvelope {
+        header: SaveHeader {
+            magic: MAGIC,
+            format_version: format_version,
+            game_version: GAME_VERSION.to_string(),
+        },
+        metadata: metadata(1),
+        body: body,
+    };
+
+    bincode::serialize(&envelope, SizeLimit::Infinite).unwrap()
+}
+
+/// If this fails, a change to ecs.toml or sh.toml has probably changed how
+/// games are encoded. If FORMAT_VERSION has been released, bump it and add
+/// a migration. Then update both values here.
+#[test]
+fn format_version_matches_schema() {
+    let schema = fingerprint(&[include_str!("../../../ecs.toml"), include_str!("../../../sh.toml")]);
+
+    assert_eq!((FORMAT_VERSION, schema), (1, SCHEMA_FINGERPRINT));
+}
+
+#[test]
+fn round_trip() {
+    let opened = open(encoded_game(4)).unwrap();
+
+    assert_eq!(opened.format_version, FORMAT_VERSION);
+    assert_eq!(opened.metadata, Some(metadata(4)));
+    assert!(decode(opened).is_ok());
+}
+
+#[test]
+fn round_trip_through_slot() {
+    let user_path = temp_dir("round_trip_through_slot");
+
+    restore_slot(&user_path, 1, &encoded_game(2)).unwrap();
+
+    assert_eq!(slot_metadata(&user_path, 1).unwrap(), Some(metadata(2)));
+    assert!(load(&user_path, 1).unwrap().is_some());
+    assert_eq!(most_recent_slot(&user_path), Some(1));
+    assert_eq!(first_empty_slot(&user_path), Some(0));
+}
+
+#[test]
+fn incompatible_versions_are_rejected() {
+    for format_version in LEGACY_FORMAT_VERSION..OLDEST_COMPATIBLE_FORMAT_VERSION {
+        match migrate(format_version, Vec::new()) {
+            Err(SaveError::IncompatibleFormat) => {}
+            _ => panic!("Version {} should be incompatible", format_version),
+        }
+    }
+}
+
+#[test]
+fn current_version_is_not_migrated() {
+    let body = vec![4, 5, 6];
+
+    assert_eq!(migrate(FORMAT_VERSION, body.clone()).unwrap(), body);
+}
+
+#[test]
+fn newer_format_is_rejected() {
+    match open(encoded_envelope(FORMAT_VERSION + 1, Vec::new())) {
+        Err(SaveError::NewerFormat { format_version, .. }) => assert_eq!(format_version, FORMAT_VERSION + 1),
+        _ => panic!("Expected NewerFormat"),
+    }
+}
+
+#[test]
+fn corrupt_body_is_reported() {
+    let opened = open(encoded_envelope(FORMAT_VERSION, vec![255; 16])).unwrap();
+
+    match decode(opened) {
+        Err(SaveError::CorruptSave { format_version }) => assert_eq!(format_version, FORMAT_VERSION),
+        _ => panic!("Expected CorruptSave"),
+    }
+}
+
+#[test]
+fn damaged_save_falls_back_to_backup() {
+    let user_path = temp_dir("damaged_save_falls_back_to_backup");
+
+    restore_slot(&user_path, 0, &encoded_game(1)).unwrap();
+    restore_slot(&user_path, 0, &encoded_game(2)).unwrap();
+
+    // simulate a save being cut short
+    let damaged = encoded_game(3);
+    File::create(slot_path(&user_path, 0)).unwrap().write_all(&damaged[..damaged.len() / 2]).unwrap();
+
+    assert_eq!(slot_metadata(&user_path, 0).unwrap(), Some(metadata(1)));
+}
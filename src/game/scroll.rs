@@ -36,7 +36,8 @@ pub fn display_message_scrolling<R: KnowledgeRenderer, I: InputSource>(renderer:
                         offset -= 1;
                     }
                 }
-                InputEvent::Escape => break,
+                InputEvent::Escape |
+                    InputEvent::Quit => break,
                 _ => {
                     if press_any_key {
                         break;
@@ -0,0 +1,280 @@
+use std::cmp;
+use std::ops::DerefMut;
+use ecs::*;
+use game::*;
+use game::data::*;
+use game::terrain::util::{self, TerrainEntity};
+use coord::Coord;
+use grid::*;
+use direction::*;
+
+const MAP_WIDTH_MAX: usize = 120;
+const MAP_WIDTH_MIN: usize = 60;
+const MAP_HEIGHT: usize = 30;
+const LANE_WIDTH: usize = 2;
+const LANE_MARKING_LENGTH: usize = 2;
+const LANE_MARKING_GAP: usize = 2;
+const MIN_LANES: usize = 2;
+const MAX_LANES: usize = 4;
+const STREET_WIDTH: usize = 2;
+const BLOCK_WIDTH_MIN: usize = 8;
+const BLOCK_WIDTH_MAX: usize = 16;
+const START_COORD: Coord = Coord { x: 0, y: MAP_HEIGHT as isize / 2 };
+
+const NUM_ALLEYS_MIN: usize = 4;
+const NUM_ALLEYS_MAX: usize = 10;
+const ALLEY_LENGTH_MIN: usize = 3;
+const ALLEY_LENGTH_MAX: usize = 10;
+const RUIN_PROBABILITY: f64 = 0.08;
+const RUBBLE_PROBABILITY: f64 = 0.5;
+
+/// Chance of a squad lying in wait at the mouth of a side street, per
+/// point of difficulty
+const AMBUSH_PROBABILITY_STEP: f64 = 0.05;
+const AMBUSH_PROBABILITY_MAX: f64 = 0.4;
+
+/// What occupies each cell of the city before any entities are placed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Ground {
+    Avenue,
+    LaneMarking,
+    Street,
+    Alley,
+    Building,
+    Ruin,
+}
+
+const RANDOM_ENTITY_TYPES: [TerrainEntity; 8] = [
+    TerrainEntity::Wreck,
+    TerrainEntity::Barrel,
+    TerrainEntity::Letter,
+    TerrainEntity::FuelCan,
+    TerrainEntity::Zombie,
+    TerrainEntity::Car,
+    TerrainEntity::Bike,
+    TerrainEntity::Convoy,
+];
+const RANDOM_ENTITY_TOTAL: usize = 1000;
+
+// dust doesn't blow between the buildings
+const WEATHER_TYPES: [Weather; 5] = [
+    Weather::Clear,
+    Weather::Clear,
+    Weather::Fog,
+    Weather::Night,
+    Weather::AcidRain,
+];
+
+/// Carves a straight line of alley from `start` in `direction`, stopping
+/// at the edge of the map or on reaching an avenue or street
+fn carve_alley(grid: &mut StaticGrid<Ground>, start: Coord, direction: Direction, length: usize) -> Coord {
+    let mut coord = start;
+    for _ in 0..length {
+        let next = coord + direction.vector();
+        match grid.get(next).cloned() {
+            Some(Ground::Building) | Some(Ground::Ruin) => {
+                *grid.get_checked_mut(next) = Ground::Alley;
+                coord = next;
+            }
+            _ => break,
+        }
+    }
+
+    coord
+}
+
+/// If `coord` is in the western column of a side street where it meets
+/// the avenue, the direction leading up the street away from the avenue
+fn street_mouth(grid: &StaticGrid<Ground>, coord: Coord, avenue_top: usize, avenue_bottom: usize) -> Option<Direction> {
+    if grid.get(coord + Coord::new(-1, 0)) == Some(&Ground::Street) {
+        return None;
+    }
+
+    if coord.y == avenue_top as isize - 1 {
+        Some(Direction::North)
+    } else if coord.y == avenue_bottom as isize {
+        Some(Direction::South)
+    } else {
+        None
+    }
+}
+
+pub fn city<S: TurnScheduleQueue>(ids: &EntityIdReserver,
+                                  rng: &GameRng,
+                                  prototypes: &PrototypeTable,
+                                  schedule: &mut S,
+                                  g: &mut EcsAction,
+                                  difficulty: usize) -> TerrainMetadata {
+
+    let map_width = rng.gen_usize() % (MAP_WIDTH_MAX - MAP_WIDTH_MIN) + MAP_WIDTH_MIN;
+    let mut ground: StaticGrid<Ground> = StaticGrid::new_copy(map_width, MAP_HEIGHT, Ground::Building);
+
+    let weather = if difficulty == 0 {
+        Weather::Clear
+    } else {
+        *rng.select_uniform(&WEATHER_TYPES)
+    };
+    let vehicle_percent = weather.vehicle_spawn_percent();
+    let ambush_probability = (difficulty as f64 * AMBUSH_PROBABILITY_STEP).min(AMBUSH_PROBABILITY_MAX) *
+        vehicle_percent as f64 / 100.0;
+
+    // the avenue runs the length of the map, centred on the start coord
+    let num_lanes = MIN_LANES + rng.gen_usize_below(MAX_LANES - MIN_LANES + 1);
+    let avenue_height = num_lanes * LANE_WIDTH;
+    let avenue_top = MAP_HEIGHT / 2 - avenue_height / 2;
+    let avenue_bottom = avenue_top + avenue_height;
+
+    for y in avenue_top..avenue_bottom {
+        for x in 0..map_width {
+            *ground.get_checked_mut(Coord::new(x as isize, y as isize)) = Ground::Avenue;
+        }
+    }
+
+    // dashed lines are painted along the top of each lane but the first
+    for lane in 1..num_lanes {
+        let y = avenue_top + lane * LANE_WIDTH;
+        for x in 0..map_width {
+            if x % (LANE_MARKING_LENGTH + LANE_MARKING_GAP) < LANE_MARKING_LENGTH {
+                *ground.get_checked_mut(Coord::new(x as isize, y as isize)) = Ground::LaneMarking;
+            }
+        }
+    }
+
+    // side streets branch off the avenue between blocks of buildings
+    let branches = [(true, false), (false, true), (true, true)];
+    let mut street_x = 0;
+    loop {
+        street_x += BLOCK_WIDTH_MIN + rng.gen_usize_below(BLOCK_WIDTH_MAX - BLOCK_WIDTH_MIN + 1);
+        if street_x + STREET_WIDTH >= map_width {
+            break;
+        }
+
+        let (north, south) = *rng.select_uniform(&branches);
+
+        for x in street_x..street_x + STREET_WIDTH {
+            if north {
+                for y in 0..avenue_top {
+                    *ground.get_checked_mut(Coord::new(x as isize, y as isize)) = Ground::Street;
+                }
+            }
+            if south {
+                for y in avenue_bottom..MAP_HEIGHT {
+                    *ground.get_checked_mut(Coord::new(x as isize, y as isize)) = Ground::Street;
+                }
+            }
+        }
+
+        street_x += STREET_WIDTH;
+    }
+
+    for cell in ground.iter_mut() {
+        if *cell == Ground::Building && rng.gen_f64() < RUIN_PROBABILITY {
+            *cell = Ground::Ruin;
+        }
+    }
+
+    // alleys lead from the avenue into a block, then turn along it
+    let num_alleys = NUM_ALLEYS_MIN + rng.gen_usize_below(NUM_ALLEYS_MAX - NUM_ALLEYS_MIN + 1);
+    for _ in 0..num_alleys {
+        let x = rng.gen_usize_below(map_width) as isize;
+        let (start, direction) = if rng.gen_f64() < 0.5 {
+            (Coord::new(x, avenue_top as isize), Direction::North)
+        } else {
+            (Coord::new(x, avenue_bottom as isize - 1), Direction::South)
+        };
+
+        let length = ALLEY_LENGTH_MIN + rng.gen_usize_below(ALLEY_LENGTH_MAX - ALLEY_LENGTH_MIN + 1);
+        let corner = carve_alley(&mut ground, start, direction, length);
+
+        let turn = *rng.select_uniform(&[Direction::East, Direction::West]);
+        let length = ALLEY_LENGTH_MIN + rng.gen_usize_below(ALLEY_LENGTH_MAX - ALLEY_LENGTH_MIN + 1);
+        carve_alley(&mut ground, corner, turn, length);
+    }
+
+    let random_entity_avenue_weights = [
+        5, /* Wreck */
+        10, /* Barrrel */
+        3, /* Letter */
+        2, /* FuelCan */
+        weather.zombie_spawn_weight(10 + cmp::min(difficulty * 5, 30)), /* Zombie */
+        weather.vehicle_spawn_weight(1 + cmp::min(difficulty / 3, 6)), /* Car */
+        weather.vehicle_spawn_weight(1 + cmp::min(difficulty / 3, 6)), /* Bike */
+        weather.vehicle_spawn_weight(cmp::min(difficulty / 5, 3)), /* Convoy */
+    ];
+
+    let random_entity_street_weights = [
+        5, /* Wreck */
+        10, /* Barrrel */
+        8, /* Letter */
+        2, /* FuelCan */
+        weather.zombie_spawn_weight(20 + cmp::min(difficulty * 10, 60)), /* Zombie */
+        0, /* Car */
+        0, /* Bike */
+        0, /* Convoy */
+    ];
+
+    let mut grid: StaticGrid<Vec<TerrainEntity>> = StaticGrid::new_default(map_width, MAP_HEIGHT);
+
+    for (coord, ground_cell, cell_mut) in izip!(ground.coord_iter(), ground.iter(), grid.iter_mut()) {
+        let weights = match *ground_cell {
+            Ground::Avenue => {
+                cell_mut.push(TerrainEntity::Road);
+                Some(&random_entity_avenue_weights)
+            }
+            Ground::LaneMarking => {
+                cell_mut.push(TerrainEntity::RoadMarking);
+                Some(&random_entity_avenue_weights)
+            }
+            Ground::Street => {
+                cell_mut.push(TerrainEntity::Road);
+                match street_mouth(&ground, coord, avenue_top, avenue_bottom) {
+                    Some(direction) if rng.gen_f64() < ambush_probability => {
+                        // vehicles hide in the side street, out of sight of the avenue
+                        cell_mut.push(TerrainEntity::Ambush(direction));
+                        None
+                    }
+                    _ => Some(&random_entity_street_weights),
+                }
+            }
+            Ground::Alley => {
+                cell_mut.push(TerrainEntity::Dirt);
+                Some(&random_entity_street_weights)
+            }
+            Ground::Ruin => {
+                cell_mut.push(TerrainEntity::Dirt);
+                if rng.gen_f64() < RUBBLE_PROBABILITY {
+                    cell_mut.push(TerrainEntity::Wreck);
+                }
+                None
+            }
+            Ground::Building => {
+                cell_mut.push(TerrainEntity::Building);
+                None
+            }
+        };
+
+        if let Some(weights) = weights {
+            if let Some(entity_type) = util::choose_random_entity(&RANDOM_ENTITY_TYPES,
+                                                                  weights,
+                                                                  RANDOM_ENTITY_TOTAL,
+                                                                  rng.inner_mut().deref_mut()) {
+                cell_mut.push(entity_type);
+            }
+
+            if coord.x == map_width as isize - 1 {
+                cell_mut.push(TerrainEntity::Goal);
+            }
+        }
+    }
+
+    util::spawn_terrain(&grid, ids, schedule, g, rng, prototypes);
+    util::add_management_entities(ids, schedule, g);
+
+    TerrainMetadata {
+        width: map_width,
+        height: MAP_HEIGHT,
+        start_coord: START_COORD,
+        connection_report: LevelConnectionReport::new(),
+        weather: weather,
+    }
+}
@@ -8,18 +8,20 @@ const START_COORD: Coord = Coord { x: 0, y: 14 };
 
 pub fn demo_a<S: TurnScheduleQueue>(ids: &EntityIdReserver,
                                   rng: &GameRng,
+                                  prototypes: &PrototypeTable,
                                   schedule: &mut S,
                                   g: &mut EcsAction) -> TerrainMetadata {
 
     let level_switch = LevelSwitch::LeaveLevel;
 
-    let (width, height) = util::terrain_from_strings(&level_str(), Some(level_switch), ids, schedule, g, rng);
+    let (width, height) = util::terrain_from_strings(&level_str(), Some(level_switch), ids, schedule, g, rng, prototypes);
 
     TerrainMetadata {
         width: width,
         height: height,
         start_coord: START_COORD,
         connection_report: LevelConnectionReport::new(),
+        weather: Weather::Clear,
     }
 }
 
@@ -1,5 +1,7 @@
 mod demo_a;
 mod road;
+mod city;
 
 pub use self::demo_a::*;
 pub use self::road::*;
+pub use self::city::*;
@@ -1,15 +1,13 @@
 use std::cmp;
 use std::ops::DerefMut;
-use rand::Rng;
 use ecs::*;
 use game::*;
 use game::data::*;
-use game::terrain::util;
+use game::terrain::util::{self, TerrainEntity};
 use coord::Coord;
 use grid::*;
 use perlin::*;
 use math::*;
-use direction::*;
 
 const MAP_WIDTH_MAX: usize = 120;
 const MAP_WIDTH_MIN: usize = 60;
@@ -24,51 +22,45 @@ const PERLIN_ZOOM_F: f64 = PERLIN_ZOOM as f64;
 const PERLIN_MIN: f64 = 0.2;
 const PERLIN_MAX: f64 = 0.5;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum EntityType {
-    Dirt,
-    Road,
-    Acid,
-    Goal,
-    Wreck,
-    Barrel,
-    Letter,
-    Zombie,
-    Car,
-    Bike,
-}
-
-const RANDOM_ENTITY_TYPES: [EntityType; 6] = [
-    EntityType::Wreck,
-    EntityType::Barrel,
-    EntityType::Letter,
-    EntityType::Zombie,
-    EntityType::Car,
-    EntityType::Bike,
+const RANDOM_ENTITY_TYPES: [TerrainEntity; 8] = [
+    TerrainEntity::Wreck,
+    TerrainEntity::Barrel,
+    TerrainEntity::Letter,
+    TerrainEntity::FuelCan,
+    TerrainEntity::Zombie,
+    TerrainEntity::Car,
+    TerrainEntity::Bike,
+    TerrainEntity::Convoy,
 ];
 const RANDOM_ENTITY_TOTAL: usize = 1000;
 
-fn choose_random_entity<R: Rng>(types: &[EntityType], weights: &[usize], total: usize, rng: &mut R) -> Option<EntityType> {
-    let mut roll = rng.gen::<usize>() % total;
-    for (weight, entity_type) in izip!(weights.iter(), types.iter()) {
-        if roll < *weight {
-            return Some(*entity_type);
-        }
-        roll -= *weight;
-    }
+const WEATHER_TYPES: [Weather; 6] = [
+    Weather::Clear,
+    Weather::Clear,
+    Weather::Fog,
+    Weather::Night,
+    Weather::DustStorm,
+    Weather::AcidRain,
+];
 
-    None
-}
 
 pub fn road<S: TurnScheduleQueue>(ids: &EntityIdReserver,
                                   rng: &GameRng,
+                                  prototypes: &PrototypeTable,
                                   schedule: &mut S,
                                   g: &mut EcsAction,
                                   difficulty: usize) -> TerrainMetadata {
 
 
     let map_width = rng.gen_usize() % (MAP_WIDTH_MAX - MAP_WIDTH_MIN) + MAP_WIDTH_MIN;
-    let mut grid: StaticGrid<Vec<EntityType>> = StaticGrid::new_default(map_width, MAP_HEIGHT);
+
+    // the first delivery is always made in clear weather
+    let weather = if difficulty == 0 {
+        Weather::Clear
+    } else {
+        *rng.select_uniform(&WEATHER_TYPES)
+    };
+    let mut grid: StaticGrid<Vec<TerrainEntity>> = StaticGrid::new_default(map_width, MAP_HEIGHT);
 
     let perlin = PerlinGrid::new(map_width / PERLIN_ZOOM, MAP_HEIGHT / PERLIN_ZOOM,
                                  PerlinWrapType::Repeat, rng.inner_mut().deref_mut());
@@ -77,18 +69,22 @@ pub fn road<S: TurnScheduleQueue>(ids: &EntityIdReserver,
         5, /* Wreck */
         10, /* Barrrel */
         3, /* Letter */
-        20 + cmp::min(difficulty * 10, 60), /* Zombie */
-        cmp::min(difficulty / 4, 4), /* Car */
-        1 + cmp::min(difficulty / 2, 8), /* Bike */
+        2, /* FuelCan */
+        weather.zombie_spawn_weight(20 + cmp::min(difficulty * 10, 60)), /* Zombie */
+        weather.vehicle_spawn_weight(cmp::min(difficulty / 4, 4)), /* Car */
+        weather.vehicle_spawn_weight(1 + cmp::min(difficulty / 2, 8)), /* Bike */
+        0, /* Convoy */
     ];
 
     let random_entity_road_weights = [
         5, /* Wreck */
         10, /* Barrrel */
         3, /* Letter */
-        10 + cmp::min(difficulty * 5, 30), /* Zombie */
-        1 + cmp::min(difficulty / 3, 6), /* Car */
-        cmp::min(difficulty / 3, 6), /* Bike */
+        2, /* FuelCan */
+        weather.zombie_spawn_weight(10 + cmp::min(difficulty * 5, 30)), /* Zombie */
+        weather.vehicle_spawn_weight(1 + cmp::min(difficulty / 3, 6)), /* Car */
+        weather.vehicle_spawn_weight(cmp::min(difficulty / 3, 6)), /* Bike */
+        weather.vehicle_spawn_weight(cmp::min(difficulty / 5, 3)), /* Convoy */
     ];
 
     for (coord, cell_mut) in izip!(grid.coord_iter(), grid.iter_mut()) {
@@ -102,94 +98,31 @@ pub fn road<S: TurnScheduleQueue>(ids: &EntityIdReserver,
         }
 
         if acid {
-            cell_mut.push(EntityType::Acid);
+            cell_mut.push(TerrainEntity::Acid);
         } else if coord.y > ROAD_BOTTOM as isize && coord.y <= ROAD_TOP as isize {
-            cell_mut.push(EntityType::Road);
-            if let Some(entity_type) = choose_random_entity(&RANDOM_ENTITY_TYPES,
-                                                            &random_entity_road_weights,
-                                                            RANDOM_ENTITY_TOTAL,
-                                                            rng.inner_mut().deref_mut()) {
+            cell_mut.push(TerrainEntity::Road);
+            if let Some(entity_type) = util::choose_random_entity(&RANDOM_ENTITY_TYPES,
+                                                                  &random_entity_road_weights,
+                                                                  RANDOM_ENTITY_TOTAL,
+                                                                  rng.inner_mut().deref_mut()) {
                 cell_mut.push(entity_type);
             }
         } else {
-            cell_mut.push(EntityType::Dirt);
-            if let Some(entity_type) = choose_random_entity(&RANDOM_ENTITY_TYPES,
-                                                            &random_entity_dirt_weights,
-                                                            RANDOM_ENTITY_TOTAL,
-                                                            rng.inner_mut().deref_mut()) {
+            cell_mut.push(TerrainEntity::Dirt);
+            if let Some(entity_type) = util::choose_random_entity(&RANDOM_ENTITY_TYPES,
+                                                                  &random_entity_dirt_weights,
+                                                                  RANDOM_ENTITY_TOTAL,
+                                                                  rng.inner_mut().deref_mut()) {
                 cell_mut.push(entity_type);
             }
         }
 
         if coord.x == map_width as isize - 1 {
-            cell_mut.push(EntityType::Goal);
-        }
-    }
-
-    for (coord, cell) in izip!(grid.coord_iter(), grid.iter()) {
-        for entity_type in cell.iter() {
-            match *entity_type {
-                EntityType::Dirt => {
-                    prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
-                }
-                EntityType::Road => {
-                    prototypes::road(g.entity_mut(ids.new_id()), coord, rng);
-                }
-                EntityType::Acid => {
-                    prototypes::acid(g.entity_mut(ids.new_id()), coord, rng);
-                }
-                EntityType::Goal => {
-                    prototypes::goal(g.entity_mut(ids.new_id()), coord, LevelSwitch::LeaveLevel);
-                }
-                EntityType::Wreck => {
-                    prototypes::wreck(g.entity_mut(ids.new_id()), coord, rng);
-                }
-                EntityType::Barrel => {
-                    prototypes::barrel(g.entity_mut(ids.new_id()), coord);
-                }
-                EntityType::Letter => {
-                    prototypes::letter(g.entity_mut(ids.new_id()), coord);
-                }
-                EntityType::Zombie => {
-                    prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
-                    let id = ids.new_id();
-                    prototypes::zombie(g.entity_mut(id), coord);
-                    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
-                    let ticket = schedule.schedule_turn(id, turn_offset);
-                    g.insert_schedule_ticket(id, ticket);
-                }
-                EntityType::Car => {
-                    prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
-                    let id = ids.new_id();
-                    prototypes::car(g.entity_mut(id), coord);
-                    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
-                    let ticket = schedule.schedule_turn(id, turn_offset);
-                    g.insert_schedule_ticket(id, ticket);
-
-                    let gun_id = ids.new_id();
-                    prototypes::shotgun(g.entity_mut(gun_id));
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::North, gun_id);
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::South, gun_id);
-                }
-                EntityType::Bike => {
-                    prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
-                    let id = ids.new_id();
-                    prototypes::bike(g.entity_mut(id), coord);
-                    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
-                    let ticket = schedule.schedule_turn(id, turn_offset);
-                    g.insert_schedule_ticket(id, ticket);
-
-                    let gun_id = ids.new_id();
-                    prototypes::pistol(g.entity_mut(gun_id));
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::North, gun_id);
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::South, gun_id);
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::East, gun_id);
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::West, gun_id);
-                }
-            }
+            cell_mut.push(TerrainEntity::Goal);
         }
     }
 
+    util::spawn_terrain(&grid, ids, schedule, g, rng, prototypes);
     util::add_management_entities(ids, schedule, g);
 
     TerrainMetadata {
@@ -197,5 +130,6 @@ pub fn road<S: TurnScheduleQueue>(ids: &EntityIdReserver,
         height: MAP_HEIGHT,
         start_coord: START_COORD,
         connection_report: LevelConnectionReport::new(),
+        weather: weather,
     }
 }
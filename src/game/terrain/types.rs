@@ -1,5 +1,6 @@
 use ecs::*;
 use game::*;
+use game::data::*;
 use coord::Coord;
 
 pub struct TerrainMetadata {
@@ -7,6 +8,7 @@ pub struct TerrainMetadata {
     pub height: usize,
     pub start_coord: Coord,
     pub connection_report: LevelConnectionReport,
+    pub weather: Weather,
 }
 
 pub struct ParentLevelCtx<'a> {
@@ -19,19 +21,22 @@ pub struct ParentLevelCtx<'a> {
 pub enum TerrainType {
     DemoA,
     Road,
+    City,
 }
 
 impl TerrainType {
     pub fn generate<S: TurnScheduleQueue>(self,
                                           ids: &EntityIdReserver,
                                           rng: &GameRng,
+                                          prototypes: &PrototypeTable,
                                           schedule: &mut S,
                                           action: &mut EcsAction,
                                           _parent: Option<ParentLevelCtx>,
                                           difficulty: usize) -> TerrainMetadata {
         match self {
-            TerrainType::DemoA => generators::demo_a(ids, rng, schedule, action),
-            TerrainType::Road => generators::road(ids, rng, schedule, action, difficulty),
+            TerrainType::DemoA => generators::demo_a(ids, rng, prototypes, schedule, action),
+            TerrainType::Road => generators::road(ids, rng, prototypes, schedule, action, difficulty),
+            TerrainType::City => generators::city(ids, rng, prototypes, schedule, action, difficulty),
         }
     }
 }
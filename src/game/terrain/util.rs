@@ -1,15 +1,47 @@
+use rand::Rng;
 use ecs::*;
 use game::*;
 use game::data::*;
 use coord::Coord;
 use direction::Direction;
+use grid::*;
+
+/// Entities placed by generators which lay out a level as a grid of cells
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TerrainEntity {
+    Dirt,
+    Road,
+    RoadMarking,
+    Acid,
+    Building,
+    Goal,
+    Wreck,
+    Barrel,
+    Letter,
+    FuelCan,
+    Zombie,
+    Car,
+    Bike,
+    Convoy,
+    /// A squad parked off the road, lying in wait for the van. The
+    /// direction points away from the road.
+    Ambush(Direction),
+}
+
+/// Where the bikes in a convoy start relative to its car, and the role
+/// each one plays
+const CONVOY_BIKES: [(Coord, SquadRole); 2] = [
+    (Coord { x: -2, y: -1 }, SquadRole::Blocker),
+    (Coord { x: -2, y: 1 }, SquadRole::Flanker),
+];
 
 pub fn terrain_from_strings<S: TurnScheduleQueue>(strings: &[&str],
                                                   level_switch: Option<LevelSwitch>,
                                                   ids: &EntityIdReserver,
                                                   schedule: &mut S,
                                                   g: &mut EcsAction,
-                                                  rng: &GameRng) -> (usize, usize) {
+                                                  rng: &GameRng,
+                                                  prototypes: &PrototypeTable) -> (usize, usize) {
     let width = strings[0].len();
     let height = strings.len();
 
@@ -23,11 +55,11 @@ pub fn terrain_from_strings<S: TurnScheduleQueue>(strings: &[&str],
                     prototypes::road(g.entity_mut(ids.new_id()), coord, rng);
                 }
                 '#' => {
-                    prototypes::barrel(g.entity_mut(ids.new_id()), coord);
+                    prototypes::barrel(g.entity_mut(ids.new_id()), coord, prototypes);
                     prototypes::road(g.entity_mut(ids.new_id()), coord, rng);
                 }
                 '&' => {
-                    prototypes::letter(g.entity_mut(ids.new_id()), coord);
+                    prototypes::letter(g.entity_mut(ids.new_id()), coord, prototypes);
                     prototypes::road(g.entity_mut(ids.new_id()), coord, rng);
                 }
                 ',' => {
@@ -35,56 +67,26 @@ pub fn terrain_from_strings<S: TurnScheduleQueue>(strings: &[&str],
                 }
                 'z' => {
                     prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
-                    let id = ids.new_id();
-                    prototypes::zombie(g.entity_mut(id), coord);
-                    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
-                    let ticket = schedule.schedule_turn(id, turn_offset);
-                    g.insert_schedule_ticket(id, ticket);
+                    add_zombie(coord, ids, schedule, g, prototypes);
                 }
                 'c' => {
                     prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
-                    let id = ids.new_id();
-                    prototypes::car(g.entity_mut(id), coord);
-                    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
-                    let ticket = schedule.schedule_turn(id, turn_offset);
-                    g.insert_schedule_ticket(id, ticket);
-
-
-                    let gun_id = ids.new_id();
-                    prototypes::shotgun(g.entity_mut(gun_id));
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::North, gun_id);
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::South, gun_id);
+                    add_car(coord, ids, schedule, g, prototypes);
                 }
                 'b' => {
                     prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
-                    let id = ids.new_id();
-                    prototypes::bike(g.entity_mut(id), coord);
-                    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
-                    let ticket = schedule.schedule_turn(id, turn_offset);
-                    g.insert_schedule_ticket(id, ticket);
-
-
-                    let gun_id = ids.new_id();
-                    prototypes::pistol(g.entity_mut(gun_id));
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::North, gun_id);
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::South, gun_id);
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::East, gun_id);
-                    g.weapon_slots_mut(id).unwrap().insert(Direction::West, gun_id);
+                    add_bike(coord, ids, schedule, g, prototypes);
                 }
                 'Z' => {
                     prototypes::road(g.entity_mut(ids.new_id()), coord, rng);
-                    let id = ids.new_id();
-                    prototypes::zombie(g.entity_mut(id), coord);
-                    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
-                    let ticket = schedule.schedule_turn(id, turn_offset);
-                    g.insert_schedule_ticket(id, ticket);
+                    add_zombie(coord, ids, schedule, g, prototypes);
                 }
                 '%' => {
-                    prototypes::wreck(g.entity_mut(ids.new_id()), coord, rng);
+                    prototypes::wreck(g.entity_mut(ids.new_id()), coord, rng, prototypes);
                     prototypes::road(g.entity_mut(ids.new_id()), coord, rng);
                 }
                 '$' => {
-                    prototypes::wreck(g.entity_mut(ids.new_id()), coord, rng);
+                    prototypes::wreck(g.entity_mut(ids.new_id()), coord, rng, prototypes);
                     prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
                 }
                 '~' => {
@@ -107,6 +109,156 @@ pub fn terrain_from_strings<S: TurnScheduleQueue>(strings: &[&str],
     (width, height)
 }
 
+/// Creates the entities in each cell of `grid`
+pub fn spawn_terrain<S: TurnScheduleQueue>(grid: &StaticGrid<Vec<TerrainEntity>>,
+                                           ids: &EntityIdReserver,
+                                           schedule: &mut S,
+                                           g: &mut EcsAction,
+                                           rng: &GameRng,
+                                           prototypes: &PrototypeTable) {
+
+    // cells already taken by bikes from other squads
+    let mut squad_bike_coords = Vec::new();
+
+    for (coord, cell) in izip!(grid.coord_iter(), grid.iter()) {
+        for entity in cell.iter() {
+            match *entity {
+                TerrainEntity::Dirt => {
+                    prototypes::dirt(g.entity_mut(ids.new_id()), coord, rng);
+                }
+                TerrainEntity::Road => {
+                    prototypes::road(g.entity_mut(ids.new_id()), coord, rng);
+                }
+                TerrainEntity::RoadMarking => {
+                    prototypes::road_marking(g.entity_mut(ids.new_id()), coord);
+                }
+                TerrainEntity::Acid => {
+                    prototypes::acid(g.entity_mut(ids.new_id()), coord, rng);
+                }
+                TerrainEntity::Building => {
+                    prototypes::building(g.entity_mut(ids.new_id()), coord, prototypes);
+                }
+                TerrainEntity::Goal => {
+                    prototypes::goal(g.entity_mut(ids.new_id()), coord, LevelSwitch::LeaveLevel);
+                }
+                TerrainEntity::Wreck => {
+                    prototypes::wreck(g.entity_mut(ids.new_id()), coord, rng, prototypes);
+                }
+                TerrainEntity::Barrel => {
+                    prototypes::barrel(g.entity_mut(ids.new_id()), coord, prototypes);
+                }
+                TerrainEntity::Letter => {
+                    prototypes::letter(g.entity_mut(ids.new_id()), coord, prototypes);
+                }
+                TerrainEntity::FuelCan => {
+                    prototypes::fuel_can(g.entity_mut(ids.new_id()), coord, prototypes);
+                }
+                TerrainEntity::Zombie => {
+                    add_zombie(coord, ids, schedule, g, prototypes);
+                }
+                TerrainEntity::Car => {
+                    add_car(coord, ids, schedule, g, prototypes);
+                }
+                TerrainEntity::Bike => {
+                    add_bike(coord, ids, schedule, g, prototypes);
+                }
+                TerrainEntity::Convoy => {
+                    let bikes = squad_bikes(coord, &CONVOY_BIKES, grid, &mut squad_bike_coords);
+                    add_squad(coord, &bikes, ids, schedule, g, prototypes);
+                }
+                TerrainEntity::Ambush(direction) => {
+                    // the blocker waits beside the car, ready to pull out in
+                    // front of the van, and the flanker waits behind the car
+                    let offsets = [
+                        (Coord::new(1, 0), SquadRole::Blocker),
+                        (direction.vector(), SquadRole::Flanker),
+                    ];
+
+                    let bikes = squad_bikes(coord, &offsets, grid, &mut squad_bike_coords);
+                    let members = add_squad(coord, &bikes, ids, schedule, g, prototypes);
+                    for id in members {
+                        g.insert_current_speed(id, 0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Where to place the bikes of a squad whose car is at `coord`, given
+/// their offsets from the car. Offsets which aren't clear ground, or are
+/// already taken by another squad, are skipped.
+fn squad_bikes(coord: Coord,
+               offsets: &[(Coord, SquadRole)],
+               grid: &StaticGrid<Vec<TerrainEntity>>,
+               taken: &mut Vec<Coord>) -> Vec<(Coord, SquadRole)> {
+
+    let mut bikes = Vec::new();
+
+    for &(offset, role) in offsets.iter() {
+        let bike_coord = coord + offset;
+        let clear = grid.get(bike_coord).map(|cell| {
+            cell.iter().all(|e| is_ground(*e))
+        }).unwrap_or(false);
+
+        if clear && !taken.contains(&bike_coord) {
+            taken.push(bike_coord);
+            bikes.push((bike_coord, role));
+        }
+    }
+
+    bikes
+}
+
+/// Adds a car at `coord` leading a squad of bikes, returning the ids of
+/// every member of the squad
+fn add_squad<S: TurnScheduleQueue>(coord: Coord,
+                                   bikes: &[(Coord, SquadRole)],
+                                   ids: &EntityIdReserver,
+                                   schedule: &mut S,
+                                   g: &mut EcsAction,
+                                   prototypes: &PrototypeTable) -> Vec<EntityId> {
+
+    let car_id = add_car(coord, ids, schedule, g, prototypes);
+    g.insert_squad_id(car_id, car_id);
+    g.insert_squad_role(car_id, SquadRole::Shooter);
+
+    let mut members = vec![car_id];
+
+    for &(bike_coord, role) in bikes.iter() {
+        let bike_id = add_bike(bike_coord, ids, schedule, g, prototypes);
+        g.insert_squad_id(bike_id, car_id);
+        g.insert_squad_role(bike_id, role);
+        members.push(bike_id);
+    }
+
+    members
+}
+
+/// True for entities which can be driven over
+fn is_ground(entity: TerrainEntity) -> bool {
+    match entity {
+        TerrainEntity::Dirt |
+        TerrainEntity::Road |
+        TerrainEntity::RoadMarking => true,
+        _ => false,
+    }
+}
+
+/// Chooses one of `types` with probability proportional to its weight out
+/// of `total`, or nothing if the roll exceeds the sum of the weights
+pub fn choose_random_entity<T: Copy, R: Rng>(types: &[T], weights: &[usize], total: usize, rng: &mut R) -> Option<T> {
+    let mut roll = rng.gen::<usize>() % total;
+    for (weight, entity_type) in izip!(weights.iter(), types.iter()) {
+        if roll < *weight {
+            return Some(*entity_type);
+        }
+        roll -= *weight;
+    }
+
+    None
+}
+
 pub fn add_management_entities<S: TurnScheduleQueue>(ids: &EntityIdReserver,
                                                      schedule: &mut S,
                                                      g: &mut EcsAction) {
@@ -123,3 +275,56 @@ pub fn add_management_entities<S: TurnScheduleQueue>(ids: &EntityIdReserver,
     g.insert_schedule_ticket(physics_id, ticket);
 }
 
+pub fn add_zombie<S: TurnScheduleQueue>(coord: Coord,
+                                        ids: &EntityIdReserver,
+                                        schedule: &mut S,
+                                        g: &mut EcsAction,
+                                        prototypes: &PrototypeTable) -> EntityId {
+    let id = ids.new_id();
+    prototypes::zombie(g.entity_mut(id), coord, prototypes);
+    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
+    let ticket = schedule.schedule_turn(id, turn_offset);
+    g.insert_schedule_ticket(id, ticket);
+
+    id
+}
+
+pub fn add_car<S: TurnScheduleQueue>(coord: Coord,
+                                     ids: &EntityIdReserver,
+                                     schedule: &mut S,
+                                     g: &mut EcsAction,
+                                     prototypes: &PrototypeTable) -> EntityId {
+    let id = ids.new_id();
+    prototypes::car(g.entity_mut(id), coord, prototypes);
+    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
+    let ticket = schedule.schedule_turn(id, turn_offset);
+    g.insert_schedule_ticket(id, ticket);
+
+    let gun_id = ids.new_id();
+    prototypes::shotgun(g.entity_mut(gun_id), prototypes);
+    g.weapon_slots_mut(id).unwrap().insert(Direction::North, gun_id);
+    g.weapon_slots_mut(id).unwrap().insert(Direction::South, gun_id);
+
+    id
+}
+
+pub fn add_bike<S: TurnScheduleQueue>(coord: Coord,
+                                      ids: &EntityIdReserver,
+                                      schedule: &mut S,
+                                      g: &mut EcsAction,
+                                      prototypes: &PrototypeTable) -> EntityId {
+    let id = ids.new_id();
+    prototypes::bike(g.entity_mut(id), coord, prototypes);
+    let turn_offset = g.turn_offset(id).expect("Expected component turn_offset");
+    let ticket = schedule.schedule_turn(id, turn_offset);
+    g.insert_schedule_ticket(id, ticket);
+
+    let gun_id = ids.new_id();
+    prototypes::pistol(g.entity_mut(gun_id), prototypes);
+    g.weapon_slots_mut(id).unwrap().insert(Direction::North, gun_id);
+    g.weapon_slots_mut(id).unwrap().insert(Direction::South, gun_id);
+    g.weapon_slots_mut(id).unwrap().insert(Direction::East, gun_id);
+    g.weapon_slots_mut(id).unwrap().insert(Direction::West, gun_id);
+
+    id
+}
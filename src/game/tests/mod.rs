@@ -0,0 +1,27 @@
+use std::env;
+use std::fs;
+use std::path;
+
+use game::*;
+
+mod recording;
+
+/// Empty directory unique to a test
+pub fn temp_dir(name: &str) -> path::PathBuf {
+    let path = env::temp_dir().join(format!("apocalypse-post-test-{}", name));
+    if path.exists() {
+        fs::remove_dir_all(&path).expect("Failed to remove old test directory");
+    }
+    fs::create_dir_all(&path).expect("Failed to create test directory");
+    path
+}
+
+/// Path to a file in the resources directory
+pub fn resource_path(name: &str) -> path::PathBuf {
+    path::Path::new(env!("CARGO_MANIFEST_DIR")).join("resources").join(name)
+}
+
+/// Prototype table loaded from the game's resources
+pub fn prototypes() -> PrototypeTable {
+    PrototypeTable::from_file(resource_path(PROTOTYPE_FILE)).expect("Failed to load prototypes")
+}
@@ -0,0 +1,60 @@
+use std::fs::{self, File};
+use std::io::Write;
+
+use game::*;
+use super::temp_dir;
+
+struct NullInput;
+
+impl InputSource for NullInput {
+    fn next_input(&mut self) -> Option<InputEvent> {
+        Some(InputEvent::Space)
+    }
+}
+
+#[test]
+fn recording_includes_saves_and_controls() {
+    let dir = temp_dir("recording");
+    let user_path = dir.join("user");
+    let replay_path = dir.join("replay");
+    let recording_path = dir.join("recording");
+    fs::create_dir(&user_path).unwrap();
+    fs::create_dir(&replay_path).unwrap();
+
+    let mut control_map = ControlMap::default();
+    control_map.remove(InputEvent::Space);
+    control_map.insert(InputEvent::Char('z'), Control::Wait);
+    control_file::to_file(user_path.join(user_files::CONTROL), &control_map);
+    save_file::restore_slot(&user_path, 2, &[1, 2, 3, 255]).unwrap();
+
+    {
+        let mut input = RecordingInputSource::new(NullInput, 42, &user_path, &recording_path).unwrap();
+        input.next_input();
+        input.next_input();
+    }
+
+    let recording = InputRecording::load(&recording_path).unwrap();
+
+    assert_eq!(recording.rng_seed, 42);
+    assert_eq!(recording.inputs, vec![InputEvent::Space, InputEvent::Space]);
+    assert_eq!(recording.saves, vec![(2, vec![1, 2, 3, 255])]);
+    assert_eq!(recording.control_map.get(InputEvent::Char('z')), Some(Control::Wait));
+
+    recording.install(&replay_path).unwrap();
+
+    assert_eq!(save_file::slot_contents(&replay_path), vec![(2, vec![1, 2, 3, 255])]);
+}
+
+#[test]
+fn recording_without_header_uses_defaults() {
+    let dir = temp_dir("recording_without_header");
+    let recording_path = dir.join("recording");
+
+    File::create(&recording_path).unwrap().write_all(b"seed 7\nUp\nQuit\n").unwrap();
+
+    let recording = InputRecording::load(&recording_path).unwrap();
+
+    assert_eq!(recording.rng_seed, 7);
+    assert_eq!(recording.inputs, vec![InputEvent::Up, InputEvent::Quit]);
+    assert!(recording.saves.is_empty());
+}
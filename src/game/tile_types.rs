@@ -19,17 +19,26 @@ pub enum TileType {
     Letter,
     Barrel,
     Explosion,
+    Building,
+    FuelCan,
+    Mine,
+    Flame,
+    Harpoon,
+    Rocket,
+    RoadMarking,
 }
 
 impl TileType {
     pub fn opaque_bg(self) -> bool {
         match self {
             TileType::Road0 |
+            TileType::RoadMarking |
             TileType::Dirt0 |
             TileType::Dirt1 |
             TileType::Acid0 |
             TileType::Acid1 |
-            TileType::Explosion => true,
+            TileType::Explosion |
+            TileType::Building => true,
             _ => false,
         }
     }
@@ -57,6 +66,13 @@ impl TileType {
             "Letter" => TileType::Letter,
             "Barrel" => TileType::Barrel,
             "Explosion" => TileType::Explosion,
+            "Building" => TileType::Building,
+            "FuelCan" => TileType::FuelCan,
+            "Mine" => TileType::Mine,
+            "Flame" => TileType::Flame,
+            "Harpoon" => TileType::Harpoon,
+            "Rocket" => TileType::Rocket,
+            "RoadMarking" => TileType::RoadMarking,
             _ => return None,
         };
 
@@ -65,7 +65,10 @@ pub struct TurnEnv<'game, 'level: 'game, Renderer: 'game + KnowledgeRenderer> {
     pub renderer: &'game RefCell<Renderer>,
     pub ecs: &'level mut EcsCtx,
     pub spatial_hash: &'level mut SpatialHashTable,
+    pub weather: Weather,
     pub behaviour_ctx: &'game BehaviourCtx<Renderer>,
+    pub rules: &'game RuleRegistry,
+    pub rule_trace: bool,
     pub rule_reactions: &'game mut Vec<Reaction>,
     pub ecs_action: &'game mut EcsAction,
     pub action_schedule: &'game mut Schedule<ActionArgs>,
@@ -175,30 +178,33 @@ impl<'game, 'level, Renderer: KnowledgeRenderer> TurnEnv<'game, 'level, Renderer
             spatial_hash: self.spatial_hash,
         };
 
-        if self.ecs_action.contains_no_commit() {
-            rules::projectile_collision(rule_env, self.ecs_action, self.rule_reactions)?;
+        if self.rule_trace {
+            self.rules.check_traced(rule_env, self.ecs_action, self.rule_reactions)
         } else {
-            rules::then(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::bounds(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::letter(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::acid(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::run_over(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::bump_attack(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::projectile_collision_trigger(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::collision(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::death(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::enemy_collision(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::pc_collision(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::level_switch(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::level_switch_auto(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::physics(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::driving(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::realtime_velocity_start(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::realtime_velocity(rule_env, self.ecs_action, self.rule_reactions)?;
-            rules::explosion_destroy(rule_env, self.ecs_action, self.rule_reactions)?;
+            self.rules.check(rule_env, self.ecs_action, self.rule_reactions)
         }
+    }
+
+    fn trace_commit(&self) {
+        debug_println!("  committed");
 
-        RULE_ACCEPT
+        trace_components(self.ecs, self.ecs_action);
+
+        if let Some(alternative_turn_time) = self.ecs_action.alternative_turn_time() {
+            debug_println!("    alternative_turn_time {}", alternative_turn_time);
+        }
+        if let Some(action_time_ms) = self.ecs_action.action_time_ms() {
+            debug_println!("    action_time_ms {}", action_time_ms);
+        }
+        if let Some(level_switch_action) = self.ecs_action.level_switch_action() {
+            debug_println!("    level_switch_action {:?}", level_switch_action);
+        }
+        if self.ecs_action.contains_player_died() {
+            debug_println!("    player_died");
+        }
+        if self.ecs_action.contains_player_stranded() {
+            debug_println!("    player_stranded");
+        }
     }
 
     fn commit(&mut self) {
@@ -216,14 +222,17 @@ impl<'game, 'level, Renderer: KnowledgeRenderer> TurnEnv<'game, 'level, Renderer
         let level_knowledge = knowledge.level_mut_or_insert_size(self.level_id,
                                                                  self.spatial_hash.width(),
                                                                  self.spatial_hash.height());
+        level_knowledge.set_weather(self.weather);
         let position = entity.position().expect("PC missing position");
-        let vision_distance = entity.vision_distance().expect("PC missing vision_distance");
+        let vision_distance = self.weather.lit_vision_distance(entity.vision_distance().expect("PC missing vision_distance"));
 
         let mut message_log = entity.message_log_borrow_mut().expect("PC missing message_log");
 
         let action_env = ActionEnv::new(self.ecs, *self.action_id);
+        let light_map = LightMap::with_lights(self.weather, self.ecs, self.spatial_hash, self.pc_observer, action_env);
 
-        self.pc_observer.observe(position, self.spatial_hash, vision_distance, level_knowledge, action_env);
+        self.pc_observer.observe(position, self.spatial_hash, vision_distance, self.weather.opacity(),
+                                 &mut level_knowledge.lit(&light_map, position, vision_distance), action_env);
 
         if level_knowledge.can_see(action_description.coord, action_env) {
             message_log.add(MessageType::Action(action_description.message));
@@ -244,14 +253,17 @@ impl<'game, 'level, Renderer: KnowledgeRenderer> TurnEnv<'game, 'level, Renderer
         let level_knowledge = knowledge.level_mut_or_insert_size(self.level_id,
                                                                  self.spatial_hash.width(),
                                                                  self.spatial_hash.height());
+        level_knowledge.set_weather(self.weather);
         let position = entity.position().expect("PC missing position");
-        let vision_distance = entity.vision_distance().expect("PC missing vision_distance");
+        let vision_distance = self.weather.lit_vision_distance(entity.vision_distance().expect("PC missing vision_distance"));
         let message_log = entity.message_log_borrow().expect("PC missing message_log");
 
 
         let action_env = ActionEnv::new(self.ecs, *self.action_id);
+        let light_map = LightMap::with_lights(self.weather, self.ecs, self.spatial_hash, self.pc_observer, action_env);
 
-        let changed = self.pc_observer.observe(position, self.spatial_hash, vision_distance, level_knowledge, action_env);
+        let changed = self.pc_observer.observe(position, self.spatial_hash, vision_distance, self.weather.opacity(),
+                                               &mut level_knowledge.lit(&light_map, position, vision_distance), action_env);
 
         if force == Some(ForceRender::IgnoreChange) || changed {
             let mut renderer = self.renderer.borrow_mut();
@@ -292,8 +304,12 @@ impl<'game, 'level, Renderer: KnowledgeRenderer> TurnEnv<'game, 'level, Renderer
 
             *self.action_id += 1;
 
+            if self.rule_trace {
+                debug_println!("action {} by {:?}: {:?}", *self.action_id, self.entity_id, action_event.event);
+            }
+
             // construct an action from the action args
-            action_event.event.to_action(&mut self.ecs_action, self.ecs, self.spatial_hash, self.entity_ids, self.rng.inner_mut().deref_mut());
+            action_event.event.to_action(&mut self.ecs_action, self.ecs, self.spatial_hash, self.weather, self.entity_ids, self.rng.inner_mut().deref_mut());
 
             let mut action_time = 0;
             self.rule_reactions.clear();
@@ -305,6 +321,9 @@ impl<'game, 'level, Renderer: KnowledgeRenderer> TurnEnv<'game, 'level, Renderer
                         action_description = self.ecs_action.clear_action_description();
 
                         if self.ecs_action.contains_no_commit() {
+                            if self.rule_trace {
+                                debug_println!("  accepted without committing");
+                            }
                             self.ecs_action.clear();
                             break;
                         }
@@ -330,20 +349,33 @@ impl<'game, 'level, Renderer: KnowledgeRenderer> TurnEnv<'game, 'level, Renderer
 
                         if self.ecs_action.contains_player_died() {
                             game_over_reason = Some(GameOverReason::PlayerDied);
+                        } else if self.ecs_action.contains_player_stranded() {
+                            game_over_reason = Some(GameOverReason::OutOfFuel);
+                        }
+
+                        if self.rule_trace {
+                            self.trace_commit();
                         }
 
                         self.commit();
                         break;
                     }
                     RuleResolution::Reject => {
+                        if self.rule_trace {
+                            debug_println!("  rejected");
+                        }
+
                         // Committing the action clears its data.
                         // It must be cleared explicitly if the action is rejected.
                         self.ecs_action.clear();
                         break;
                     }
                     RuleResolution::Consume(action_args) => {
+                        if self.rule_trace {
+                            debug_println!("  consumed, retrying as {:?}", action_args);
+                        }
                         // modify the current action with the new action args and retry
-                        action_args.to_action(&mut self.ecs_action, self.ecs, self.spatial_hash, self.entity_ids, self.rng.inner_mut().deref_mut());
+                        action_args.to_action(&mut self.ecs_action, self.ecs, self.spatial_hash, self.weather, self.entity_ids, self.rng.inner_mut().deref_mut());
                     }
                 }
             }
@@ -390,6 +422,7 @@ impl<'game, 'level, Renderer: KnowledgeRenderer> TurnEnv<'game, 'level, Renderer
             entity: entity,
             ecs: self.ecs,
             spatial_hash: self.spatial_hash,
+            weather: self.weather,
             level_id: self.level_id,
             action_env: ActionEnv::new(self.ecs, *self.action_id),
             renderer: self.renderer,
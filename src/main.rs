@@ -10,10 +10,14 @@ extern crate toml;
 extern crate serde_derive;
 extern crate serde;
 extern crate bincode;
+extern crate serde_json;
 
 #[cfg(feature = "sdl2")]
 extern crate sdl2;
 
+#[cfg(all(unix, feature = "rustty"))]
+extern crate rustty;
+
 use std::env;
 
 #[macro_use]
@@ -307,6 +307,27 @@ impl InnerGridSearchCtx {
 
         self.check_errors(grid, start)?;
 
+        self.search_predicate_cost(grid, start, predicate,
+                                   |info, dir| info.cell.traverse_cost().map(|cost| cost * dir.multiplier()),
+                                   config, path)
+    }
+
+    fn search_predicate_cost<T, G, F, C>(&mut self,
+                                         grid: &G,
+                                         start: Coord,
+                                         predicate: F,
+                                         cost: C,
+                                         config: &GridSearchCfg,
+                                         path: &mut GridPath) -> Result<()>
+        where G: Grid<Item = T>,
+              F: Fn(GridCellInfo<G::Item>) -> bool,
+              C: Fn(GridCellInfo<G::Item>, Direction) -> Option<f64>
+    {
+
+        if grid.get(start).is_none() {
+            return Err(Error::StartOutOfGrid);
+        }
+
         self.clear();
 
         self.queue.push(Node::new(start, 0.0, 0.0));
@@ -339,8 +360,12 @@ impl InnerGridSearchCtx {
             for dir in config.directions {
                 let nei_coord = node.coord + dir.vector();
                 if let Some(cell) = grid.get(nei_coord) {
-                    if let Some(cost) = cell.traverse_cost() {
-                        let total_cost = node.cost + cost * dir.multiplier();
+                    let info = GridCellInfo {
+                        cell: cell,
+                        coord: nei_coord,
+                    };
+                    if let Some(step_cost) = cost(info, *dir) {
+                        let total_cost = node.cost + step_cost;
                         let parent = Parent::new(node.coord, *dir);
                         if self.grid.maybe_see(nei_coord, total_cost, parent) {
                             self.queue.push(Node::new(nei_coord, total_cost, total_cost));
@@ -426,6 +451,24 @@ impl GridSearchCtx {
         self.ctx.borrow_mut().search_predicate(grid, start, predicate, config, path)
     }
 
+    /// Like `search_predicate`, but the cost of stepping into a cell in a
+    /// given direction is computed by `cost` rather than read from the cell,
+    /// and the start cell needn't be traversable. `cost` returns `None` for
+    /// steps which can't be taken.
+    pub fn search_predicate_cost<T, G, F, C>(&self,
+                                             grid: &G,
+                                             start: Coord,
+                                             predicate: F,
+                                             cost: C,
+                                             config: &GridSearchCfg,
+                                             path: &mut GridPath) -> Result<()>
+        where G: Grid<Item = T>,
+              F: Fn(GridCellInfo<G::Item>) -> bool,
+              C: Fn(GridCellInfo<G::Item>, Direction) -> Option<f64>
+    {
+        self.ctx.borrow_mut().search_predicate_cost(grid, start, predicate, cost, config, path)
+    }
+
    pub  fn search_coord<T, G>(&mut self,
                                  grid: &G,
                                  start: Coord,
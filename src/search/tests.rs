@@ -1,6 +1,7 @@
 use search::*;
 use grid::*;
 use coord::Coord;
+use direction::Direction;
 
 #[derive(Clone, Copy, Debug)]
 struct Cell {
@@ -96,3 +97,28 @@ fn astar_optimality() {
 
     assert_eq!((env.path.cost() * 100.0).floor(), 1724.0);
 }
+
+#[test]
+fn custom_cost() {
+
+    let mut env = Env::new();
+
+    let grid = grid_a();
+
+    // only allow moving right, and make every step cost the same
+    env.ctx.search_predicate_cost(&grid,
+                                  Coord::new(1, 1),
+                                  |c| c.coord == Coord::new(7, 1),
+                                  |c, dir| {
+                                      if dir == Direction::East && c.cell.is_traversable() {
+                                          Some(1.0)
+                                      } else {
+                                          None
+                                      }
+                                  },
+                                  &env.cfg,
+                                  &mut env.path).unwrap();
+
+    assert_eq!(env.path.cost(), 6.0);
+    assert_eq!(env.path.len(), 6);
+}